@@ -0,0 +1,54 @@
+//! A simulated i2c bus shared by the examples, so they can run on a host.
+
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// A simulated i2c bus with an LSM6 at each slave address.
+pub struct SimulatedBus {
+    devices: [(u8, [u8; 0x80]); 2],
+}
+
+impl SimulatedBus {
+    pub fn new() -> Self {
+        let mut bus = SimulatedBus {
+            devices: [(0x6A, [0; 0x80]), (0x6B, [0; 0x80])],
+        };
+        for (i, (_, regs)) in bus.devices.iter_mut().enumerate() {
+            regs[0x0F] = 0x69;
+            regs[0x1E] = 0b111;
+            // Give each device a distinct accelerometer reading.
+            regs[0x28] = i as u8 + 1;
+        }
+        bus
+    }
+
+    pub fn registers(&mut self, address: u8) -> Result<&mut [u8; 0x80], ()> {
+        self.devices
+            .iter_mut()
+            .find(|(a, _)| *a == address)
+            .map(|(_, regs)| regs)
+            .ok_or(())
+    }
+}
+
+impl Write for SimulatedBus {
+    type Error = ();
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+        let regs = self.registers(address)?;
+        for (i, byte) in bytes[1..].iter().enumerate() {
+            regs[bytes[0] as usize + i] = *byte;
+        }
+        Ok(())
+    }
+}
+
+impl WriteRead for SimulatedBus {
+    type Error = ();
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), ()> {
+        let regs = self.registers(address)?;
+        let start = bytes[0] as usize;
+        buffer.copy_from_slice(&regs[start..start + buffer.len()]);
+        Ok(())
+    }
+}
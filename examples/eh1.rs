@@ -0,0 +1,59 @@
+//! Shares one embedded-hal 1.0 i2c bus between two LSM6s with `embedded-hal-bus`.
+//! Run with `--features eh1`.
+
+mod common;
+
+use core::cell::RefCell;
+
+use common::SimulatedBus;
+use embedded_hal_1::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use embedded_hal_bus::i2c::RefCellDevice;
+use lsm6ds33::{interface::Eh1, AccelerometerMode, SlaveAddr, LSM6};
+
+/// The simulated bus, with the embedded-hal 1.0 `I2c` trait.
+struct SimulatedBus1(SimulatedBus);
+
+impl ErrorType for SimulatedBus1 {
+    type Error = ErrorKind;
+}
+
+impl I2c for SimulatedBus1 {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        let regs = self
+            .0
+            .registers(address)
+            .map_err(|_| ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))?;
+        let mut pointer = 0;
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    pointer = bytes[0] as usize;
+                    for byte in &bytes[1..] {
+                        regs[pointer] = *byte;
+                        pointer += 1;
+                    }
+                }
+                Operation::Read(buffer) => {
+                    buffer.copy_from_slice(&regs[pointer..pointer + buffer.len()]);
+                    pointer += buffer.len();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn main() {
+    let bus = RefCell::new(SimulatedBus1(SimulatedBus::new()));
+
+    let mut low = LSM6::new_with_address(Eh1(RefCellDevice::new(&bus)), SlaveAddr::Low).unwrap();
+    let mut high = LSM6::new_with_address(Eh1(RefCellDevice::new(&bus)), SlaveAddr::High).unwrap();
+    low.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    high.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    println!("low: {:?}", low.read_accel().unwrap());
+    println!("high: {:?}", high.read_accel().unwrap());
+}
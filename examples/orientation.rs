@@ -0,0 +1,28 @@
+//! Tracks which way up an LSM6 is through the `accelerometer` crate's traits.
+//! Run with `--features accelerometer`.
+
+mod common;
+
+use accelerometer::{Accelerometer, Tracker};
+use common::SimulatedBus;
+use lsm6ds33::{AccelerometerMode, AccelerometerScale, SlaveAddr, LSM6};
+
+fn main() {
+    let mut bus = SimulatedBus::new();
+    // Lying flat: 1 g on Z at ±4 g, which is 8197 LSB
+    let regs = bus.registers(0x6B).unwrap();
+    regs[0x28..0x2E].copy_from_slice(&[0, 0, 0, 0, 0x05, 0x20]);
+
+    let mut lsm6 = LSM6::new_with_address(bus, SlaveAddr::High).unwrap();
+    lsm6.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    lsm6.set_accel_scale(AccelerometerScale::G4).unwrap();
+
+    let mut tracker = Tracker::new(0.8);
+    let accel = lsm6.accel_norm().unwrap();
+    println!(
+        "{:?} at {} Hz: {:?}",
+        accel,
+        lsm6.sample_rate().unwrap(),
+        tracker.update(accel)
+    );
+}
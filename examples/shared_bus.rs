@@ -0,0 +1,23 @@
+//! Shares one i2c bus between two LSM6s with `shared-bus`,
+//! and lends the bus to a short-lived driver with `BusRef`.
+
+mod common;
+
+use common::SimulatedBus;
+use lsm6ds33::{AccelerometerMode, BusRef, SlaveAddr, LSM6};
+
+fn main() {
+    let bus = shared_bus::BusManagerSimple::new(SimulatedBus::new());
+
+    let mut low = LSM6::new_with_address(bus.acquire_i2c(), SlaveAddr::Low).unwrap();
+    let mut high = LSM6::new_with_address(bus.acquire_i2c(), SlaveAddr::High).unwrap();
+    low.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    high.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    println!("low: {:?}", low.read_accel().unwrap());
+    println!("high: {:?}", high.read_accel().unwrap());
+
+    // Without a bus manager, the bus can still be borrowed for as long as the driver is needed.
+    let mut i2c = SimulatedBus::new();
+    let mut imu = LSM6::new(BusRef(&mut i2c)).unwrap();
+    println!("borrowed: {:?}", imu.read_accel().unwrap());
+}
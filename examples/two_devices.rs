@@ -0,0 +1,45 @@
+//! Opens two LSM6s sharing one i2c bus, one with SA0 high and one with SA0 low,
+//! and reads from both.
+//!
+//! The bus here is simulated so the example runs on a host, but the `SharedBus`
+//! wrapper is the same shape as the proxies provided by bus-sharing crates.
+
+mod common;
+
+use common::SimulatedBus;
+use core::cell::RefCell;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use lsm6ds33::{AccelerometerMode, SlaveAddr, LSM6};
+
+/// A handle to the shared bus that can be given to each driver.
+#[derive(Clone, Copy)]
+struct SharedBus<'a>(&'a RefCell<SimulatedBus>);
+
+impl Write for SharedBus<'_> {
+    type Error = ();
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.0.borrow_mut().write(address, bytes)
+    }
+}
+
+impl WriteRead for SharedBus<'_> {
+    type Error = ();
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), ()> {
+        self.0.borrow_mut().write_read(address, bytes, buffer)
+    }
+}
+
+fn main() {
+    let bus = RefCell::new(SimulatedBus::new());
+
+    let mut low = LSM6::new_with_address(SharedBus(&bus), SlaveAddr::Low).unwrap();
+    let mut high = LSM6::new_with_address(SharedBus(&bus), SlaveAddr::High).unwrap();
+
+    for imu in [&mut low, &mut high].iter_mut() {
+        imu.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+        let accel = imu.read_accel().unwrap();
+        println!("{:#x}: {:?}", imu.address(), accel);
+    }
+}
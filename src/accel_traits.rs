@@ -0,0 +1,60 @@
+//! Implementations of the `accelerometer` crate's traits, so the LSM6 can be used
+//! with code written against them, such as `accelerometer::Tracker`.
+
+use core::fmt::Debug;
+
+use accelerometer::{
+    vector::{F32x3, I16x3},
+    Accelerometer, ErrorKind, RawAccelerometer,
+};
+
+use crate::{interface::RegisterInterface, registers, AccelerometerMode, Error, LSM6};
+
+/// Bus errors are `ErrorKind::Bus`, and everything else the driver returns is `ErrorKind::Device`.
+fn convert<E: Debug>(error: Error<E>) -> accelerometer::Error<Error<E>> {
+    let kind = match error {
+        Error::Bus(_) => ErrorKind::Bus,
+        _ => ErrorKind::Device,
+    };
+    accelerometer::Error::new_with_cause(kind, error)
+}
+
+impl<E: Debug, I: RegisterInterface<Error = E>> RawAccelerometer<I16x3> for LSM6<I> {
+    type Error = Error<E>;
+
+    /// Reads the latest acceleration data, whether or not it has been read before.
+    /// This fails with `ErrorKind::Mode` if the accelerometer is powered down.
+    fn accel_raw(&mut self) -> Result<I16x3, accelerometer::Error<Error<E>>> {
+        if self.accel_mode == AccelerometerMode::PowerDown {
+            return Err(accelerometer::Error::new(ErrorKind::Mode));
+        }
+        let (x, y, z) = self
+            .incremental_read_measurements(registers::OUTX_L_XL)
+            .map_err(convert)?;
+        Ok(I16x3::new(x, y, z))
+    }
+}
+
+impl<E: Debug, I: RegisterInterface<Error = E>> Accelerometer for LSM6<I> {
+    type Error = Error<E>;
+
+    /// Reads the latest acceleration data like `RawAccelerometer::accel_raw`, in g at the current scale.
+    fn accel_norm(&mut self) -> Result<F32x3, accelerometer::Error<Error<E>>> {
+        let raw = self.accel_raw()?;
+        let scale = self.accel_scale;
+        Ok(F32x3::new(
+            scale.to_g(raw.x),
+            scale.to_g(raw.y),
+            scale.to_g(raw.z),
+        ))
+    }
+
+    /// The nominal output data rate of the accelerometer's current mode.
+    /// This fails with `ErrorKind::Mode` if the accelerometer is powered down.
+    fn sample_rate(&mut self) -> Result<f32, accelerometer::Error<Error<E>>> {
+        match self.accel_mode {
+            AccelerometerMode::PowerDown => Err(accelerometer::Error::new(ErrorKind::Mode)),
+            mode => Ok(mode.odr_hz()),
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use embedded_hal_async::{digital::Wait, i2c::I2c};
+
+use crate::{
+    combine_le, ctrl1_xl, ctrl2_g,
+    fifo::{fifo_ctrl3, fifo_ctrl5, untagged_fifo},
+    identify, masked, registers, trace, AccelerometerMode, AccelerometerScale, AxisMapping,
+    ChipVariant, DataRate, Error, FifoMode, FifoStatus, GyroscopeMode, GyroscopeScale, Search,
+    SlaveAddr, PROBE_ORDER,
+};
+
+/// A driver for an LSM6 on an i2c bus implementing the embedded-hal-async `I2c` trait.
+///
+/// This covers construction, modes and scales, the output reads and the FIFO.
+/// The register values, the probing, the FIFO layout and the mounting remap
+/// come from the same functions `LSM6` uses, so the two drivers behave alike.
+/// Every future can be dropped before it completes without leaving the driver inconsistent:
+/// the cached modes and scales are only updated once the write that changes them has finished,
+/// so after a cancelled setter the driver still describes the device as it was before,
+/// and the setter can simply be called again.
+pub struct AsyncLSM6<I> {
+    address: u8,
+    i2c: I,
+    variant: ChipVariant,
+    accel_mode: AccelerometerMode,
+    accel_scale: AccelerometerScale,
+    gyro_mode: GyroscopeMode,
+    gyro_scale: GyroscopeScale,
+    active_low: bool,
+    mounting: AxisMapping,
+}
+
+/// The raw contents of the event source registers, as read by `AsyncLSM6::wait_read_events`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EventSources {
+    pub wake_up_src: u8,
+    pub tap_src: u8,
+    pub d6d_src: u8,
+}
+
+impl<E, I: I2c<Error = E>> AsyncLSM6<I> {
+    /// Create a new `AsyncLSM6`, probing both slave addresses like `LSM6::new`.
+    /// This sets the CTRL3_C register to 4 but doesn't turn on either sensor.
+    /// Any bus error stops the probe, including a NACK at the first address;
+    /// `AsyncLSM6::new_with_classifier` can skip NACKed addresses instead.
+    pub async fn new(i2c: I) -> Result<Self, Error<E>> {
+        Self::find(i2c, ChipVariant::from_who_am_i, |_| false).await
+    }
+
+    /// Create a new `AsyncLSM6` like `AsyncLSM6::new`, but carry on probing when an address isn't acknowledged,
+    /// like `LSM6::new_with_classifier`.
+    /// With the `eh1` feature, `interface::is_nack` does this with `Error::kind`.
+    pub async fn new_with_classifier(
+        i2c: I,
+        is_nack: impl Fn(&E) -> bool,
+    ) -> Result<Self, Error<E>> {
+        Self::find(i2c, ChipVariant::from_who_am_i, is_nack).await
+    }
+
+    /// Create a new `AsyncLSM6` like `AsyncLSM6::new`, but only accept the given variant, like `LSM6::new_variant`.
+    pub async fn new_variant(i2c: I, variant: ChipVariant) -> Result<Self, Error<E>> {
+        let identify = |id| Some(variant).filter(|variant| variant.who_am_i() == id);
+        Self::find(i2c, identify, |_| false).await
+    }
+
+    /// Create a new `AsyncLSM6` that only talks to the given slave address, like `LSM6::new_with_address`.
+    pub async fn new_with_address(mut i2c: I, address: SlaveAddr) -> Result<Self, Error<E>> {
+        let address = address.to_address();
+        let mut found = [0];
+        i2c.write_read(address, &[registers::WHO_AM_I], &mut found)
+            .await?;
+        let variant = identify(address, found[0])?;
+        Self::with_address(i2c, address, variant).await
+    }
+
+    async fn find(
+        mut i2c: I,
+        identify: impl Fn(u8) -> Option<ChipVariant>,
+        is_nack: impl Fn(&E) -> bool,
+    ) -> Result<Self, Error<E>> {
+        let mut search = Search::new();
+        for &address in &PROBE_ORDER {
+            let mut found = [0];
+            let result = i2c
+                .write_read(address, &[registers::WHO_AM_I], &mut found)
+                .await
+                .map(|()| found[0]);
+            match search.step(address, result, &identify, &is_nack) {
+                Some(Ok((address, variant))) => {
+                    return Self::with_address(i2c, address, variant).await
+                }
+                Some(Err(e)) => return Err(e),
+                None => {}
+            }
+        }
+        Err(search.end())
+    }
+
+    async fn with_address(i2c: I, address: u8, variant: ChipVariant) -> Result<Self, Error<E>> {
+        let mut this = Self {
+            address,
+            i2c,
+            variant,
+            accel_mode: AccelerometerMode::PowerDown,
+            accel_scale: AccelerometerScale::G2,
+            gyro_mode: GyroscopeMode::PowerDown,
+            gyro_scale: GyroscopeScale::Dps245,
+            active_low: false,
+            mounting: AxisMapping::IDENTITY,
+        };
+        // Set automatic register incrementing between reads
+        this.set_register_raw(registers::CTRL3_C, 4).await?;
+        Ok(this)
+    }
+
+    /// Consumes the driver and gives back the bus, leaving the device as it is.
+    pub fn release(self) -> I {
+        self.i2c
+    }
+
+    /// The part that was detected when the driver was created.
+    pub fn variant(&self) -> ChipVariant {
+        self.variant
+    }
+
+    /// See `LSM6::who_am_i`.
+    pub async fn who_am_i(&mut self) -> Result<u8, Error<E>> {
+        self.read_register_raw(registers::WHO_AM_I).await
+    }
+
+    /// Turns on both sensors in high performance mode, in a single write.
+    pub async fn init_default(&mut self) -> Result<(), Error<E>> {
+        let (accel, gyro) = (
+            AccelerometerMode::HighPerformance1660Hz,
+            GyroscopeMode::HighPerformance1660Hz,
+        );
+        self.write_registers(&[
+            registers::CTRL1_XL,
+            ctrl1_xl(accel, self.accel_scale),
+            ctrl2_g(gyro, self.gyro_scale),
+        ])
+        .await?;
+        self.accel_mode = accel;
+        self.gyro_mode = gyro;
+        Ok(())
+    }
+
+    /// Powers down both sensors, in a single write.
+    pub async fn full_power_down(&mut self) -> Result<(), Error<E>> {
+        self.write_registers(&[
+            registers::CTRL1_XL,
+            ctrl1_xl(AccelerometerMode::PowerDown, self.accel_scale),
+            ctrl2_g(GyroscopeMode::PowerDown, self.gyro_scale),
+        ])
+        .await?;
+        self.accel_mode = AccelerometerMode::PowerDown;
+        self.gyro_mode = GyroscopeMode::PowerDown;
+        Ok(())
+    }
+
+    /// This overwrites the CTRL1_XL register, keeping the current scale.
+    pub async fn set_accel_mode(&mut self, mode: AccelerometerMode) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL1_XL, ctrl1_xl(mode, self.accel_scale))
+            .await?;
+        self.accel_mode = mode;
+        Ok(())
+    }
+
+    /// This overwrites the CTRL2_G register, keeping the current scale.
+    pub async fn set_gyro_mode(&mut self, mode: GyroscopeMode) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL2_G, ctrl2_g(mode, self.gyro_scale))
+            .await?;
+        self.gyro_mode = mode;
+        Ok(())
+    }
+
+    /// This overwrites the CTRL1_XL register, keeping the current mode.
+    pub async fn set_accel_scale(&mut self, scale: AccelerometerScale) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL1_XL, ctrl1_xl(self.accel_mode, scale))
+            .await?;
+        self.accel_scale = scale;
+        Ok(())
+    }
+
+    /// This overwrites the CTRL2_G register, keeping the current mode.
+    pub async fn set_gyro_scale(&mut self, scale: GyroscopeScale) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL2_G, ctrl2_g(self.gyro_mode, scale))
+            .await?;
+        self.gyro_scale = scale;
+        Ok(())
+    }
+
+    /// The scale the accelerometer was last set to.
+    pub fn accel_scale(&self) -> AccelerometerScale {
+        self.accel_scale
+    }
+
+    /// The scale the gyroscope was last set to.
+    pub fn gyro_scale(&self) -> GyroscopeScale {
+        self.gyro_scale
+    }
+
+    /// The mounting orientation set with `AsyncLSM6::set_mounting_orientation`.
+    pub fn mounting_orientation(&self) -> AxisMapping {
+        self.mounting
+    }
+
+    /// Sets how the sensor is mounted, like `LSM6::set_mounting_orientation`.
+    /// Every reading, including FIFO samples, is remapped with it.
+    pub fn set_mounting_orientation(&mut self, mapping: AxisMapping) {
+        self.mounting = mapping;
+    }
+
+    /// Sets one of the LSM6's registers without checking that it is writable.
+    pub async fn set_register_raw(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_registers(&[reg, value]).await
+    }
+
+    /// Reads one of the LSM6's registers without checking that it exists.
+    pub async fn read_register_raw(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        let mut value = [0];
+        self.read_registers(reg, &mut value).await?;
+        Ok(value[0])
+    }
+
+    /// See `LSM6::read_accel`.
+    pub async fn read_accel(&mut self) -> Result<Option<(i16, i16, i16)>, Error<E>> {
+        if self.read_register_raw(registers::STATUS_REG).await? & 1 == 0 {
+            return Ok(None);
+        }
+        self.read_xyz(registers::OUTX_L_XL).await.map(Some)
+    }
+
+    /// See `LSM6::read_gyro`.
+    pub async fn read_gyro(&mut self) -> Result<Option<(i16, i16, i16)>, Error<E>> {
+        if self.read_register_raw(registers::STATUS_REG).await? & 0b10 == 0 {
+            return Ok(None);
+        }
+        self.read_xyz(registers::OUTX_L_G).await.map(Some)
+    }
+
+    /// See `LSM6::read_accel_g`.
+    pub async fn read_accel_g(&mut self) -> Result<Option<(f32, f32, f32)>, Error<E>> {
+        let scale = self.accel_scale;
+        Ok(self
+            .read_accel()
+            .await?
+            .map(|(x, y, z)| (scale.to_g(x), scale.to_g(y), scale.to_g(z))))
+    }
+
+    /// See `LSM6::read_gyro_dps`.
+    pub async fn read_gyro_dps(&mut self) -> Result<Option<(f32, f32, f32)>, Error<E>> {
+        let scale = self.gyro_scale;
+        Ok(self
+            .read_gyro()
+            .await?
+            .map(|(x, y, z)| (scale.to_dps(x), scale.to_dps(y), scale.to_dps(z))))
+    }
+
+    /// See `LSM6::read_temperature`.
+    pub async fn read_temperature(&mut self) -> Result<Option<i16>, Error<E>> {
+        if self.read_register_raw(registers::STATUS_REG).await? & 0b100 == 0 {
+            return Ok(None);
+        }
+        let mut values = [0; 2];
+        self.read_registers(registers::OUT_TEMP_L, &mut values)
+            .await?;
+        Ok(Some(combine_le(values[0], values[1])))
+    }
+
+    /// See `LSM6::read_temperature_celsius`.
+    pub async fn read_temperature_celsius(&mut self) -> Result<Option<f32>, Error<E>> {
+        let variant = self.variant;
+        Ok(self
+            .read_temperature()
+            .await?
+            .map(|raw| variant.to_celsius(raw)))
+    }
+
+    /// See `LSM6::set_fifo_mode`.
+    pub async fn set_fifo_mode(&mut self, mode: FifoMode, rate: DataRate) -> Result<(), Error<E>> {
+        untagged_fifo(self.variant)?;
+        self.set_register_raw(registers::FIFO_CTRL5, fifo_ctrl5(mode, rate))
+            .await
+    }
+
+    /// See `LSM6::set_fifo_batching`.
+    pub async fn set_fifo_batching(&mut self, accel: bool, gyro: bool) -> Result<(), Error<E>> {
+        untagged_fifo(self.variant)?;
+        self.set_register_raw(registers::FIFO_CTRL3, fifo_ctrl3(accel, gyro))
+            .await
+    }
+
+    /// See `LSM6::read_fifo_status`. The FIFO pattern position isn't followed by this driver,
+    /// but is still decoded into `FifoStatus::pattern`.
+    pub async fn read_fifo_status(&mut self) -> Result<FifoStatus, Error<E>> {
+        let mut bytes = [0; 4];
+        let len = FifoStatus::len(self.variant);
+        self.read_registers(registers::FIFO_STATUS1, &mut bytes[..len])
+            .await?;
+        Ok(FifoStatus::decode(self.variant, &bytes[..len]))
+    }
+
+    /// See `LSM6::fifo_unread_words`.
+    pub async fn fifo_unread_words(&mut self) -> Result<u16, Error<E>> {
+        Ok(self.read_fifo_status().await?.unread_words)
+    }
+
+    /// See `LSM6::read_fifo`.
+    /// If this is cancelled, the samples read so far are lost from the FIFO,
+    /// but the rest can still be read afterwards.
+    pub async fn read_fifo(&mut self, samples: &mut [(i16, i16, i16)]) -> Result<usize, Error<E>> {
+        untagged_fifo(self.variant)?;
+        let available = (self.fifo_unread_words().await? / 3) as usize;
+        let count = available.min(samples.len());
+        for sample in &mut samples[..count] {
+            *sample = self.read_xyz(registers::FIFO_DATA_OUT_L).await?;
+        }
+        Ok(count)
+    }
+
+    /// See `LSM6::drain_fifo`.
+    #[cfg(feature = "heapless")]
+    pub async fn drain_fifo<const N: usize>(
+        &mut self,
+    ) -> Result<heapless::Vec<(i16, i16, i16), N>, Error<E>> {
+        untagged_fifo(self.variant)?;
+        let mut samples = heapless::Vec::new();
+        let available = (self.fifo_unread_words().await? / 3) as usize;
+        for _ in 0..available.min(N) {
+            let sample = self.read_xyz(registers::FIFO_DATA_OUT_L).await?;
+            // The loop is bounded by the capacity, so this can't fail.
+            let _ = samples.push(sample);
+        }
+        Ok(samples)
+    }
+
+    /// Sets whether the interrupt pins are active low, using the H_LACTIVE bit of CTRL3_C.
+    /// They are active high by default. The `wait_*` methods wait for the pin's active level.
+    pub async fn set_interrupt_active_low(&mut self, active_low: bool) -> Result<(), Error<E>> {
+        let prev = self.read_register_raw(registers::CTRL3_C).await?;
+        let value = masked(prev, 0b100000, if active_low { 0b100000 } else { 0 });
+        self.set_register_raw(registers::CTRL3_C, value).await?;
+        self.active_low = active_low;
+        Ok(())
+    }
+
+    /// Waits for `pin` to reach its active level, then reads the acceleration data
+    /// without checking STATUS_REG first.
+    /// `pin` must be connected to the interrupt pin that the accelerometer data-ready signal is routed to.
+    /// Since the data-ready signal stays active until the data is read, a sample that became ready
+    /// before this was called is read straight away rather than missed.
+    pub async fn wait_read_accel<P: Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<(i16, i16, i16), Error<E>> {
+        self.wait_active(pin).await?;
+        self.read_xyz(registers::OUTX_L_XL).await
+    }
+
+    /// Like `AsyncLSM6::wait_read_accel`, but for the gyroscope.
+    pub async fn wait_read_gyro<P: Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<(i16, i16, i16), Error<E>> {
+        self.wait_active(pin).await?;
+        self.read_xyz(registers::OUTX_L_G).await
+    }
+
+    /// Waits for `pin` to reach its active level, for a pin that carries events as well as
+    /// the accelerometer data-ready signal. Reads STATUS_REG to see whether acceleration data is ready,
+    /// reading it if so, and the event source registers in one burst, which releases latched events.
+    pub async fn wait_read_events<P: Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<(Option<(i16, i16, i16)>, EventSources), Error<E>> {
+        self.wait_active(pin).await?;
+        let accel = self.read_accel().await?;
+        let mut sources = [0; 3];
+        self.read_registers(registers::WAKE_UP_SRC, &mut sources)
+            .await?;
+        Ok((
+            accel,
+            EventSources {
+                wake_up_src: sources[0],
+                tap_src: sources[1],
+                d6d_src: sources[2],
+            },
+        ))
+    }
+
+    async fn wait_active<P: Wait>(&mut self, pin: &mut P) -> Result<(), Error<E>> {
+        if self.active_low {
+            pin.wait_for_low().await
+        } else {
+            pin.wait_for_high().await
+        }
+        .map_err(|_| Error::Pin)
+    }
+
+    async fn read_xyz(&mut self, start: u8) -> Result<(i16, i16, i16), Error<E>> {
+        let mut values = [0; 6];
+        self.read_registers(start, &mut values).await?;
+        Ok(self.mounting.decode(&values))
+    }
+
+    /// Writes `bytes[1..]` starting at register `bytes[0]`, traced like `LSM6`'s writes.
+    async fn write_registers(&mut self, bytes: &[u8]) -> Result<(), Error<E>> {
+        let result = self.i2c.write(self.address, bytes).await;
+        trace::write(self.address, false, bytes, &result);
+        Ok(result?)
+    }
+
+    /// Reads into `buffer` starting at register `start`, traced like `LSM6`'s reads.
+    async fn read_registers(&mut self, start: u8, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        let result = self.i2c.write_read(self.address, &[start], buffer).await;
+        trace::read(self.address, false, start, buffer, &result);
+        Ok(result?)
+    }
+}
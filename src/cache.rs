@@ -0,0 +1,211 @@
+use crate::{
+    ctrl1_xl, ctrl2_g, interface::RegisterInterface, registers, Error, LSM6, SELF_CLEARING_BITS,
+};
+
+/// How many registers the shadow cache holds:
+/// INT1_CTRL through CTRL10_C, and TAP_CFG through MD2_CFG.
+pub(crate) const CACHE_SIZE: usize = 21;
+
+/// Where `reg` is kept in the shadow cache, if it is cached.
+fn cache_index(reg: u8) -> Option<usize> {
+    match reg {
+        registers::WHO_AM_I => None,
+        registers::INT1_CTRL..=registers::CTRL10_C => Some((reg - registers::INT1_CTRL) as usize),
+        registers::TAP_CFG..=registers::MD2_CFG => Some((reg - registers::TAP_CFG) as usize + 13),
+        _ => None,
+    }
+}
+
+impl<I> LSM6<I> {
+    /// The value of `reg` in the shadow cache, if it is cached and known.
+    pub(crate) fn cached(&self, reg: u8) -> Option<u8> {
+        let index = cache_index(reg)?;
+        if self.caching && self.cache_valid & 1 << index != 0 {
+            Some(self.cache[index])
+        } else {
+            None
+        }
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Sets whether the driver keeps a shadow copy of the control and interrupt configuration registers.
+    /// With caching on, which is the default, setters that change part of a register
+    /// use the copy instead of reading the register first, saving a transaction.
+    /// The copy is filled in as registers are read or written, or all at once by `LSM6::sync_cache`.
+    /// Turn caching off if anything else (e.g. another bus master) writes to the LSM6.
+    pub fn set_caching(&mut self, enabled: bool) {
+        self.caching = enabled;
+        self.cache_valid = 0;
+    }
+
+    /// Reads every cached register from the device into the shadow cache,
+    /// so later setters don't need to read them.
+    /// This does nothing if caching is off.
+    pub fn sync_cache(&mut self) -> Result<(), Error<E>> {
+        if !self.caching {
+            return Ok(());
+        }
+        let mut ctrl = [0; 13];
+        self.read_registers(registers::INT1_CTRL, &mut ctrl)?;
+        let mut tap = [0; 8];
+        self.read_registers(registers::TAP_CFG, &mut tap)?;
+        for (reg, &value) in (registers::INT1_CTRL..)
+            .zip(&ctrl)
+            .chain((registers::TAP_CFG..).zip(&tap))
+        {
+            self.update_cache(reg, value);
+        }
+        Ok(())
+    }
+
+    /// Forgets every cached register, so each is read from the device the next time it's needed.
+    pub fn invalidate_cache(&mut self) {
+        self.cache_valid = 0;
+    }
+
+    /// Checks that the device still has the configuration the driver expects,
+    /// which it loses if it resets (e.g. after a brown-out).
+    /// This reads INT1_CTRL through CTRL10_C in one burst and compares CTRL1_XL and CTRL2_G
+    /// against the driver's modes and scales, and the other registers against the shadow cache
+    /// where they are cached.
+    pub fn check_configured(&mut self) -> Result<bool, Error<E>> {
+        let mut regs = [0; 13];
+        self.read_registers(registers::INT1_CTRL, &mut regs)?;
+        let expected_modes = [
+            ctrl1_xl(self.accel_mode, self.accel_scale),
+            ctrl2_g(self.gyro_mode, self.gyro_scale),
+        ];
+        if regs[3..5] != expected_modes {
+            return Ok(false);
+        }
+        if !self.caching {
+            return Ok(true);
+        }
+        Ok((registers::INT1_CTRL..).zip(&regs).all(|(reg, &value)| {
+            let self_clearing = SELF_CLEARING_BITS
+                .iter()
+                .find(|(r, _)| *r == reg)
+                .map_or(0, |(_, bits)| *bits);
+            match cache_index(reg) {
+                Some(index) if self.cache_valid & 1 << index != 0 => {
+                    self.cache[index] == value & !self_clearing
+                }
+                _ => true,
+            }
+        }))
+    }
+
+    /// Writes the driver's configuration back to the device, e.g. after `LSM6::check_configured`
+    /// finds that it reset. The other control and embedded function registers are written
+    /// from the shadow cache where they are cached, then the modes and scales,
+    /// then the interrupt routing. The FIFO configuration is not restored.
+    pub fn reapply(&mut self) -> Result<(), Error<E>> {
+        self.write_cached(registers::CTRL3_C, registers::CTRL10_C)?;
+        self.write_cached(registers::TAP_CFG, registers::FREE_FALL)?;
+        self.write_registers(&[
+            registers::CTRL1_XL,
+            ctrl1_xl(self.accel_mode, self.accel_scale),
+            ctrl2_g(self.gyro_mode, self.gyro_scale),
+        ])?;
+        self.write_cached(registers::INT1_CTRL, registers::INT2_CTRL)?;
+        self.write_cached(registers::MD1_CFG, registers::MD2_CFG)
+    }
+
+    /// Sets how many calls to `LSM6::read_accel` and `LSM6::read_gyro` there are between
+    /// automatic calls to `LSM6::check_configured`, with `LSM6::reapply` following a mismatch.
+    /// This trades one extra transaction every `reads` reads for recovering from resets on its own.
+    /// Zero, the default, turns the automatic check off.
+    pub fn set_auto_check(&mut self, reads: u16) {
+        self.auto_check_every = reads;
+        self.reads_since_check = 0;
+    }
+
+    /// The number of times the automatic check has found and repaired a reset.
+    pub fn resets_detected(&self) -> u32 {
+        self.resets_detected
+    }
+
+    /// Counts a read towards the automatic check, running it when it is due.
+    pub(crate) fn auto_check(&mut self) -> Result<(), Error<E>> {
+        if self.auto_check_every == 0 {
+            return Ok(());
+        }
+        self.reads_since_check += 1;
+        if self.reads_since_check < self.auto_check_every {
+            return Ok(());
+        }
+        self.reads_since_check = 0;
+        if !self.check_configured()? {
+            self.resets_detected = self.resets_detected.saturating_add(1);
+            self.reapply()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the cached registers from `first` through `last`, in bursts of consecutive cached ones.
+    fn write_cached(&mut self, first: u8, last: u8) -> Result<(), Error<E>> {
+        let mut bytes = [0; 1 + CACHE_SIZE];
+        let mut len = 0;
+        for reg in first..=last + 1 {
+            let cached = match cache_index(reg) {
+                Some(index)
+                    if reg <= last && self.caching && self.cache_valid & 1 << index != 0 =>
+                {
+                    Some(self.cache[index])
+                }
+                _ => None,
+            };
+            match cached {
+                Some(value) => {
+                    if len == 0 {
+                        bytes[0] = reg;
+                        len = 1;
+                    }
+                    bytes[len] = value;
+                    len += 1;
+                }
+                None if len > 0 => {
+                    self.write_registers(&bytes[..len])?;
+                    len = 0;
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets the cached value of a single register, if it is cached.
+    pub(crate) fn forget_cached(&mut self, reg: u8) {
+        if let Some(index) = cache_index(reg) {
+            self.cache_valid &= !(1 << index);
+        }
+    }
+
+    /// Reads a register for a read-modify-write, using the shadow cache when possible.
+    pub(crate) fn read_cached(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        if let Some(index) = cache_index(reg) {
+            if self.caching && !self.embedded_bank && self.cache_valid & 1 << index != 0 {
+                return Ok(self.cache[index]);
+            }
+        }
+        let value = self.read_register_raw(reg)?;
+        self.update_cache(reg, value);
+        Ok(value)
+    }
+
+    /// Records the value of a register in the shadow cache, if it is cached.
+    pub(crate) fn update_cache(&mut self, reg: u8, value: u8) {
+        if !self.caching || self.embedded_bank {
+            return;
+        }
+        if let Some(index) = cache_index(reg) {
+            let self_clearing = SELF_CLEARING_BITS
+                .iter()
+                .find(|(r, _)| *r == reg)
+                .map_or(0, |(_, bits)| *bits);
+            self.cache[index] = value & !self_clearing;
+            self.cache_valid |= 1 << index;
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use embedded_hal::blocking::delay::DelayMs;
+
+use crate::{interface::RegisterInterface, registers, selftest::wait_ready, Error, LSM6};
+
+/// The error returned by `LSM6::calibrate_gyro` and `LSM6::calibrate_accel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CalibrationError<E> {
+    /// A sample exceeded the motion threshold, so the device wasn't still and the calibration was abandoned.
+    Moved,
+    /// The samples couldn't be read.
+    Driver(Error<E>),
+}
+
+impl<E> From<Error<E>> for CalibrationError<E> {
+    fn from(error: Error<E>) -> Self {
+        CalibrationError::Driver(error)
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Measures the gyroscope's zero-rate offset by averaging `samples` readings at its current mode and scale,
+    /// which must not be powered down. At least one sample is taken.
+    /// The device has to be still: if the rate on any axis of a sample is over `motion_threshold_mdps`
+    /// (in milli-degrees per second), this stops and returns `CalibrationError::Moved`.
+    /// The offset is returned in raw units, to be subtracted from later readings.
+    pub fn calibrate_gyro<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        samples: u16,
+        motion_threshold_mdps: u32,
+    ) -> Result<(i16, i16, i16), CalibrationError<E>> {
+        // The sensitivity is in eighths of a milli-degree per second per LSB
+        let threshold =
+            motion_threshold_mdps as i64 * 8 / self.gyro_scale.eighth_mdps_per_lsb() as i64;
+        let (x, y, z) =
+            self.average_still(delay, samples, registers::OUTX_L_G, 0b10, |sample, _| {
+                sample.iter().any(|v| v.abs() > threshold)
+            })?;
+        Ok((x, y, z))
+    }
+
+    /// Measures the accelerometer's offset by averaging `samples` readings at its current mode and scale,
+    /// which must not be powered down. At least one sample is taken.
+    /// The device has to be still and level with the Z axis pointing up, so that it measures +1 g on Z only.
+    /// A Z offset that doesn't fit in an `i16` is saturated.
+    /// Like the offset, the axes are those of the mounting orientation (see `LSM6::set_mounting_orientation`).
+    /// If any axis of a sample differs from the first sample by more than `motion_threshold_mg`,
+    /// this stops and returns `CalibrationError::Moved`.
+    /// The offset is returned in raw units, to be subtracted from later readings.
+    pub fn calibrate_accel<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        samples: u16,
+        motion_threshold_mg: u32,
+    ) -> Result<(i16, i16, i16), CalibrationError<E>> {
+        let ug = self.accel_scale.micro_g_per_lsb() as i64;
+        let threshold = motion_threshold_mg as i64 * 1000 / ug;
+        let (x, y, z) = self.average_still(
+            delay,
+            samples,
+            registers::OUTX_L_XL,
+            0b1,
+            |sample, first| {
+                sample
+                    .iter()
+                    .zip(first.iter())
+                    .any(|(v, f)| (v - f).abs() > threshold)
+            },
+        )?;
+        let one_g = (1_000_000 + ug / 2) / ug;
+        // Upside down, the offset is about -2 g, which doesn't fit at the smallest scale
+        let z = (z as i64 - one_g).clamp(i16::MIN as i64, i16::MAX as i64);
+        Ok((x, y, z as i16))
+    }
+
+    /// Averages `samples` readings from `output`, stopping with `CalibrationError::Moved`
+    /// as soon as `moved` returns true for a sample and the first sample.
+    fn average_still<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        samples: u16,
+        output: u8,
+        ready: u8,
+        moved: impl Fn(&[i64; 3], &[i64; 3]) -> bool,
+    ) -> Result<(i16, i16, i16), CalibrationError<E>> {
+        let samples = samples.max(1);
+        let mut first = [0; 3];
+        let mut sum = [0; 3];
+        for i in 0..samples {
+            wait_ready(self, delay, ready)?;
+            let (x, y, z) = self.incremental_read_measurements(output)?;
+            let sample = [x as i64, y as i64, z as i64];
+            if i == 0 {
+                first = sample;
+            }
+            if moved(&sample, &first) {
+                return Err(CalibrationError::Moved);
+            }
+            for (total, v) in sum.iter_mut().zip(sample.iter()) {
+                *total += v;
+            }
+        }
+        let n = samples as i64;
+        Ok((
+            (sum[0] / n) as i16,
+            (sum[1] / n) as i16,
+            (sum[2] / n) as i16,
+        ))
+    }
+}
@@ -0,0 +1,547 @@
+use crate::{
+    axes_bits, ctrl1_xl, ctrl2_g, decode_axes, fifo::fifo_set_words, interface::RegisterInterface,
+    registers, AccelerometerMode, AccelerometerScale, ChipVariant, DataRate, Error, FifoMode,
+    GyroscopeMode, GyroscopeScale, LSM6, SELF_CLEARING_BITS,
+};
+
+/// A snapshot of the LSM6's configuration registers, as raw register contents.
+/// Use `LSM6::read_config` to take one and `LSM6::apply_config` to put it back.
+/// The fields are named after the LSM6DS33's registers. On the LSM6DSO and LSM6DSOX,
+/// `fifo_ctrl1` through `fifo_ctrl4` hold their own FIFO_CTRL1 through FIFO_CTRL4,
+/// `tap_cfg` holds TAP_CFG2, and `fifo_ctrl5` and `orient_cfg_g` are left at 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Config {
+    pub fifo_ctrl1: u8,
+    pub fifo_ctrl2: u8,
+    pub fifo_ctrl3: u8,
+    pub fifo_ctrl4: u8,
+    pub fifo_ctrl5: u8,
+    pub orient_cfg_g: u8,
+    pub int1_ctrl: u8,
+    pub int2_ctrl: u8,
+    pub ctrl1_xl: u8,
+    pub ctrl2_g: u8,
+    pub ctrl3_c: u8,
+    pub ctrl4_c: u8,
+    pub ctrl5_c: u8,
+    pub ctrl6_c: u8,
+    pub ctrl7_g: u8,
+    pub ctrl8_xl: u8,
+    pub ctrl9_xl: u8,
+    pub ctrl10_c: u8,
+    pub tap_cfg: u8,
+    pub tap_ths_6d: u8,
+    pub int_dur2: u8,
+    pub wake_up_ths: u8,
+    pub wake_up_dur: u8,
+    pub free_fall: u8,
+    pub md1_cfg: u8,
+    pub md2_cfg: u8,
+    /// COUNTER_BDR_REG1, on the LSM6DSO and LSM6DSOX only.
+    pub counter_bdr_reg1: u8,
+    /// TAP_CFG0, on the LSM6DSO and LSM6DSOX only.
+    pub tap_cfg0: u8,
+    /// TAP_CFG1, on the LSM6DSO and LSM6DSOX only.
+    pub tap_cfg1: u8,
+}
+
+/// The LSM6's interrupt pins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InterruptPin {
+    Int1,
+    Int2,
+}
+
+impl InterruptPin {
+    /// The register that routes interrupts to this pin.
+    pub(crate) fn ctrl_register(self) -> u8 {
+        match self {
+            InterruptPin::Int1 => registers::INT1_CTRL,
+            InterruptPin::Int2 => registers::INT2_CTRL,
+        }
+    }
+}
+
+/// The routing and electrical setup of both interrupt pins, applied by `LSM6::configure_interrupts`.
+/// The default routes nothing, with push-pull, active high pins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct InterruptConfig {
+    /// The contents of INT1_CTRL, which signals are routed to INT1.
+    pub int1_ctrl: u8,
+    /// The contents of INT2_CTRL, which signals are routed to INT2.
+    pub int2_ctrl: u8,
+    /// The pins are active low, from the H_LACTIVE bit of CTRL3_C.
+    pub active_low: bool,
+    /// The pins are open drain rather than push-pull, from the PP_OD bit of CTRL3_C.
+    pub open_drain: bool,
+}
+
+/// The groups of registers that `ConfigBuilder::apply` writes, in the order it writes them.
+/// CTRL1_XL through CTRL3_C are written in one burst, so the BDU and BLE bits are written along with the modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigStep {
+    /// The FIFO mode and rate in FIFO_CTRL5.
+    Fifo,
+    /// The axis enables in CTRL9_XL and CTRL10_C.
+    Axes,
+    /// The BDU and BLE bits of CTRL3_C, when no mode is set.
+    Control,
+    /// The modes and scales in CTRL1_XL and CTRL2_G, and the BDU and BLE bits of CTRL3_C if they are set.
+    Modes,
+    /// The data-ready routing in INT1_CTRL or INT2_CTRL.
+    Interrupts,
+}
+
+/// The error returned by `ConfigBuilder::apply`: the step that failed and why.
+/// The steps before it were written, and the ones after it were not.
+/// If the configuration was invalid, `error` is `Error::InvalidConfig`, or `Error::Unsupported`
+/// for a setting the variant doesn't have, and nothing was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigError<E> {
+    pub step: ConfigStep,
+    pub error: Error<E>,
+}
+
+/// Collects settings to apply to the LSM6 together, checking that they make sense first.
+/// Anything that isn't set is left as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ConfigBuilder {
+    accel: Option<(AccelerometerMode, AccelerometerScale)>,
+    gyro: Option<(GyroscopeMode, GyroscopeScale)>,
+    bdu: Option<bool>,
+    big_endian: Option<bool>,
+    accel_axes: Option<(bool, bool, bool)>,
+    gyro_axes: Option<(bool, bool, bool)>,
+    fifo: Option<(FifoMode, DataRate)>,
+    drdy: Option<InterruptPin>,
+}
+
+impl ConfigBuilder {
+    /// Starts with nothing set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes the settings a builder can express from a `Config`, such as one from `LSM6::read_config`:
+    /// the sensors' modes and scales, BDU, the endianness, the axis enables and the FIFO mode and rate.
+    /// A field with a reserved bit pattern is left unset, so applying the builder keeps it as it is,
+    /// and so is the FIFO while it has no rate.
+    /// Settings the builder doesn't cover, such as the filters and interrupts, stay in the `Config`.
+    /// `variant` is the part the `Config` was read from. The axis enables are left unset on the variants
+    /// other than the LSM6DS33, which use those bits for other things, and the FIFO is left unset
+    /// for the LSM6DSO, whose FIFO registers are laid out differently.
+    pub fn from_config(variant: ChipVariant, config: &Config) -> Self {
+        let accel = AccelerometerMode::from_bitcode(config.ctrl1_xl >> 4)
+            .map(|mode| (mode, AccelerometerScale::from_bitcode(config.ctrl1_xl >> 2)));
+        let gyro = GyroscopeMode::from_bitcode(config.ctrl2_g >> 4)
+            .map(|mode| (mode, GyroscopeScale::from_bitcode(config.ctrl2_g >> 1)));
+        let fifo = if variant.has_dso_registers() {
+            None
+        } else {
+            FifoMode::from_bitcode(config.fifo_ctrl5 & 0b111)
+                .zip(DataRate::from_bitcode(config.fifo_ctrl5 >> 3 & 0b1111))
+        };
+        let axes = variant == ChipVariant::Lsm6ds33;
+        Self {
+            accel,
+            gyro,
+            bdu: Some(config.ctrl3_c & 0b1000000 != 0),
+            big_endian: Some(config.ctrl3_c & 0b10 != 0),
+            accel_axes: Some(decode_axes(config.ctrl9_xl)).filter(|_| axes),
+            gyro_axes: Some(decode_axes(config.ctrl10_c)).filter(|_| axes),
+            fifo,
+            drdy: None,
+        }
+    }
+
+    /// Sets the accelerometer's mode and scale.
+    pub fn accel(mut self, mode: AccelerometerMode, scale: AccelerometerScale) -> Self {
+        self.accel = Some((mode, scale));
+        self
+    }
+
+    /// Sets the gyroscope's mode and scale.
+    pub fn gyro(mut self, mode: GyroscopeMode, scale: GyroscopeScale) -> Self {
+        self.gyro = Some((mode, scale));
+        self
+    }
+
+    /// Sets whether the output registers are only updated once both bytes of a value have been read.
+    pub fn bdu(mut self, enabled: bool) -> Self {
+        self.bdu = Some(enabled);
+        self
+    }
+
+    /// Sets whether the outputs are big endian, with the BLE bit of CTRL3_C.
+    /// The driver's reads assume little endian outputs, so with this on,
+    /// raw reads of the output registers have to be decoded with `combine_be` instead.
+    pub fn big_endian(mut self, enabled: bool) -> Self {
+        self.big_endian = Some(enabled);
+        self
+    }
+
+    /// Sets which axes of the accelerometer are enabled, like `LSM6::set_accel_axes`.
+    /// Only the LSM6DS33 has these bits.
+    pub fn accel_axes(mut self, x: bool, y: bool, z: bool) -> Self {
+        self.accel_axes = Some((x, y, z));
+        self
+    }
+
+    /// Sets which axes of the gyroscope are enabled, like `LSM6::set_gyro_axes`.
+    /// Only the LSM6DS33 has these bits.
+    pub fn gyro_axes(mut self, x: bool, y: bool, z: bool) -> Self {
+        self.gyro_axes = Some((x, y, z));
+        self
+    }
+
+    /// Sets the mode of the FIFO and the rate at which samples are stored in it.
+    /// The rate can't be faster than the faster of the two sensors.
+    pub fn fifo(mut self, mode: FifoMode, rate: DataRate) -> Self {
+        self.fifo = Some((mode, rate));
+        self
+    }
+
+    /// Routes the data-ready signals of the sensors that are on to `pin`.
+    /// At least one sensor has to be on.
+    pub fn drdy_on(mut self, pin: InterruptPin) -> Self {
+        self.drdy = Some(pin);
+        self
+    }
+
+    /// Checks the settings against each other and the driver's current modes,
+    /// then writes them in the order of `ConfigStep`, with the modes and BDU in a single burst.
+    /// A low-power mode is rejected while that sensor's high performance mode is on,
+    /// as it is after a reset; `LSM6::setup_low_power` shows how to turn it off first.
+    pub fn apply<E, I: RegisterInterface<Error = E>>(
+        &self,
+        imu: &mut LSM6<I>,
+    ) -> Result<(), ConfigError<E>> {
+        let (accel_mode, accel_scale) = self.accel.unwrap_or((imu.accel_mode, imu.accel_scale));
+        let (gyro_mode, gyro_scale) = self.gyro.unwrap_or((imu.gyro_mode, imu.gyro_scale));
+        // The mode bitcodes are the output data rate bitcodes
+        let fastest = accel_mode.to_bitcode().max(gyro_mode.to_bitcode());
+        let invalid = |step| ConfigError {
+            step,
+            error: Error::InvalidConfig,
+        };
+        if let Some((mode, rate)) = self.fifo {
+            if mode != FifoMode::Bypass && rate.to_bitcode() > fastest {
+                return Err(invalid(ConfigStep::Fifo));
+            }
+        }
+        if self.drdy.is_some() && fastest == 0 {
+            return Err(invalid(ConfigStep::Interrupts));
+        }
+        if (self.accel_axes.is_some() || self.gyro_axes.is_some())
+            && imu.variant != ChipVariant::Lsm6ds33
+        {
+            return Err(ConfigError {
+                step: ConfigStep::Axes,
+                error: Error::Unsupported,
+            });
+        }
+
+        let step = |step| move |error| ConfigError { step, error };
+        // The low-power modes need high performance turned off, with XL_HM_MODE in CTRL6_C
+        // for the accelerometer and G_HM_MODE in CTRL7_G for the gyroscope
+        let low_power = |bitcode| (1..=0b11).contains(&bitcode);
+        if self.accel.is_some() && low_power(accel_mode.to_bitcode()) {
+            let ctrl6_c = imu
+                .read_cached(registers::CTRL6_C)
+                .map_err(step(ConfigStep::Modes))?;
+            if ctrl6_c & 0b10000 == 0 {
+                return Err(invalid(ConfigStep::Modes));
+            }
+        }
+        if self.gyro.is_some() && low_power(gyro_mode.to_bitcode()) {
+            let ctrl7_g = imu
+                .read_cached(registers::CTRL7_G)
+                .map_err(step(ConfigStep::Modes))?;
+            if ctrl7_g & 0b10000000 == 0 {
+                return Err(invalid(ConfigStep::Modes));
+            }
+        }
+
+        if let Some((mode, rate)) = self.fifo {
+            imu.set_fifo_mode(mode, rate)
+                .map_err(step(ConfigStep::Fifo))?;
+        }
+        // CTRL9_XL and CTRL10_C are consecutive too
+        let axes = |axes: Option<(bool, bool, bool)>| axes.map(|(x, y, z)| axes_bits(x, y, z));
+        match (axes(self.accel_axes), axes(self.gyro_axes)) {
+            (Some(accel), Some(gyro)) => {
+                let ctrl9_xl = imu
+                    .read_cached(registers::CTRL9_XL)
+                    .map_err(step(ConfigStep::Axes))?;
+                let ctrl10_c = imu
+                    .read_cached(registers::CTRL10_C)
+                    .map_err(step(ConfigStep::Axes))?;
+                imu.write_registers(&[
+                    registers::CTRL9_XL,
+                    ctrl9_xl & !0b111000 | accel,
+                    ctrl10_c & !0b111000 | gyro,
+                ])
+                .map_err(step(ConfigStep::Axes))?;
+            }
+            (Some(accel), None) => {
+                imu.modify_register(registers::CTRL9_XL, 0b111000, accel)
+                    .map_err(step(ConfigStep::Axes))?;
+            }
+            (None, Some(gyro)) => {
+                imu.modify_register(registers::CTRL10_C, 0b111000, gyro)
+                    .map_err(step(ConfigStep::Axes))?;
+            }
+            (None, None) => {}
+        }
+        // CTRL1_XL, CTRL2_G and CTRL3_C are consecutive, so whichever of them are set
+        // go out in one burst, with any register in between them rewritten as it is
+        let burst_step = if self.accel.is_some() || self.gyro.is_some() {
+            ConfigStep::Modes
+        } else {
+            ConfigStep::Control
+        };
+        let mut burst = [
+            self.accel.map(|_| ctrl1_xl(accel_mode, accel_scale)),
+            self.gyro.map(|_| ctrl2_g(gyro_mode, gyro_scale)),
+            None,
+        ];
+        if self.bdu.is_some() || self.big_endian.is_some() {
+            let mut ctrl3_c = imu
+                .read_cached(registers::CTRL3_C)
+                .map_err(step(ConfigStep::Control))?;
+            for (setting, bit) in [(self.bdu, 0b1000000), (self.big_endian, 0b10)] {
+                match setting {
+                    Some(true) => ctrl3_c |= bit,
+                    Some(false) => ctrl3_c &= !bit,
+                    None => {}
+                }
+            }
+            burst[2] = Some(ctrl3_c);
+        }
+        if let (Some(first), Some(last)) = (
+            burst.iter().position(Option::is_some),
+            burst.iter().rposition(Option::is_some),
+        ) {
+            let mut bytes = [registers::CTRL1_XL + first as u8, 0, 0, 0];
+            for (i, value) in burst[first..=last].iter().enumerate() {
+                bytes[i + 1] = match value {
+                    Some(value) => *value,
+                    None => imu
+                        .read_cached(registers::CTRL1_XL + (first + i) as u8)
+                        .map_err(step(burst_step))?,
+                };
+            }
+            imu.write_registers(&bytes[..last - first + 2])
+                .map_err(step(burst_step))?;
+        }
+        imu.accel_mode = accel_mode;
+        imu.accel_scale = accel_scale;
+        imu.gyro_mode = gyro_mode;
+        imu.gyro_scale = gyro_scale;
+        if let Some(pin) = self.drdy {
+            let routed = if gyro_mode.to_bitcode() != 0 { 0b10 } else { 0 }
+                | if accel_mode.to_bitcode() != 0 { 1 } else { 0 };
+            imu.modify_register(pin.ctrl_register(), 0b11, routed)
+                .map_err(step(ConfigStep::Interrupts))?;
+        }
+        Ok(())
+    }
+}
+
+impl LSM6<()> {
+    /// Starts building a configuration to apply with `ConfigBuilder::apply`.
+    /// This is the same as `ConfigBuilder::new`.
+    pub fn configure() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Reads the FIFO, interrupt, control and embedded function configuration registers
+    /// into a `Config`, in three bursts.
+    pub fn read_config(&mut self) -> Result<Config, Error<E>> {
+        let dso = self.variant.has_dso_registers();
+        // FIFO_CTRL1 through ORIENT_CFG_G, or FIFO_CTRL1 through COUNTER_BDR_REG1 on the LSM6DSO
+        let mut fifo = [0; 6];
+        if dso {
+            self.read_registers(registers::lsm6dso::FIFO_CTRL1, &mut fifo[..5])?;
+        } else {
+            self.read_registers(registers::FIFO_CTRL1, &mut fifo)?;
+        }
+        // INT1_CTRL through CTRL10_C, including WHO_AM_I
+        let mut ctrl = [0; 13];
+        self.read_registers(registers::INT1_CTRL, &mut ctrl)?;
+        // TAP_CFG through MD2_CFG, after TAP_CFG0 and TAP_CFG1 on the LSM6DSO
+        let mut func = [0; 10];
+        if dso {
+            self.read_registers(registers::lsm6dso::TAP_CFG0, &mut func)?;
+        } else {
+            self.read_registers(registers::TAP_CFG, &mut func[2..])?;
+        }
+        let (fifo_ctrl5, orient_cfg_g, counter_bdr_reg1) = if dso {
+            (0, 0, fifo[4])
+        } else {
+            (fifo[4], fifo[5], 0)
+        };
+        Ok(Config {
+            fifo_ctrl1: fifo[0],
+            fifo_ctrl2: fifo[1],
+            fifo_ctrl3: fifo[2],
+            fifo_ctrl4: fifo[3],
+            fifo_ctrl5,
+            orient_cfg_g,
+            int1_ctrl: ctrl[0],
+            int2_ctrl: ctrl[1],
+            ctrl1_xl: ctrl[3],
+            ctrl2_g: ctrl[4],
+            ctrl3_c: ctrl[5],
+            ctrl4_c: ctrl[6],
+            ctrl5_c: ctrl[7],
+            ctrl6_c: ctrl[8],
+            ctrl7_g: ctrl[9],
+            ctrl8_xl: ctrl[10],
+            ctrl9_xl: ctrl[11],
+            ctrl10_c: ctrl[12],
+            tap_cfg: func[2],
+            tap_ths_6d: func[3],
+            int_dur2: func[4],
+            wake_up_ths: func[5],
+            wake_up_dur: func[6],
+            free_fall: func[7],
+            md1_cfg: func[8],
+            md2_cfg: func[9],
+            counter_bdr_reg1,
+            tap_cfg0: func[0],
+            tap_cfg1: func[1],
+        })
+    }
+
+    /// Reads the configuration registers like `LSM6::read_config`, and decodes them into a `ConfigBuilder`
+    /// with `ConfigBuilder::from_config`, e.g. to apply the same settings to another device.
+    pub fn read_config_builder(&mut self) -> Result<ConfigBuilder, Error<E>> {
+        let config = self.read_config()?;
+        Ok(ConfigBuilder::from_config(self.variant, &config))
+    }
+
+    /// Sets up both interrupt pins at once.
+    /// Both pins are masked first, then their electrical setup in CTRL3_C is changed,
+    /// keeping the rest of the register, and finally the routing is written in one burst,
+    /// so a pin never signals with the wrong polarity or a partial routing.
+    /// If a write fails, the pins may be left masked.
+    pub fn configure_interrupts(&mut self, config: InterruptConfig) -> Result<(), Error<E>> {
+        self.write_registers(&[registers::INT1_CTRL, 0, 0])?;
+        self.modify_register(
+            registers::CTRL3_C,
+            0b110000,
+            if config.active_low { 0b100000 } else { 0 }
+                | if config.open_drain { 0b10000 } else { 0 },
+        )?;
+        self.write_registers(&[registers::INT1_CTRL, config.int1_ctrl, config.int2_ctrl])
+    }
+
+    /// Writes a `Config` back to the device.
+    /// Interrupt routing is turned off first, then everything else is written,
+    /// then the sensor modes and the FIFO mode, and finally the interrupt routing,
+    /// so the intermediate states don't raise spurious interrupts.
+    /// The BOOT and SW_RESET bits of CTRL3_C are never written,
+    /// and if the IF_INC bit is clear in `config`, it is only cleared once everything else has been written.
+    /// On the LSM6DSO and LSM6DSOX, the FIFO mode is in FIFO_CTRL4,
+    /// and the RST_COUNTER_BDR bit of COUNTER_BDR_REG1 is never written either.
+    /// Consecutive registers are written in bursts, taking ten transactions in all.
+    /// The driver's modes and scales are updated to match.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), Error<E>> {
+        let dso = self.variant.has_dso_registers();
+        self.write_registers(&[registers::INT1_CTRL, 0, 0])?;
+        self.write_registers(&[registers::MD1_CFG, 0, 0])?;
+
+        let self_clearing = SELF_CLEARING_BITS
+            .iter()
+            .find(|(reg, _)| *reg == registers::CTRL3_C)
+            .map_or(0, |(_, bits)| *bits);
+        // IF_INC is kept set until the end, so the bursts after this one still work
+        self.write_registers(&[
+            registers::CTRL3_C,
+            config.ctrl3_c & !self_clearing | 0b100,
+            config.ctrl4_c,
+            config.ctrl5_c,
+            config.ctrl6_c,
+            config.ctrl7_g,
+            config.ctrl8_xl,
+            config.ctrl9_xl,
+            config.ctrl10_c,
+        ])?;
+        if dso {
+            self.write_registers(&[
+                registers::lsm6dso::FIFO_CTRL1,
+                config.fifo_ctrl1,
+                config.fifo_ctrl2,
+                config.fifo_ctrl3,
+            ])?;
+            // Without RST_COUNTER_BDR, which clears itself
+            self.write_registers(&[
+                registers::lsm6dso::COUNTER_BDR_REG1,
+                config.counter_bdr_reg1 & !0b1000000,
+            ])?;
+            self.write_registers(&[
+                registers::lsm6dso::TAP_CFG0,
+                config.tap_cfg0,
+                config.tap_cfg1,
+                config.tap_cfg,
+                config.tap_ths_6d,
+                config.int_dur2,
+                config.wake_up_ths,
+                config.wake_up_dur,
+                config.free_fall,
+            ])?;
+        } else {
+            self.write_registers(&[
+                registers::FIFO_CTRL1,
+                config.fifo_ctrl1,
+                config.fifo_ctrl2,
+                config.fifo_ctrl3,
+                config.fifo_ctrl4,
+            ])?;
+            self.write_registers(&[registers::ORIENT_CFG_G, config.orient_cfg_g])?;
+            self.write_registers(&[
+                registers::TAP_CFG,
+                config.tap_cfg,
+                config.tap_ths_6d,
+                config.int_dur2,
+                config.wake_up_ths,
+                config.wake_up_dur,
+                config.free_fall,
+            ])?;
+        }
+
+        self.write_registers(&[registers::CTRL1_XL, config.ctrl1_xl, config.ctrl2_g])?;
+        self.restore_modes(config.ctrl1_xl, config.ctrl2_g);
+        if dso {
+            self.write_registers(&[registers::lsm6dso::FIFO_CTRL4, config.fifo_ctrl4])?;
+            // The LSM6DSO's FIFO is tagged, so the driver has no pattern to keep track of
+            self.fifo_set_words = 0;
+        } else {
+            self.write_registers(&[registers::FIFO_CTRL5, config.fifo_ctrl5])?;
+            self.fifo_set_words = fifo_set_words(config.fifo_ctrl3);
+        }
+        self.fifo_pattern = 0;
+
+        self.write_registers(&[registers::INT1_CTRL, config.int1_ctrl, config.int2_ctrl])?;
+        self.write_registers(&[registers::MD1_CFG, config.md1_cfg, config.md2_cfg])?;
+        if config.ctrl3_c & 0b100 == 0 {
+            self.write_registers(&[registers::CTRL3_C, config.ctrl3_c & !self_clearing])?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::div_round;
+
+/// Reduces the rate of a stream of raw readings by averaging, e.g. from 1660 Hz to 52 Hz for logging.
+///
+/// Every `factor` readings, the average of the last `window` readings is emitted.
+/// A `window` equal to `factor` gives plain block averaging, and a longer one smooths more.
+/// `N` is the most readings the window can hold, between 1 and 65535.
+/// The sums are kept in `i32`, which can't overflow with that many full-scale readings,
+/// and averages are rounded to the nearest count, with halves rounded away from zero.
+/// Until the window has filled, the readings seen so far are averaged.
+#[derive(Debug, Clone)]
+pub struct Decimator<const N: usize> {
+    readings: [(i16, i16, i16); N],
+    next: usize,
+    filled: usize,
+    window: usize,
+    factor: u16,
+    since_output: u16,
+}
+
+impl<const N: usize> Decimator<N> {
+    const VALID_CAPACITY: () = assert!(N >= 1 && N <= 65535);
+
+    /// Creates a decimator emitting one average every `factor` readings,
+    /// over a window of `window` readings. `factor` is at least 1,
+    /// and `window` is kept between 1 and `N`.
+    pub fn new(factor: u16, window: usize) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_CAPACITY;
+        Self {
+            readings: [(0, 0, 0); N],
+            next: 0,
+            filled: 0,
+            window: window.clamp(1, N),
+            factor: factor.max(1),
+            since_output: 0,
+        }
+    }
+
+    /// Adds a reading, returning an average if one is due.
+    pub fn push(&mut self, reading: (i16, i16, i16)) -> Option<(i16, i16, i16)> {
+        self.readings[self.next] = reading;
+        self.next = (self.next + 1) % N;
+        self.filled = (self.filled + 1).min(N);
+        self.since_output += 1;
+        if self.since_output < self.factor {
+            return None;
+        }
+        self.since_output = 0;
+
+        let count = self.filled.min(self.window);
+        let mut sum = (0i32, 0i32, 0i32);
+        for i in 0..count {
+            let (x, y, z) = self.readings[(self.next + N - 1 - i) % N];
+            sum.0 += x as i32;
+            sum.1 += y as i32;
+            sum.2 += z as i32;
+        }
+        let count = count as i32;
+        Some((
+            div_round(sum.0, count) as i16,
+            div_round(sum.1, count) as i16,
+            div_round(sum.2, count) as i16,
+        ))
+    }
+
+    /// Forgets every reading, e.g. after a gap in the stream.
+    pub fn reset(&mut self) {
+        self.next = 0;
+        self.filled = 0;
+        self.since_output = 0;
+    }
+
+    /// Wraps an iterator of readings, such as the samples from `LSM6::read_fifo`,
+    /// into one of averages at the reduced rate.
+    pub fn decimate<I: Iterator<Item = (i16, i16, i16)>>(self, readings: I) -> Decimate<I, N> {
+        Decimate {
+            readings,
+            decimator: self,
+        }
+    }
+}
+
+/// An iterator of averaged readings, created with `Decimator::decimate`.
+#[derive(Debug, Clone)]
+pub struct Decimate<I, const N: usize> {
+    readings: I,
+    decimator: Decimator<N>,
+}
+
+impl<I, const N: usize> Decimate<I, N> {
+    /// Gives back the decimator, with the readings it has seen so far, and the wrapped iterator.
+    pub fn into_parts(self) -> (Decimator<N>, I) {
+        (self.decimator, self.readings)
+    }
+}
+
+impl<I: Iterator<Item = (i16, i16, i16)>, const N: usize> Iterator for Decimate<I, N> {
+    type Item = (i16, i16, i16);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let reading = self.readings.next()?;
+            if let Some(average) = self.decimator.push(reading) {
+                return Some(average);
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{interface::RegisterInterface, registers, ChipVariant, Error, LSM6};
+
+/// Which sensor's output the data enable (DEN) signal is stamped into, set with `LSM6::set_den_sensor`.
+/// The stamp replaces the least significant bit of the axes selected by the DEN_X, DEN_Y and DEN_Z bits of CTRL9_XL,
+/// which are all on after power-on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DenSensor {
+    /// The gyroscope, which is the default.
+    Gyroscope,
+    /// The accelerometer.
+    Accelerometer,
+    /// Both sensors.
+    Both,
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Selects which sensor the DEN signal is stamped into, e.g. the gyroscope for capture synchronized to a camera.
+    /// This is the DEN_XL_G bit of CTRL9_XL, which picks the accelerometer over the gyroscope,
+    /// and the DEN_XL_EN bit, which stamps both. DEN_XL_EN is in CTRL4_C on the LSM6DSL and LSM6DSM
+    /// and in CTRL9_XL on the LSM6DSO and LSM6DSOX. The rest of both registers is kept.
+    /// The LSM6DS33 can't stamp DEN into the outputs, so this returns `Error::Unsupported` there.
+    pub fn set_den_sensor(&mut self, sensor: DenSensor) -> Result<(), Error<E>> {
+        let den_xl_g = if sensor == DenSensor::Accelerometer {
+            0b10000
+        } else {
+            0
+        };
+        let both = sensor == DenSensor::Both;
+        match self.variant {
+            ChipVariant::Lsm6ds33 => return Err(Error::Unsupported),
+            ChipVariant::Lsm6dsl | ChipVariant::Lsm6dsm => {
+                self.modify_register(registers::CTRL9_XL, 0b10000, den_xl_g)?;
+                self.modify_register(
+                    registers::CTRL4_C,
+                    0b10000000,
+                    if both { 0b10000000 } else { 0 },
+                )?;
+            }
+            ChipVariant::Lsm6dso | ChipVariant::Lsm6dsox => {
+                let den_xl_en = if both { 0b1000 } else { 0 };
+                self.modify_register(registers::CTRL9_XL, 0b11000, den_xl_g | den_xl_en)?;
+            }
+        }
+        Ok(())
+    }
+}
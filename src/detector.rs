@@ -0,0 +1,234 @@
+//! Software detectors that run on polled or FIFO readings, for designs without the interrupt pins routed.
+
+use crate::AccelerometerScale;
+
+/// Whether a `MotionDetector` thinks the sensor is moving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MotionState {
+    Still,
+    Moving,
+}
+
+/// A change of `MotionState`, returned by `MotionDetector::update` when it happens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MotionEvent {
+    Started,
+    Stopped,
+}
+
+/// Detects motion in software from raw accelerometer readings, like the wake-up function
+/// (see `LSM6::setup_wake_on_motion`) but without needing an interrupt pin.
+///
+/// The criterion is the peak-to-peak change on any axis over the last `N` readings,
+/// so gravity and a constant tilt don't count as motion. The detector starts `Still`,
+/// becomes `Moving` once the change exceeds the threshold, and only goes back to `Still`
+/// once it has dropped under the threshold minus the hysteresis, so it doesn't chatter at the boundary.
+/// The defaults are a 50 mg threshold with 20 mg of hysteresis, well above the few mg of noise
+/// of a sensor lying still at ±2 g, even in the low-power modes.
+#[derive(Debug, Clone)]
+pub struct MotionDetector<const N: usize> {
+    readings: [(i16, i16, i16); N],
+    next: usize,
+    filled: usize,
+    micro_g_per_lsb: i32,
+    threshold_mg: u16,
+    hysteresis_mg: u16,
+    state: MotionState,
+}
+
+impl<const N: usize> MotionDetector<N> {
+    const VALID_WINDOW: () = assert!(N >= 2);
+
+    /// Creates a detector for readings taken at `scale`, with the default threshold and hysteresis.
+    /// `N`, the window length in readings, has to be at least 2;
+    /// around a tenth of a second of readings works well.
+    pub fn new(scale: AccelerometerScale) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_WINDOW;
+        Self {
+            readings: [(0, 0, 0); N],
+            next: 0,
+            filled: 0,
+            micro_g_per_lsb: scale.micro_g_per_lsb(),
+            threshold_mg: 50,
+            hysteresis_mg: 20,
+            state: MotionState::Still,
+        }
+    }
+
+    /// Sets the change that counts as motion, and how far under it the change has to drop
+    /// to count as still again, both in milli-g. The hysteresis is at most the threshold.
+    pub fn set_threshold(&mut self, threshold_mg: u16, hysteresis_mg: u16) {
+        self.threshold_mg = threshold_mg;
+        self.hysteresis_mg = hysteresis_mg.min(threshold_mg);
+    }
+
+    /// Sets the scale later readings are taken at, e.g. after `LSM6::set_accel_scale`.
+    /// This also forgets the readings so far, since they were taken at the old scale.
+    pub fn set_scale(&mut self, scale: AccelerometerScale) {
+        self.micro_g_per_lsb = scale.micro_g_per_lsb();
+        self.reset();
+    }
+
+    /// The current state.
+    pub fn state(&self) -> MotionState {
+        self.state
+    }
+
+    /// Forgets the readings so far and goes back to `Still`.
+    pub fn reset(&mut self) {
+        self.next = 0;
+        self.filled = 0;
+        self.state = MotionState::Still;
+    }
+
+    /// Adds a raw accelerometer reading, such as one from `LSM6::read_accel` or `LSM6::read_fifo`,
+    /// returning an event if the state changed.
+    pub fn update(&mut self, reading: (i16, i16, i16)) -> Option<MotionEvent> {
+        self.readings[self.next] = reading;
+        self.next = (self.next + 1) % N;
+        self.filled = (self.filled + 1).min(N);
+        if self.filled < 2 {
+            return None;
+        }
+
+        let mut min = [i16::MAX; 3];
+        let mut max = [i16::MIN; 3];
+        for &(x, y, z) in &self.readings[..self.filled] {
+            for (axis, &v) in [x, y, z].iter().enumerate() {
+                min[axis] = min[axis].min(v);
+                max[axis] = max[axis].max(v);
+            }
+        }
+        let change = (0..3)
+            .map(|axis| max[axis] as i32 - min[axis] as i32)
+            .max()
+            .unwrap_or(0);
+        let change_mg = change as i64 * self.micro_g_per_lsb as i64 / 1000;
+
+        match self.state {
+            MotionState::Still if change_mg > self.threshold_mg as i64 => {
+                self.state = MotionState::Moving;
+                Some(MotionEvent::Started)
+            }
+            MotionState::Moving if change_mg < (self.threshold_mg - self.hysteresis_mg) as i64 => {
+                self.state = MotionState::Still;
+                Some(MotionEvent::Stopped)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A completed shock found by `ShockDetector`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ShockEvent {
+    /// The largest magnitude of the acceleration during the shock, in milli-g.
+    pub peak_mg: u32,
+    /// How many readings in a row were over the threshold.
+    pub duration_samples: u32,
+    /// An axis hit the end of the full scale during the shock, so the true peak was higher than `peak_mg`.
+    pub saturated: bool,
+}
+
+/// Detects shocks in software from raw accelerometer readings: runs of readings whose magnitude
+/// is over a threshold. Each shock's peak and duration are recorded once it ends,
+/// in a queue of up to `Q` events that is read with `ShockDetector::pop`.
+///
+/// Feeding it every sample from the FIFO (see `LSM6::read_fifo`) means no shock is missed between polls.
+/// The magnitude is computed in 64-bit integers, so full-scale readings at ±16 g don't overflow.
+/// Gravity counts towards the magnitude, so the threshold should be well over 1 g.
+#[derive(Debug, Clone)]
+pub struct ShockDetector<const Q: usize> {
+    micro_g_per_lsb: i32,
+    threshold_counts_squared: i64,
+    current: Option<ShockEvent>,
+    peak_counts_squared: i64,
+    events: [ShockEvent; Q],
+    first: usize,
+    len: usize,
+    dropped: u32,
+}
+
+impl<const Q: usize> ShockDetector<Q> {
+    const VALID_CAPACITY: () = assert!(Q >= 1);
+
+    /// Creates a detector for readings taken at `scale`, flagging magnitudes over `threshold_mg`.
+    pub fn new(scale: AccelerometerScale, threshold_mg: u32) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_CAPACITY;
+        let micro_g_per_lsb = scale.micro_g_per_lsb();
+        let threshold_counts = threshold_mg as i64 * 1000 / micro_g_per_lsb as i64;
+        Self {
+            micro_g_per_lsb,
+            threshold_counts_squared: threshold_counts * threshold_counts,
+            current: None,
+            peak_counts_squared: 0,
+            events: [ShockEvent::default(); Q],
+            first: 0,
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Adds a raw accelerometer reading, returning whether a shock is in progress.
+    /// When a shock ends, it is added to the queue. If the queue is full, the oldest event is dropped.
+    pub fn update(&mut self, reading: (i16, i16, i16)) -> bool {
+        let (x, y, z) = (reading.0 as i64, reading.1 as i64, reading.2 as i64);
+        let counts_squared = x * x + y * y + z * z;
+        if counts_squared <= self.threshold_counts_squared {
+            if let Some(event) = self.current.take() {
+                self.finish(event);
+            }
+            return false;
+        }
+
+        let saturated = [reading.0, reading.1, reading.2]
+            .iter()
+            .any(|&v| v == i16::MIN || v == i16::MAX);
+        let event = self.current.get_or_insert(ShockEvent::default());
+        if event.duration_samples == 0 || counts_squared > self.peak_counts_squared {
+            self.peak_counts_squared = counts_squared;
+        }
+        event.duration_samples += 1;
+        event.saturated |= saturated;
+        true
+    }
+
+    /// Takes the oldest completed shock out of the queue.
+    pub fn pop(&mut self) -> Option<ShockEvent> {
+        if self.len == 0 {
+            return None;
+        }
+        let event = self.events[self.first];
+        self.first = (self.first + 1) % Q;
+        self.len -= 1;
+        Some(event)
+    }
+
+    /// Forgets the shock in progress, if any, keeping the queue.
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+
+    /// How many completed shocks were dropped because the queue was full.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    fn finish(&mut self, mut event: ShockEvent) {
+        // The squared magnitude in micro-g is under 2^50 even at ±16 g, so this can't overflow
+        let ug = self.micro_g_per_lsb as u64;
+        event.peak_mg = ((self.peak_counts_squared as u64 * ug * ug).isqrt() / 1000) as u32;
+        if self.len == Q {
+            self.first = (self.first + 1) % Q;
+            self.len -= 1;
+            self.dropped = self.dropped.saturating_add(1);
+        }
+        self.events[(self.first + self.len) % Q] = event;
+        self.len += 1;
+    }
+}
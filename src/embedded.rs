@@ -0,0 +1,86 @@
+use crate::{interface::RegisterInterface, registers, Error, LSM6};
+
+/// The events reported by the embedded functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EmbFuncStatus {
+    pub step_detected: bool,
+    pub tilt: bool,
+    pub significant_motion: bool,
+    /// The sensor hub has finished a read cycle, so its outputs hold fresh data from the external sensors.
+    pub sensor_hub_end_op: bool,
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Switches to the embedded function register bank, runs `f`, then switches back to the main bank.
+    /// The switch back is always attempted, even if `f` fails, and `f`'s error takes priority over its error.
+    /// Inside `f`, register addresses refer to the embedded bank, so only raw register access
+    /// should be used, and `with_embedded_bank` must not be nested.
+    /// The shadow cache is not touched while in the embedded bank.
+    /// Each bank switch is a single register write, so the retry policy can safely repeat it,
+    /// but transactions made by `f` are retried like any other.
+    pub fn with_embedded_bank<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, Error<E>>,
+    ) -> Result<T, Error<E>> {
+        self.with_bank(0b10000000, f)
+    }
+
+    /// Like `LSM6::with_embedded_bank`, but for the bank that holds the sensor hub's settings:
+    /// the sensor hub bank on the LSM6DSO, and embedded function bank A on the others.
+    pub(crate) fn with_sensor_hub_bank<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, Error<E>>,
+    ) -> Result<T, Error<E>> {
+        let access = if self.variant.has_dso_registers() {
+            0b1000000
+        } else {
+            0b10000000
+        };
+        self.with_bank(access, f)
+    }
+
+    /// Switches banks by writing `access` to FUNC_CFG_ACCESS, as described for `LSM6::with_embedded_bank`.
+    fn with_bank<T>(
+        &mut self,
+        access: u8,
+        f: impl FnOnce(&mut Self) -> Result<T, Error<E>>,
+    ) -> Result<T, Error<E>> {
+        self.set_register_raw(registers::FUNC_CFG_ACCESS, access)?;
+        self.embedded_bank = true;
+        let result = f(self);
+        self.embedded_bank = false;
+        let exit = self.set_register_raw(registers::FUNC_CFG_ACCESS, 0);
+        let value = result?;
+        exit?;
+        Ok(value)
+    }
+
+    /// Reads and decodes the embedded function event flags.
+    /// These are in FUNC_SRC on most variants, but on the LSM6DSO they are in EMB_FUNC_STATUS
+    /// behind the embedded function bank, which this switches to and back from,
+    /// and the sensor hub's end of operation flag is read from STATUS_MASTER_MAINPAGE afterwards.
+    /// The LSM6DS33 has no sensor hub, so its `sensor_hub_end_op` is always false.
+    pub fn read_embedded_function_status(&mut self) -> Result<EmbFuncStatus, Error<E>> {
+        if !self.variant.has_dso_registers() {
+            let src = self.read_register_raw(registers::FUNC_SRC)?;
+            return Ok(EmbFuncStatus {
+                step_detected: src & 0b10000 != 0,
+                tilt: src & 0b100000 != 0,
+                significant_motion: src & 0b1000000 != 0,
+                sensor_hub_end_op: src & 1 != 0,
+            });
+        }
+
+        let status = self.with_embedded_bank(|this| {
+            this.read_register_raw(registers::lsm6dso::EMB_FUNC_STATUS)
+        })?;
+        let master = self.read_register_raw(registers::lsm6dso::STATUS_MASTER_MAINPAGE)?;
+        Ok(EmbFuncStatus {
+            step_detected: status & 0b1000 != 0,
+            tilt: status & 0b10000 != 0,
+            significant_motion: status & 0b100000 != 0,
+            sensor_hub_end_op: master & 1 != 0,
+        })
+    }
+}
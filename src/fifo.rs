@@ -0,0 +1,474 @@
+use crate::{
+    combine_le, interface::RegisterInterface, registers, ChipVariant, DataRate, Error, SlaveSlot,
+    LSM6,
+};
+
+/// Different modes that the FIFO can run in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FifoMode {
+    /// The FIFO is disabled and emptied.
+    Bypass,
+    /// Samples are collected until the FIFO is full, then collection stops.
+    Fifo,
+    /// Behaves like `Continuous` until a trigger event, then like `Fifo`.
+    ContinuousToFifo,
+    /// Behaves like `Bypass` until a trigger event, then like `Continuous`.
+    BypassToContinuous,
+    /// Samples are collected continuously, overwriting the oldest once the FIFO is full.
+    Continuous,
+}
+
+impl FifoMode {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            FifoMode::Bypass => 0,
+            FifoMode::Fifo => 1,
+            FifoMode::ContinuousToFifo => 0b11,
+            FifoMode::BypassToContinuous => 0b100,
+            FifoMode::Continuous => 0b110,
+        }
+    }
+
+    pub(crate) fn from_bitcode(bits: u8) -> Option<Self> {
+        [
+            FifoMode::Bypass,
+            FifoMode::Fifo,
+            FifoMode::ContinuousToFifo,
+            FifoMode::BypassToContinuous,
+            FifoMode::Continuous,
+        ]
+        .iter()
+        .copied()
+        .find(|mode| mode.to_bitcode() == bits)
+    }
+}
+
+/// Rates at which temperature can be stored in a tagged FIFO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TemperatureBatchRate {
+    NotBatched,
+    Hz1_6,
+    Hz12_5,
+    Hz52,
+}
+
+impl TemperatureBatchRate {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            TemperatureBatchRate::NotBatched => 0,
+            TemperatureBatchRate::Hz1_6 => 1,
+            TemperatureBatchRate::Hz12_5 => 0b10,
+            TemperatureBatchRate::Hz52 => 0b11,
+        }
+    }
+}
+
+/// How often a timestamp is stored in a tagged FIFO, in terms of stored samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimestampDecimation {
+    NotBatched,
+    Every1,
+    Every8,
+    Every32,
+}
+
+impl TimestampDecimation {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            TimestampDecimation::NotBatched => 0,
+            TimestampDecimation::Every1 => 1,
+            TimestampDecimation::Every8 => 0b10,
+            TimestampDecimation::Every32 => 0b11,
+        }
+    }
+}
+
+/// One entry of a tagged FIFO, as found on the LSM6DSO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FifoWord {
+    Gyroscope((i16, i16, i16)),
+    Accelerometer((i16, i16, i16)),
+    Temperature(i16),
+    /// The value of the timestamp counter, where each tick is nominally 25 µs.
+    Timestamp(u32),
+    /// The bytes the sensor hub read through a slave, batched with `LSM6::set_sensor_hub_batching`.
+    /// Only as many bytes as the slave reads are meaningful, up to six, and the rest should be ignored.
+    ExternalSensor {
+        slot: SlaveSlot,
+        data: [u8; 6],
+    },
+    /// An entry this driver doesn't decode, with its tag and raw data.
+    Other {
+        tag: u8,
+        data: [u8; 6],
+    },
+}
+
+impl FifoWord {
+    /// Decodes the FIFO_DATA_OUT_TAG byte followed by the six FIFO_DATA_OUT bytes.
+    pub fn decode(bytes: &[u8; 7]) -> Self {
+        let word = |i: usize| combine_le(bytes[i], bytes[i + 1]);
+        match bytes[0] >> 3 {
+            0x01 => FifoWord::Gyroscope((word(1), word(3), word(5))),
+            0x02 => FifoWord::Accelerometer((word(1), word(3), word(5))),
+            0x03 => FifoWord::Temperature(word(1)),
+            0x04 => {
+                FifoWord::Timestamp(u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]))
+            }
+            tag => {
+                let mut data = [0; 6];
+                data.copy_from_slice(&bytes[1..]);
+                let slot = match tag {
+                    0x0E => SlaveSlot::Slave0,
+                    0x0F => SlaveSlot::Slave1,
+                    0x10 => SlaveSlot::Slave2,
+                    0x11 => SlaveSlot::Slave3,
+                    tag => return FifoWord::Other { tag, data },
+                };
+                FifoWord::ExternalSensor { slot, data }
+            }
+        }
+    }
+}
+
+/// The contents of the FIFO status registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FifoStatus {
+    /// The number of unread 16-bit words, or of unread entries in a tagged FIFO.
+    pub unread_words: u16,
+    /// The number of unread words has reached the watermark.
+    pub watermark: bool,
+    /// Samples have been lost because the FIFO was full.
+    pub overrun: bool,
+    /// The FIFO will be full after the next sample is stored.
+    pub full: bool,
+    /// The position of the next unread word in the repeating pattern of batched sensors,
+    /// or `None` on the LSM6DSO, whose entries are tagged instead.
+    pub pattern: Option<u16>,
+}
+
+impl FifoStatus {
+    /// How many status registers `variant` has, starting at FIFO_STATUS1.
+    pub(crate) fn len(variant: ChipVariant) -> usize {
+        if variant.has_dso_registers() {
+            2
+        } else {
+            4
+        }
+    }
+
+    /// Decodes FIFO_STATUS1 through FIFO_STATUS4, or only FIFO_STATUS1 and FIFO_STATUS2 on the LSM6DSO.
+    pub(crate) fn decode(variant: ChipVariant, bytes: &[u8]) -> Self {
+        let tagged = variant.has_dso_registers();
+        FifoStatus {
+            unread_words: if tagged {
+                ((bytes[1] & 0b11) as u16) << 8 | bytes[0] as u16
+            } else {
+                unread_words([bytes[0], bytes[1]])
+            },
+            watermark: bytes[1] & 0b10000000 != 0,
+            overrun: bytes[1] & 0b1000000 != 0,
+            full: bytes[1] & 0b100000 != 0,
+            pattern: if tagged {
+                None
+            } else {
+                Some(((bytes[3] & 0b11) as u16) << 8 | bytes[2] as u16)
+            },
+        }
+    }
+
+    /// Whether there is nothing left to read.
+    pub fn is_empty(&self) -> bool {
+        self.unread_words == 0
+    }
+}
+
+/// What `FifoStream` yields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FifoEvent {
+    /// The next sample in the FIFO. See `LSM6::read_fifo` for the ordering of samples.
+    Sample((i16, i16, i16)),
+    /// The FIFO filled up and samples were lost before the ones that follow.
+    Overrun,
+}
+
+/// Reads samples out of the FIFO as they arrive. See `LSM6::fifo_stream`.
+pub struct FifoStream<'a, I> {
+    lsm6: &'a mut LSM6<I>,
+    remaining: u16,
+}
+
+impl<E, I: RegisterInterface<Error = E>> Iterator for FifoStream<'_, I> {
+    type Item = Result<FifoEvent, Error<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            let status = match self.lsm6.read_fifo_status() {
+                Ok(status) => status,
+                Err(e) => return Some(Err(e)),
+            };
+            self.remaining = status.unread_words / 3;
+            if status.overrun {
+                return Some(Ok(FifoEvent::Overrun));
+            }
+            if self.remaining == 0 {
+                return None;
+            }
+        }
+        self.remaining -= 1;
+        let sample = self
+            .lsm6
+            .incremental_read_measurements(registers::FIFO_DATA_OUT_L);
+        self.lsm6.advance_fifo_pattern(3);
+        Some(sample.map(FifoEvent::Sample))
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Sets the mode of the FIFO and the rate at which samples are stored in it.
+    /// This overwrites the FIFO_CTRL5 register.
+    /// The LSM6DSO's tagged FIFO isn't supported, since its mode and batching rates are laid out differently.
+    pub fn set_fifo_mode(&mut self, mode: FifoMode, rate: DataRate) -> Result<(), Error<E>> {
+        untagged_fifo(self.variant)?;
+        self.set_register_raw(registers::FIFO_CTRL5, fifo_ctrl5(mode, rate))?;
+        if mode == FifoMode::Bypass {
+            self.fifo_pattern = 0;
+        }
+        Ok(())
+    }
+
+    /// Sets which sensors are stored in the FIFO, without decimation.
+    /// When both are stored, the FIFO alternates between a gyroscope sample and an accelerometer sample,
+    /// starting with the gyroscope.
+    /// This overwrites the FIFO_CTRL3 register.
+    /// The LSM6DSO's tagged FIFO isn't supported.
+    pub fn set_fifo_batching(&mut self, accel: bool, gyro: bool) -> Result<(), Error<E>> {
+        untagged_fifo(self.variant)?;
+        let ctrl3 = fifo_ctrl3(accel, gyro);
+        self.set_register_raw(registers::FIFO_CTRL3, ctrl3)?;
+        self.fifo_set_words = fifo_set_words(ctrl3);
+        Ok(())
+    }
+
+    /// Sets the FIFO threshold that the watermark flag and interrupt go off at, returning the threshold actually set.
+    /// The threshold is in 16-bit words, or in entries on the LSM6DSO, and its field is 12 bits wide on the LSM6DS33,
+    /// 11 on the LSM6DSL and LSM6DSM and 9 on the LSM6DSO, so a larger value is clamped to the widest that fits,
+    /// which is a full FIFO. The low bits go in FIFO_CTRL1, which is overwritten,
+    /// and the high bits in FIFO_CTRL2, whose other bits are kept. Both are one address higher on the LSM6DSO.
+    pub fn set_fifo_watermark(&mut self, threshold: u16) -> Result<u16, Error<E>> {
+        let (ctrl1, ctrl2, high_mask) = match self.variant {
+            ChipVariant::Lsm6ds33 => (registers::FIFO_CTRL1, registers::FIFO_CTRL2, 0b1111),
+            ChipVariant::Lsm6dsl | ChipVariant::Lsm6dsm => {
+                (registers::FIFO_CTRL1, registers::FIFO_CTRL2, 0b111)
+            }
+            ChipVariant::Lsm6dso | ChipVariant::Lsm6dsox => (
+                registers::lsm6dso::FIFO_CTRL1,
+                registers::lsm6dso::FIFO_CTRL2,
+                1,
+            ),
+        };
+        let threshold = threshold.min(u16::from_le_bytes([0xFF, high_mask]));
+        let [low, high] = threshold.to_le_bytes();
+        self.set_register_raw(ctrl1, low)?;
+        self.modify_register(ctrl2, high_mask, high)?;
+        Ok(threshold)
+    }
+
+    /// Reads every FIFO status register in one burst and decodes them,
+    /// which is four registers, or two on the LSM6DSO.
+    /// This also takes the FIFO pattern position from them, like `LSM6::parse_fifo_status`.
+    /// The other FIFO status helpers are built on this.
+    pub fn read_fifo_status(&mut self) -> Result<FifoStatus, Error<E>> {
+        let mut bytes = [0; 4];
+        let len = FifoStatus::len(self.variant);
+        self.read_registers(registers::FIFO_STATUS1, &mut bytes[..len])?;
+        Ok(self.parse_fifo_status(&bytes[..len]))
+    }
+
+    /// Reads the number of unread 16-bit words in the FIFO, or of unread entries on the LSM6DSO.
+    /// Each sample is made up of three words.
+    pub fn fifo_unread_words(&mut self) -> Result<u16, Error<E>> {
+        Ok(self.read_fifo_status()?.unread_words)
+    }
+
+    /// Checks whether the FIFO has no unread words.
+    pub fn fifo_empty(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_fifo_status()?.is_empty())
+    }
+
+    /// Checks whether the FIFO has overflowed, using the overrun flag of FIFO_STATUS2.
+    /// None of the supported variants flag overwritten samples in STATUS_REG, so running
+    /// the FIFO in `FifoMode::Continuous` and checking this is how to tell that reads fell behind the ODR.
+    pub fn fifo_overrun(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_fifo_status()?.overrun)
+    }
+
+    /// Reads samples out of the FIFO into `samples`, returning how many were read.
+    /// This stops early if the FIFO runs out of complete samples.
+    /// This method of extracting samples only works if the 2nd bit (0-indexed) of the CTRL_3C register is set to 1.
+    /// The LSM6DSO's tagged FIFO isn't supported; use `LSM6::read_fifo_tagged` there.
+    pub fn read_fifo(&mut self, samples: &mut [(i16, i16, i16)]) -> Result<usize, Error<E>> {
+        untagged_fifo(self.variant)?;
+        let available = (self.fifo_unread_words()? / 3) as usize;
+        let count = available.min(samples.len());
+        for sample in &mut samples[..count] {
+            *sample = self.incremental_read_measurements(registers::FIFO_DATA_OUT_L)?;
+            self.advance_fifo_pattern(3);
+        }
+        Ok(count)
+    }
+
+    /// Streams samples out of the FIFO, which is meant for `FifoMode::Continuous`.
+    /// The FIFO status is read whenever the samples it last reported have all been read,
+    /// and the stream returns `None` once the FIFO is empty. Calling `next` again later carries on
+    /// with whatever has been stored since, so the same stream can be polled from a main loop.
+    /// If the FIFO overran, `FifoEvent::Overrun` is yielded before the samples that were kept.
+    /// The LSM6DSO's tagged FIFO isn't supported; use `LSM6::read_fifo_tagged` there.
+    pub fn fifo_stream(&mut self) -> Result<FifoStream<'_, I>, Error<E>> {
+        untagged_fifo(self.variant)?;
+        Ok(FifoStream {
+            lsm6: self,
+            remaining: 0,
+        })
+    }
+
+    /// Reads up to `N` samples out of the FIFO.
+    /// See `LSM6::read_fifo` for the ordering of samples and register requirements.
+    #[cfg(feature = "heapless")]
+    pub fn drain_fifo<const N: usize>(
+        &mut self,
+    ) -> Result<heapless::Vec<(i16, i16, i16), N>, Error<E>> {
+        untagged_fifo(self.variant)?;
+        let mut samples = heapless::Vec::new();
+        let available = (self.fifo_unread_words()? / 3) as usize;
+        for _ in 0..available.min(N) {
+            let sample = self.incremental_read_measurements(registers::FIFO_DATA_OUT_L)?;
+            self.advance_fifo_pattern(3);
+            // The loop is bounded by the capacity, so this can't fail.
+            let _ = samples.push(sample);
+        }
+        Ok(samples)
+    }
+
+    /// Sets the rate at which temperature is stored in the FIFO, where it shows up as `FifoWord::Temperature`.
+    /// This is only available on the LSM6DSO, whose FIFO is tagged.
+    /// The rest of its FIFO_CTRL4 register is kept as it is.
+    pub fn set_fifo_temperature_batching(
+        &mut self,
+        rate: TemperatureBatchRate,
+    ) -> Result<(), Error<E>> {
+        if !self.variant.has_dso_registers() {
+            return Err(Error::Unsupported);
+        }
+        self.modify_register(
+            registers::lsm6dso::FIFO_CTRL4,
+            0b110000,
+            rate.to_bitcode() << 4,
+        )?;
+        Ok(())
+    }
+
+    /// Sets how often a timestamp is stored in the FIFO, where it shows up as `FifoWord::Timestamp`.
+    /// Batching timestamps also turns on the timestamp counter with the TIMESTAMP_EN bit of CTRL10_C.
+    /// This is only available on the LSM6DSO, whose FIFO is tagged.
+    /// The rest of the FIFO_CTRL4 and CTRL10_C registers are kept as they are.
+    pub fn set_fifo_timestamp_batching(
+        &mut self,
+        decimation: TimestampDecimation,
+    ) -> Result<(), Error<E>> {
+        if !self.variant.has_dso_registers() {
+            return Err(Error::Unsupported);
+        }
+        if decimation != TimestampDecimation::NotBatched {
+            self.set_bits(registers::CTRL10_C, 0b100000)?;
+        }
+        self.modify_register(
+            registers::lsm6dso::FIFO_CTRL4,
+            0b11000000,
+            decimation.to_bitcode() << 6,
+        )?;
+        Ok(())
+    }
+
+    /// Reads the next entry out of a tagged FIFO, returning `Ok(None)` if it is empty.
+    /// This is only available on the LSM6DSO.
+    pub fn read_fifo_tagged(&mut self) -> Result<Option<FifoWord>, Error<E>> {
+        if !self.variant.has_dso_registers() {
+            return Err(Error::Unsupported);
+        }
+        let mut status = [0; 2];
+        self.read_registers(registers::FIFO_STATUS1, &mut status)?;
+        if status[0] == 0 && status[1] & 0b11 == 0 {
+            return Ok(None);
+        }
+
+        let mut bytes = [0; 7];
+        self.read_registers(registers::lsm6dso::FIFO_DATA_OUT_TAG, &mut bytes)?;
+        Ok(Some(
+            self.mounting.apply_fifo_word(FifoWord::decode(&bytes)),
+        ))
+    }
+}
+
+impl<I> LSM6<I> {
+    /// The position of the next unread FIFO word in the repeating pattern of batched sensors.
+    /// This is taken from the last FIFO status parsed with `LSM6::parse_fifo_status`,
+    /// and moved along by every FIFO read or parse since, as long as the sensors were batched
+    /// with `LSM6::set_fifo_batching` or `LSM6::apply_config` without decimation.
+    /// When both sensors are batched, words 0 to 2 of the pattern are the gyroscope and 3 to 5 the accelerometer.
+    pub fn fifo_pattern(&self) -> u16 {
+        self.fifo_pattern
+    }
+
+    pub(crate) fn advance_fifo_pattern(&mut self, words: u16) {
+        if self.fifo_set_words != 0 {
+            self.fifo_pattern =
+                (self.fifo_pattern + words % self.fifo_set_words) % self.fifo_set_words;
+        }
+    }
+}
+
+/// Refuses the parts of the FIFO API that only work with an untagged FIFO on the LSM6DSO.
+pub(crate) fn untagged_fifo<E>(variant: ChipVariant) -> Result<(), Error<E>> {
+    if variant.has_dso_registers() {
+        Err(Error::Unsupported)
+    } else {
+        Ok(())
+    }
+}
+
+/// The FIFO_CTRL5 value for a FIFO mode and rate.
+pub(crate) fn fifo_ctrl5(mode: FifoMode, rate: DataRate) -> u8 {
+    rate.to_bitcode() << 3 | mode.to_bitcode()
+}
+
+/// The FIFO_CTRL3 value for storing the given sensors without decimation.
+pub(crate) fn fifo_ctrl3(accel: bool, gyro: bool) -> u8 {
+    (if gyro { 0b1000 } else { 0 }) | if accel { 1 } else { 0 }
+}
+
+/// The number of words in one repetition of the FIFO pattern for a FIFO_CTRL3 value,
+/// or 0 if a sensor is decimated, which this doesn't follow.
+pub(crate) fn fifo_set_words(ctrl3: u8) -> u16 {
+    let gyro = (ctrl3 >> 3 & 0b111) as u16;
+    let accel = (ctrl3 & 0b111) as u16;
+    if gyro > 1 || accel > 1 {
+        0
+    } else {
+        3 * (gyro + accel)
+    }
+}
+
+/// The number of unread words from FIFO_STATUS1 and FIFO_STATUS2.
+pub(crate) fn unread_words(status: [u8; 2]) -> u16 {
+    ((status[1] & 0xF) as u16) << 8 | status[0] as u16
+}
@@ -0,0 +1,41 @@
+use crate::{interface::RegisterInterface, registers, Error, LSM6};
+
+/// Which stage of the accelerometer's filter chain its output registers show,
+/// set with `LSM6::set_accel_output_filter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FilterPath {
+    /// The output of the first digital low-pass filter, with neither LPF2 nor the high-pass filter applied.
+    Raw,
+    /// The output of the slope or high-pass filter, with the cutoff set by the HPCF_XL bits of CTRL8_XL.
+    Filtered,
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Selects what the accelerometer's output registers (and the FIFO) show.
+    /// This is the HP_SLOPE_XL_EN bit of CTRL8_XL, which switches the outputs to the slope or high-pass filter,
+    /// and for `FilterPath::Raw` also the LPF2_XL_EN bit, which is in CTRL8_XL on most variants
+    /// but in CTRL1_XL on the LSM6DSO. The rest of both registers is kept.
+    ///
+    /// These bits only choose the output path. Tap detection always runs on the slope filter,
+    /// and wake-up and activity detection run on the slope filter or the high-pass filter
+    /// depending on the SLOPE_FDS bit of TAP_CFG, whichever path the outputs show.
+    /// So a high-pass filter set up for the embedded functions can stay on with `FilterPath::Raw`.
+    pub fn set_accel_output_filter(&mut self, path: FilterPath) -> Result<(), Error<E>> {
+        match path {
+            FilterPath::Raw => {
+                if self.variant.has_dso_registers() {
+                    self.clear_bits(registers::CTRL1_XL, 0b10)?;
+                    self.clear_bits(registers::CTRL8_XL, 0b100)?;
+                } else {
+                    self.clear_bits(registers::CTRL8_XL, 0b10000100)?;
+                }
+            }
+            FilterPath::Filtered => {
+                self.set_bits(registers::CTRL8_XL, 0b100)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+//! Readings as fixed-point numbers, behind the `fixed` feature.
+//!
+//! The conversions only use integer math, so they are cheap on targets without an FPU.
+//! They are based on the same sensitivities as the float conversions
+//! (`AccelerometerScale::micro_g_per_lsb` and `GyroscopeScale::eighth_mdps_per_lsb`).
+//! Fractional bits that don't fit in the target type are discarded, as with `Fixed::from_num`.
+//!
+//! `I16F16` holds every reading at every scale. Narrower types saturate instead of wrapping,
+//! e.g. a 2000 dps reading as `I8F8` is `I8F8::MAX`.
+
+use fixed::{traits::Fixed, types::I32F32};
+
+use crate::{interface::RegisterInterface, AccelerometerScale, Error, GyroscopeScale, LSM6};
+
+/// `raw * numerator / denominator` as an `I32F32`, rounded to the nearest representable value.
+/// This can't overflow, since `raw` is at most 2^15 and `numerator` is small.
+fn scaled(raw: i16, numerator: i32, denominator: i64) -> I32F32 {
+    let bits = ((raw as i64 * numerator as i64) << 32) + denominator / 2;
+    I32F32::from_bits(bits.div_euclid(denominator))
+}
+
+impl AccelerometerScale {
+    /// Converts a raw accelerometer reading taken at this scale into g, saturating at the limits of `F`.
+    pub fn to_g_fixed<F: Fixed>(&self, raw: i16) -> F {
+        F::saturating_from_num(scaled(raw, self.micro_g_per_lsb(), 1_000_000))
+    }
+}
+
+impl GyroscopeScale {
+    /// Converts a raw gyroscope reading taken at this scale into degrees per second,
+    /// saturating at the limits of `F`.
+    pub fn to_dps_fixed<F: Fixed>(&self, raw: i16) -> F {
+        F::saturating_from_num(scaled(raw, self.eighth_mdps_per_lsb(), 8000))
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Reads the latest acceleration data in g as fixed-point numbers, using the current scale.
+    /// This has the same requirements and `None` behavior as `LSM6::read_accel`.
+    pub fn read_accel_fixed<F: Fixed>(&mut self) -> Result<Option<(F, F, F)>, Error<E>> {
+        let scale = self.accel_scale;
+        Ok(self.read_accel()?.map(|(x, y, z)| {
+            (
+                scale.to_g_fixed(x),
+                scale.to_g_fixed(y),
+                scale.to_g_fixed(z),
+            )
+        }))
+    }
+
+    /// Reads the latest gyroscopic data in degrees per second as fixed-point numbers,
+    /// using the current scale.
+    /// This has the same requirements and `None` behavior as `LSM6::read_gyro`.
+    pub fn read_gyro_fixed<F: Fixed>(&mut self) -> Result<Option<(F, F, F)>, Error<E>> {
+        let scale = self.gyro_scale;
+        Ok(self.read_gyro()?.map(|(x, y, z)| {
+            (
+                scale.to_dps_fixed(x),
+                scale.to_dps_fixed(y),
+                scale.to_dps_fixed(z),
+            )
+        }))
+    }
+}
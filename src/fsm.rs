@@ -0,0 +1,208 @@
+//! The finite state machine (FSM) of the LSM6DSO and LSM6DSOX, which runs up to 16 small programs
+//! on the sensor data, e.g. to recognize gestures.
+
+use crate::{interface::RegisterInterface, registers, Error, InterruptPin, LSM6};
+
+/// The rate the finite state machine runs its programs at, set with `LSM6::set_fsm_odr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FsmOdr {
+    Hz12_5,
+    Hz26,
+    Hz52,
+    Hz104,
+}
+
+impl FsmOdr {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            FsmOdr::Hz12_5 => 0,
+            FsmOdr::Hz26 => 1,
+            FsmOdr::Hz52 => 0b10,
+            FsmOdr::Hz104 => 0b11,
+        }
+    }
+}
+
+/// The output of one finite state machine program, from its FSM_OUTS register:
+/// which positive and negative thresholds on each axis, and on the vector magnitude v,
+/// the program's last OUTC command flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FsmOutput {
+    pub positive_x: bool,
+    pub negative_x: bool,
+    pub positive_y: bool,
+    pub negative_y: bool,
+    pub positive_z: bool,
+    pub negative_z: bool,
+    pub positive_v: bool,
+    pub negative_v: bool,
+}
+
+impl FsmOutput {
+    fn decode(outs: u8) -> Self {
+        FsmOutput {
+            positive_x: outs & 0b10000000 != 0,
+            negative_x: outs & 0b1000000 != 0,
+            positive_y: outs & 0b100000 != 0,
+            negative_y: outs & 0b10000 != 0,
+            positive_z: outs & 0b1000 != 0,
+            negative_z: outs & 0b100 != 0,
+            positive_v: outs & 0b10 != 0,
+            negative_v: outs & 1 != 0,
+        }
+    }
+}
+
+/// Where the program count and start address live in the advanced embedded function page,
+/// and where the programs are loaded.
+const FSM_PROGRAMS: u16 = 0x017C;
+const FSM_START_ADD_L: u16 = 0x017E;
+const FSM_START_ADDRESS: u16 = 0x0400;
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Turns the finite state machine on or off with the FSM_EN bit of EMB_FUNC_EN_B.
+    /// The LSM6DSO and LSM6DSOX are the only parts with one, so anywhere else
+    /// this and the other FSM methods return `Error::Unsupported`.
+    pub fn enable_fsm(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        self.require_fsm()?;
+        self.with_embedded_bank(|this| {
+            let en = this.read_register_raw(registers::lsm6dso::EMB_FUNC_EN_B)?;
+            let en = if enabled { en | 1 } else { en & !1 };
+            this.set_register_raw(registers::lsm6dso::EMB_FUNC_EN_B, en)
+        })
+    }
+
+    /// Sets the rate the finite state machine runs at, keeping the rest of EMB_FUNC_ODR_CFG_B.
+    /// The accelerometer and gyroscope have to run at least this fast.
+    pub fn set_fsm_odr(&mut self, odr: FsmOdr) -> Result<(), Error<E>> {
+        self.require_fsm()?;
+        self.with_embedded_bank(|this| {
+            let cfg = this.read_register_raw(registers::lsm6dso::EMB_FUNC_ODR_CFG_B)?;
+            let cfg = cfg & !0b111000 | odr.to_bitcode() << 3;
+            this.set_register_raw(registers::lsm6dso::EMB_FUNC_ODR_CFG_B, cfg)
+        })
+    }
+
+    /// Loads up to 16 programs, such as those exported by ST's tools, one after the other
+    /// from the start of the program area, and sets the program count and start address to match.
+    /// The programs aren't run until they are enabled with `LSM6::set_fsm_programs_enabled`
+    /// and the state machine is turned on with `LSM6::enable_fsm`, which should be done afterwards.
+    /// More than 16 programs is `Error::InvalidConfig`, and nothing is written.
+    pub fn load_fsm_programs(&mut self, programs: &[&[u8]]) -> Result<(), Error<E>> {
+        self.require_fsm()?;
+        if programs.len() > 16 {
+            return Err(Error::InvalidConfig);
+        }
+        self.with_embedded_bank(|this| {
+            this.write_pages(FSM_PROGRAMS, &[programs.len() as u8])?;
+            this.write_pages(FSM_START_ADD_L, &FSM_START_ADDRESS.to_le_bytes())?;
+            let mut address = FSM_START_ADDRESS;
+            for program in programs {
+                this.write_pages(address, program)?;
+                address = address.wrapping_add(program.len() as u16);
+            }
+            Ok(())
+        })
+    }
+
+    /// Writes `bytes` to the embedded function page memory from `address`, where the high byte selects the page,
+    /// crossing into the next page as needed.
+    /// This is how finite state machine programs and the advanced embedded function settings are written.
+    pub fn write_page_memory(&mut self, address: u16, bytes: &[u8]) -> Result<(), Error<E>> {
+        self.require_fsm()?;
+        self.with_embedded_bank(|this| this.write_pages(address, bytes))
+    }
+
+    /// Sets which programs run, where bit n stands for program n + 1, in FSM_ENABLE_A and FSM_ENABLE_B.
+    pub fn set_fsm_programs_enabled(&mut self, programs: u16) -> Result<(), Error<E>> {
+        self.require_fsm()?;
+        let [a, b] = programs.to_le_bytes();
+        self.with_embedded_bank(|this| {
+            this.write_registers(&[registers::lsm6dso::FSM_ENABLE_A, a, b])
+        })
+    }
+
+    /// Routes the interrupts of `programs` to `pin`, where bit n stands for program n + 1.
+    /// This overwrites FSM_INT1_A and FSM_INT1_B (or FSM_INT2_A and FSM_INT2_B),
+    /// and if any program is routed, sets the INT1_EMB_FUNC or INT2_EMB_FUNC bit of MD1_CFG or MD2_CFG,
+    /// which is shared with the other embedded functions and so isn't cleared otherwise.
+    pub fn route_fsm_interrupt(
+        &mut self,
+        pin: InterruptPin,
+        programs: u16,
+    ) -> Result<(), Error<E>> {
+        self.require_fsm()?;
+        let (fsm_int, md_cfg) = match pin {
+            InterruptPin::Int1 => (registers::lsm6dso::FSM_INT1_A, registers::MD1_CFG),
+            InterruptPin::Int2 => (registers::lsm6dso::FSM_INT2_A, registers::MD2_CFG),
+        };
+        let [a, b] = programs.to_le_bytes();
+        self.with_embedded_bank(|this| this.write_registers(&[fsm_int, a, b]))?;
+        if programs != 0 {
+            self.set_bits(md_cfg, 0b10)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the outputs of all 16 programs, FSM_OUTS1 through FSM_OUTS16, in one burst.
+    pub fn read_fsm_outputs(&mut self) -> Result<[FsmOutput; 16], Error<E>> {
+        self.require_fsm()?;
+        let mut outs = [0; 16];
+        self.with_embedded_bank(|this| {
+            this.read_registers(registers::lsm6dso::FSM_OUTS1, &mut outs)
+        })?;
+        let mut outputs = [FsmOutput::default(); 16];
+        for (output, &outs) in outputs.iter_mut().zip(&outs) {
+            *output = FsmOutput::decode(outs);
+        }
+        Ok(outputs)
+    }
+
+    /// Reads which programs have raised their interrupt, where bit n stands for program n + 1,
+    /// from FSM_STATUS_A_MAINPAGE and FSM_STATUS_B_MAINPAGE without switching banks.
+    pub fn read_fsm_status(&mut self) -> Result<u16, Error<E>> {
+        self.require_fsm()?;
+        let mut status = [0; 2];
+        self.read_registers(registers::lsm6dso::FSM_STATUS_A_MAINPAGE, &mut status)?;
+        Ok(u16::from_le_bytes(status))
+    }
+
+    /// Writes to the page memory, which must be called in the embedded function bank.
+    /// Each byte goes through PAGE_VALUE, which steps PAGE_ADDRESS on by itself within a page.
+    /// Writing is enabled in PAGE_RW for the duration, and disabled again and page 0 selected
+    /// afterwards, even if a write fails.
+    fn write_pages(&mut self, address: u16, bytes: &[u8]) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::lsm6dso::PAGE_RW, 0b1000000)?;
+        let mut result = Ok(());
+        for (i, &byte) in bytes.iter().enumerate() {
+            let [offset, page] = address.wrapping_add(i as u16).to_le_bytes();
+            if i == 0 || offset == 0 {
+                // The lowest bit of PAGE_SEL has to be kept set
+                result = self
+                    .set_register_raw(registers::lsm6dso::PAGE_SEL, page << 4 | 1)
+                    .and_then(|()| self.set_register_raw(registers::lsm6dso::PAGE_ADDRESS, offset));
+            }
+            result =
+                result.and_then(|()| self.set_register_raw(registers::lsm6dso::PAGE_VALUE, byte));
+            if result.is_err() {
+                break;
+            }
+        }
+        let page_0 = self.set_register_raw(registers::lsm6dso::PAGE_SEL, 1);
+        let disable = self.set_register_raw(registers::lsm6dso::PAGE_RW, 0);
+        result?;
+        page_0?;
+        disable
+    }
+
+    fn require_fsm(&self) -> Result<(), Error<E>> {
+        if self.variant.has_dso_registers() {
+            Ok(())
+        } else {
+            Err(Error::Unsupported)
+        }
+    }
+}
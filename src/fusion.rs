@@ -0,0 +1,110 @@
+//! Helpers for turning sensor readings into orientation estimates.
+//!
+//! The helpers that need trigonometry, such as tilt compensation, also need the `micromath` or `libm` feature.
+
+#[cfg(any(feature = "micromath", feature = "libm"))]
+use crate::{math, Vector3};
+
+/// Integrates the z-axis gyroscope rate into a heading in degrees, wrapped to `[0, 360)`.
+/// A positive z rate increases the heading.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HeadingIntegrator {
+    heading: f32,
+    bias: f32,
+}
+
+impl HeadingIntegrator {
+    /// Creates an integrator starting at a heading of 0 with no bias correction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the z-axis rate, in degrees per second, that is subtracted from every sample,
+    /// e.g. the average rate measured while the sensor was still.
+    pub fn set_bias(&mut self, bias_dps: f32) {
+        self.bias = bias_dps;
+    }
+
+    /// Sets the current heading in degrees, e.g. to align it with a known direction.
+    pub fn set_heading(&mut self, heading: f32) {
+        self.heading = wrap_degrees(heading);
+    }
+
+    /// The current heading in degrees.
+    pub fn heading(&self) -> f32 {
+        self.heading
+    }
+
+    /// Adds the rotation from one gyroscope sample in degrees per second, such as one returned by
+    /// `LSM6::read_gyro_dps`, held for `dt` seconds. Returns the new heading.
+    pub fn update(&mut self, gyro_dps: (f32, f32, f32), dt: f32) -> f32 {
+        self.heading = wrap_degrees(self.heading + (gyro_dps.2 - self.bias) * dt);
+        self.heading
+    }
+
+    /// Like `HeadingIntegrator::update`, but then pulls the heading towards the tilt-compensated
+    /// magnetometer heading (see `tilt_compensated_heading`) by `gain`, a fraction between 0 and 1.
+    /// This keeps the gyroscope's short-term accuracy while the magnetometer cancels its drift.
+    /// Returns the new heading.
+    #[cfg(any(feature = "micromath", feature = "libm"))]
+    pub fn update_with_magnetometer(
+        &mut self,
+        accel: Vector3<f32>,
+        gyro_dps: Vector3<f32>,
+        mag: Vector3<f32>,
+        dt: f32,
+        gain: f32,
+    ) -> f32 {
+        self.update((gyro_dps.x, gyro_dps.y, gyro_dps.z), dt);
+        let mut error = tilt_compensated_heading(accel, mag) - self.heading;
+        // Take the short way around
+        if error > 180. {
+            error -= 360.;
+        } else if error < -180. {
+            error += 360.;
+        }
+        self.heading = wrap_degrees(self.heading + error * gain);
+        self.heading
+    }
+}
+
+/// The pitch and roll in degrees, from an accelerometer reading taken while the sensor isn't accelerating.
+/// This is `tilt_angles` as a tuple, so see `TiltAngles` for the conventions.
+#[cfg(any(feature = "micromath", feature = "libm"))]
+pub fn pitch_roll(accel: Vector3<f32>) -> (f32, f32) {
+    let angles = crate::tilt_angles(accel);
+    (angles.pitch, angles.roll)
+}
+
+/// The heading in degrees, wrapped to `[0, 360)`, from a magnetometer reading with its axes
+/// aligned to the LSM6's, corrected for tilt using an accelerometer reading.
+/// Like `HeadingIntegrator`, the heading increases counter-clockwise when viewed from above
+/// and is 0 when the x axis points to magnetic north, so a compass bearing is `360 - heading`.
+/// Both readings can be in any units.
+#[cfg(any(feature = "micromath", feature = "libm"))]
+pub fn tilt_compensated_heading(accel: Vector3<f32>, mag: Vector3<f32>) -> f32 {
+    let roll = math::atan2(accel.y, accel.z);
+    let pitch = math::atan2(-accel.x, math::sqrt(accel.y * accel.y + accel.z * accel.z));
+    let (sin_roll, cos_roll) = math::sin_cos(roll);
+    let (sin_pitch, cos_pitch) = math::sin_cos(pitch);
+    // The magnetic field rotated into the horizontal plane
+    let x = mag.x * cos_pitch + mag.y * sin_roll * sin_pitch + mag.z * cos_roll * sin_pitch;
+    let y = mag.y * cos_roll - mag.z * sin_roll;
+    wrap_degrees(math::atan2(-y, x).to_degrees())
+}
+
+/// Wraps an angle in degrees to `[0, 360)`.
+fn wrap_degrees(degrees: f32) -> f32 {
+    let wrapped = degrees % 360.;
+    if wrapped < 0. {
+        // Tiny negative angles can round up to exactly 360 here.
+        let wrapped = wrapped + 360.;
+        if wrapped >= 360. {
+            0.
+        } else {
+            wrapped
+        }
+    } else {
+        wrapped
+    }
+}
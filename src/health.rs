@@ -0,0 +1,104 @@
+use crate::{
+    ctrl1_xl, ctrl2_g, interface::RegisterInterface, registers, AccelerometerMode, Error,
+    GyroscopeMode, LSM6, SELF_CLEARING_BITS,
+};
+
+/// The findings of `LSM6::health_check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Health {
+    /// A transaction failed during the check. The findings that depend on it are `false` or `None`.
+    pub bus_error: bool,
+    /// WHO_AM_I was read and matches the driver's variant.
+    pub identified: bool,
+    /// Whether the accelerometer has new data, or `None` if it is powered down or STATUS_REG couldn't be read.
+    pub accel_data_ready: Option<bool>,
+    /// Whether the gyroscope has new data, or `None` if it is powered down or STATUS_REG couldn't be read.
+    pub gyro_data_ready: Option<bool>,
+    /// STATUS_REG has bits set that are always clear, which happens when the bus is stuck high.
+    pub status_stuck: bool,
+}
+
+impl Health {
+    /// Whether nothing is wrong: the device was identified, STATUS_REG isn't stuck
+    /// and every sensor that is on has new data.
+    pub fn is_healthy(&self) -> bool {
+        self.identified
+            && !self.bus_error
+            && !self.status_stuck
+            && self.accel_data_ready != Some(false)
+            && self.gyro_data_ready != Some(false)
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Checks that the device still answers and produces data, in two single register reads.
+    /// This is cheap enough to call periodically, e.g. once a second from a watchdog.
+    /// Bus errors don't stop the check, but are recorded in `Health::bus_error`.
+    /// The data-ready findings only mean something if nothing else reads the outputs
+    /// more often than one sample period before the check.
+    pub fn health_check(&mut self) -> Health {
+        let mut bus_error = false;
+        let identified = match self.read_register_raw(registers::WHO_AM_I) {
+            Ok(id) => id == self.variant.who_am_i(),
+            Err(_) => {
+                bus_error = true;
+                false
+            }
+        };
+        let status = match self.read_register_raw(registers::STATUS_REG) {
+            Ok(status) => Some(status),
+            Err(_) => {
+                bus_error = true;
+                None
+            }
+        };
+        let accel_on = self.accel_mode != AccelerometerMode::PowerDown;
+        let gyro_on = self.gyro_mode != GyroscopeMode::PowerDown;
+        Health {
+            bus_error,
+            identified,
+            accel_data_ready: status.filter(|_| accel_on).map(|s| s & 1 != 0),
+            gyro_data_ready: status.filter(|_| gyro_on).map(|s| s & 0b10 != 0),
+            status_stuck: status.is_some_and(|s| s & 0b11110000 != 0),
+        }
+    }
+
+    /// Checks that the device is still there and hasn't reset, as one assertion that can be called periodically,
+    /// e.g. from a watchdog. This reads WHO_AM_I, then CTRL1_XL through CTRL3_C in one burst.
+    /// A bus failure is returned as `Error::Bus`, and a WHO_AM_I that doesn't match the variant
+    /// as `Error::WrongChipId` (0x00 or 0xFF usually means the device fell off the bus).
+    /// CTRL1_XL and CTRL2_G are compared against the driver's modes and scales, and CTRL3_C against
+    /// the shadow cache if it is cached, or otherwise only its auto-increment bit against what the driver set.
+    /// A reset powers both sensors down, so it is caught whenever either was on; with both powered down
+    /// and CTRL3_C at its reset value, a reset device can't be told apart from one that didn't.
+    /// A mismatch is returned as `Error::ConfigurationLost`.
+    pub fn check(&mut self) -> Result<(), Error<E>> {
+        let found = self.read_register_raw(registers::WHO_AM_I)?;
+        if found != self.variant.who_am_i() {
+            return Err(Error::WrongChipId {
+                address: self.address,
+                found,
+            });
+        }
+        let expected = self.cached(registers::CTRL3_C);
+        let mut ctrl = [0; 3];
+        self.read_registers(registers::CTRL1_XL, &mut ctrl)?;
+        let [ctrl1, ctrl2, ctrl3_c] = ctrl;
+        let modes_intact = ctrl1 == ctrl1_xl(self.accel_mode, self.accel_scale)
+            && ctrl2 == ctrl2_g(self.gyro_mode, self.gyro_scale);
+        let self_clearing = SELF_CLEARING_BITS
+            .iter()
+            .find(|(reg, _)| *reg == registers::CTRL3_C)
+            .map_or(0, |(_, bits)| *bits);
+        let ctrl3_intact = match expected {
+            Some(expected) => ctrl3_c & !self_clearing == expected,
+            None => (ctrl3_c & 0b100 != 0) == self.auto_increment,
+        };
+        if modes_intact && ctrl3_intact {
+            Ok(())
+        } else {
+            Err(Error::ConfigurationLost { ctrl3_c })
+        }
+    }
+}
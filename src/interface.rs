@@ -0,0 +1,200 @@
+//! The buses the driver can talk to the LSM6 over.
+//!
+//! Any i2c implementor can be used directly, and SPI is used through `SpiInterface`.
+//! With the `eh1` feature, buses implementing the embedded-hal 1.0 traits
+//! can be used through `Eh1` (i2c) and `Eh1Spi` (SPI).
+//! Anything else, such as a USB bridge or a simulated device in tests,
+//! can be used by implementing `RegisterInterface` for it.
+
+use embedded_hal::{
+    blocking::{
+        i2c::{Write, WriteRead},
+        spi,
+    },
+    digital::v2::OutputPin,
+};
+
+/// Register access over a bus, which everything in the driver is built on.
+/// Register addresses are auto-incremented over multi-byte transfers,
+/// which the LSM6 does as long as the IF_INC bit of CTRL3_C is set.
+/// This is implemented for every i2c implementor and for `SpiInterface`.
+/// Other transports only need to implement `write_registers` and `read_registers`,
+/// and are then used with `LSM6::from_interface`.
+pub trait RegisterInterface {
+    type Error;
+
+    /// Writes `bytes[1..]` to consecutive registers starting at `bytes[0]`, in one transaction.
+    /// `address` is the device's i2c slave address, which is ignored over SPI.
+    fn write_registers(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Reads consecutive registers starting at `start` into `buffer`, in one transaction.
+    /// `address` is the device's i2c slave address, which is ignored over SPI.
+    fn read_registers(
+        &mut self,
+        address: u8,
+        start: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Writes `value` to register `reg`.
+    fn write_register(&mut self, address: u8, reg: u8, value: u8) -> Result<(), Self::Error> {
+        self.write_registers(address, &[reg, value])
+    }
+
+    /// Reads register `reg`.
+    fn read_register(&mut self, address: u8, reg: u8) -> Result<u8, Self::Error> {
+        let mut value = [0];
+        self.read_registers(address, reg, &mut value)?;
+        Ok(value[0])
+    }
+}
+
+impl<E, I: Write<Error = E> + WriteRead<Error = E>> RegisterInterface for I {
+    type Error = E;
+
+    fn write_registers(&mut self, address: u8, bytes: &[u8]) -> Result<(), E> {
+        self.write(address, bytes)
+    }
+
+    fn read_registers(&mut self, address: u8, start: u8, buffer: &mut [u8]) -> Result<(), E> {
+        self.write_read(address, &[start], buffer)
+    }
+}
+
+/// An LSM6 on an SPI bus, selected with its own chip select pin.
+/// The bus has to be set up for SPI mode 3 and at most 10 MHz.
+pub struct SpiInterface<S, CS> {
+    spi: S,
+    cs: CS,
+}
+
+impl<S, CS> SpiInterface<S, CS> {
+    /// Pairs a bus with the LSM6's chip select pin, which should already be high.
+    pub fn new(spi: S, cs: CS) -> Self {
+        Self { spi, cs }
+    }
+
+    /// Gives back the bus and chip select pin.
+    pub fn release(self) -> (S, CS) {
+        (self.spi, self.cs)
+    }
+}
+
+/// Errors from an SPI transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SpiError<S, P> {
+    /// The SPI implementor returned an error.
+    Spi(S),
+    /// The chip select pin couldn't be set.
+    ChipSelect(P),
+}
+
+impl<S, CS, SE, PE> SpiInterface<S, CS>
+where
+    S: spi::Transfer<u8, Error = SE> + spi::Write<u8, Error = SE>,
+    CS: OutputPin<Error = PE>,
+{
+    /// Runs `f` with the chip select pin low, always raising it again afterwards.
+    fn select<T>(
+        &mut self,
+        f: impl FnOnce(&mut S) -> Result<T, SE>,
+    ) -> Result<T, SpiError<SE, PE>> {
+        self.cs.set_low().map_err(SpiError::ChipSelect)?;
+        let result = f(&mut self.spi).map_err(SpiError::Spi);
+        self.cs.set_high().map_err(SpiError::ChipSelect)?;
+        result
+    }
+}
+
+impl<S, CS, SE, PE> RegisterInterface for SpiInterface<S, CS>
+where
+    S: spi::Transfer<u8, Error = SE> + spi::Write<u8, Error = SE>,
+    CS: OutputPin<Error = PE>,
+{
+    type Error = SpiError<SE, PE>;
+
+    fn write_registers(&mut self, _address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        // The top bit of the register address is clear for writes
+        self.select(|spi| spi.write(bytes))
+    }
+
+    fn read_registers(
+        &mut self,
+        _address: u8,
+        start: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        // The top bit of the register address is set for reads,
+        // and the data is clocked out while zeros are sent
+        self.select(|spi| {
+            spi.write(&[start | 0b10000000])?;
+            for byte in buffer.iter_mut() {
+                *byte = 0;
+            }
+            spi.transfer(buffer)?;
+            Ok(())
+        })
+    }
+}
+
+/// Wraps an i2c bus implementing the embedded-hal 1.0 `I2c` trait,
+/// so it can be used wherever the driver takes an i2c implementor, e.g. `LSM6::new(Eh1(i2c))`.
+#[cfg(feature = "eh1")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eh1<I>(pub I);
+
+#[cfg(feature = "eh1")]
+impl<I: embedded_hal_1::i2c::I2c> Write for Eh1<I> {
+    type Error = I::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I::Error> {
+        self.0.write(address, bytes)
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<I: embedded_hal_1::i2c::I2c> WriteRead for Eh1<I> {
+    type Error = I::Error;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), I::Error> {
+        self.0.write_read(address, bytes, buffer)
+    }
+}
+
+/// Whether an embedded-hal 1.0 i2c error is a NACK, for use with `LSM6::new_with_classifier`.
+#[cfg(feature = "eh1")]
+pub fn is_nack<E: embedded_hal_1::i2c::Error>(error: &E) -> bool {
+    matches!(
+        error.kind(),
+        embedded_hal_1::i2c::ErrorKind::NoAcknowledge(_)
+    )
+}
+
+/// Wraps an SPI device implementing the embedded-hal 1.0 `SpiDevice` trait, which manages chip select itself.
+/// See `LSM6::new_spi_device`.
+#[cfg(feature = "eh1")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eh1Spi<S>(pub S);
+
+#[cfg(feature = "eh1")]
+impl<S: embedded_hal_1::spi::SpiDevice> RegisterInterface for Eh1Spi<S> {
+    type Error = S::Error;
+
+    fn write_registers(&mut self, _address: u8, bytes: &[u8]) -> Result<(), S::Error> {
+        self.0.write(bytes)
+    }
+
+    fn read_registers(
+        &mut self,
+        _address: u8,
+        start: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), S::Error> {
+        use embedded_hal_1::spi::Operation;
+        self.0.transaction(&mut [
+            Operation::Write(&[start | 0b10000000]),
+            Operation::Read(buffer),
+        ])
+    }
+}
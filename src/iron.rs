@@ -0,0 +1,78 @@
+//! Hard-iron and soft-iron correction of a magnetometer read by the sensor hub, which the LSM6DSL and LSM6DSM
+//! apply before the data reaches OUT_MAG_RAW_X_L through OUT_MAG_RAW_Z_H.
+
+use crate::{decode_xyz, interface::RegisterInterface, registers, ChipVariant, Error, LSM6};
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Sets the hard-iron offsets that are subtracted from the x, y and z axes of the magnetometer,
+    /// in its own units, and turns the correction on with the IRON_EN bit of MASTER_CONFIG.
+    /// The offsets are written to MAG_OFFX_L through MAG_OFFZ_H in embedded function bank A in one burst.
+    /// The correction only runs on data fetched by the sensor hub, so it has to be set up and enabled too.
+    /// Only the LSM6DSL and LSM6DSM do this, so anywhere else this and the other iron methods return `Error::Unsupported`.
+    pub fn set_hard_iron_offset(&mut self, offset: (i16, i16, i16)) -> Result<(), Error<E>> {
+        self.require_iron_correction()?;
+        let [x_l, x_h] = offset.0.to_le_bytes();
+        let [y_l, y_h] = offset.1.to_le_bytes();
+        let [z_l, z_h] = offset.2.to_le_bytes();
+        let bytes = [registers::lsm6dsl::MAG_OFFX_L, x_l, x_h, y_l, y_h, z_l, z_h];
+        self.with_embedded_bank(|this| this.write_registers(&bytes))?;
+        self.set_bits(registers::lsm6dsl::MASTER_CONFIG, 0b10)?;
+        Ok(())
+    }
+
+    /// Reads the hard-iron offsets back from MAG_OFFX_L through MAG_OFFZ_H.
+    pub fn hard_iron_offset(&mut self) -> Result<(i16, i16, i16), Error<E>> {
+        self.require_iron_correction()?;
+        let mut bytes = [0; 6];
+        self.with_embedded_bank(|this| {
+            this.read_registers(registers::lsm6dsl::MAG_OFFX_L, &mut bytes)
+        })?;
+        Ok(decode_xyz(&bytes))
+    }
+
+    /// Sets the soft-iron matrix that the magnetometer's axes are multiplied by, after the hard-iron offsets
+    /// are subtracted, and turns the correction on with the SOFT_EN bit of CTRL9_XL.
+    /// Each coefficient is in eighths, so 8 is 1, and is stored in sign-magnitude form in one of
+    /// MAG_SI_XX through MAG_SI_ZZ, row by row, so -128 can't be stored and is `Error::InvalidConfig`.
+    pub fn set_soft_iron_matrix(&mut self, matrix: [[i8; 3]; 3]) -> Result<(), Error<E>> {
+        self.require_iron_correction()?;
+        let mut bytes = [0; 10];
+        bytes[0] = registers::lsm6dsl::MAG_SI_XX;
+        for (byte, &coefficient) in bytes[1..].iter_mut().zip(matrix.iter().flatten()) {
+            if coefficient == i8::MIN {
+                return Err(Error::InvalidConfig);
+            }
+            let sign = if coefficient < 0 { 0b10000000 } else { 0 };
+            *byte = sign | coefficient.unsigned_abs();
+        }
+        self.with_embedded_bank(|this| this.write_registers(&bytes))?;
+        self.set_bits(registers::CTRL9_XL, 0b100)?;
+        Ok(())
+    }
+
+    /// Reads the soft-iron matrix back from MAG_SI_XX through MAG_SI_ZZ, in eighths.
+    pub fn soft_iron_matrix(&mut self) -> Result<[[i8; 3]; 3], Error<E>> {
+        self.require_iron_correction()?;
+        let mut bytes = [0; 9];
+        self.with_embedded_bank(|this| {
+            this.read_registers(registers::lsm6dsl::MAG_SI_XX, &mut bytes)
+        })?;
+        let mut matrix = [[0; 3]; 3];
+        for (coefficient, &byte) in matrix.iter_mut().flatten().zip(&bytes) {
+            let magnitude = (byte & 0b1111111) as i8;
+            *coefficient = if byte & 0b10000000 != 0 {
+                -magnitude
+            } else {
+                magnitude
+            };
+        }
+        Ok(matrix)
+    }
+
+    fn require_iron_correction(&self) -> Result<(), Error<E>> {
+        match self.variant {
+            ChipVariant::Lsm6dsl | ChipVariant::Lsm6dsm => Ok(()),
+            _ => Err(Error::Unsupported),
+        }
+    }
+}
@@ -1,14 +1,106 @@
+//! A driver for the LSM6DS33 and related LSM6 gyroscopes and accelerometers,
+//! built on the i2c and SPI traits from embedded-hal.
+//!
+//! # Migrating from 0.1
+//!
+//! - `LSM6<E, I>` is now `LSM6<I>`. The error type is always the bus's, so it is no longer spelled out.
+//! - Fallible methods return `Result<T, Error<E>>`, where `E` is the bus's error type,
+//!   instead of `Result<T, E>`. Bus errors are wrapped in `Error::Bus`.
+//! - `LSM6::new` returns `Result<LSM6<I>, Error<E>>` instead of `Result<Option<LSM6<E, I>>, E>`.
+//!   The old `Ok(None)` is now `Err(Error::WrongChipId { .. })` or `Err(Error::NotDetected)`.
+//! - Accelerometer and gyroscope readings are still `(i16, i16, i16)`;
+//!   `LSM6::read_measurement` returns the new `Measurement` type.
+
 #![no_std]
 
+#[cfg(feature = "accelerometer")]
+mod accel_traits;
+#[cfg(feature = "async")]
+mod asynch;
+mod cache;
+mod calibration;
+mod config;
+mod decimate;
+mod den;
+mod detector;
+mod embedded;
+mod fifo;
+mod filter;
+#[cfg(feature = "fixed")]
+mod fixed_point;
+mod fsm;
+#[cfg(feature = "fusion")]
+pub mod fusion;
+mod health;
+pub mod interface;
+mod iron;
+#[cfg(any(feature = "micromath", feature = "libm"))]
+mod math;
+#[cfg(feature = "mlc")]
+mod mlc;
+mod motion;
+mod mounting;
+mod odr;
+mod orientation;
 pub mod registers;
+mod rounding;
+mod selftest;
+mod sensor_hub;
+mod split;
+mod sync;
+#[cfg(any(feature = "micromath", feature = "libm"))]
+mod tilt;
+mod trace;
+pub mod transfer;
+#[cfg(feature = "uom")]
+mod units;
+
+#[cfg(feature = "async")]
+pub use asynch::{AsyncLSM6, EventSources};
+pub use calibration::CalibrationError;
+pub use config::{Config, ConfigBuilder, ConfigError, ConfigStep, InterruptConfig, InterruptPin};
+pub use decimate::{Decimate, Decimator};
+pub use den::DenSensor;
+pub use detector::{MotionDetector, MotionEvent, MotionState, ShockDetector, ShockEvent};
+pub use embedded::EmbFuncStatus;
+pub use fifo::{
+    FifoEvent, FifoMode, FifoStatus, FifoStream, FifoWord, TemperatureBatchRate,
+    TimestampDecimation,
+};
+pub use filter::FilterPath;
+pub use fsm::{FsmOdr, FsmOutput};
+pub use health::Health;
+pub use interface::RegisterInterface;
+pub use motion::{InactivityMode, TapTiming};
+pub use mounting::{Axis, AxisMapping, AxisMappingError};
+pub use odr::{MeasuredOdr, Sensor};
+pub use orientation::Orientation;
+pub use registers::{IntoRegister, Register};
+pub use rounding::Rounding;
+pub use selftest::{SelfTestLimits, SelfTestResult};
+pub use sensor_hub::{SensorHubConfig, SensorHubSlave, SensorHubTrigger, SlaveSlot};
+pub use split::{AccelHandle, GyroHandle};
+pub use sync::{SyncResolution, SyncTimeFrame};
+#[cfg(any(feature = "micromath", feature = "libm"))]
+pub use tilt::{tilt_angles, OrientationFilter, OrientationState, TiltAngles};
 
-use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+use embedded_hal::{
+    blocking::{
+        delay::DelayMs,
+        i2c::{Write, WriteRead},
+        spi,
+    },
+    digital::v2::{InputPin, OutputPin},
+};
+use interface::{SpiError, SpiInterface};
 
 const LSM6_SA0_HIGH_ADDRESS: u8 = 0b1101011;
 const LSM6_SA0_LOW_ADDRESS: u8 = 0b1101010;
-const LSM6_WHO_ID: u8 = 0x69;
 
 /// Different modes and frequency that the accelerometer can run at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AccelerometerMode {
     PowerDown,
     LowPower13Hz,
@@ -39,9 +131,65 @@ impl AccelerometerMode {
             AccelerometerMode::HighPerformance6660Hz => 0b1010,
         }
     }
+
+    fn from_bitcode(bits: u8) -> Option<Self> {
+        [
+            AccelerometerMode::PowerDown,
+            AccelerometerMode::LowPower13Hz,
+            AccelerometerMode::LowPower26Hz,
+            AccelerometerMode::LowPower52Hz,
+            AccelerometerMode::Normal104Hz,
+            AccelerometerMode::Normal208Hz,
+            AccelerometerMode::HighPerformance416Hz,
+            AccelerometerMode::HighPerformance833Hz,
+            AccelerometerMode::HighPerformance1660Hz,
+            AccelerometerMode::HighPerformance3330Hz,
+            AccelerometerMode::HighPerformance6660Hz,
+        ]
+        .iter()
+        .copied()
+        .find(|mode| mode.to_bitcode() == bits)
+    }
+
+    /// The nominal output data rate of this mode in Hz, or 0 when powered down.
+    pub fn odr_hz(self) -> f32 {
+        match self {
+            AccelerometerMode::PowerDown => 0.,
+            AccelerometerMode::LowPower13Hz => 12.5,
+            AccelerometerMode::LowPower26Hz => 26.,
+            AccelerometerMode::LowPower52Hz => 52.,
+            AccelerometerMode::Normal104Hz => 104.,
+            AccelerometerMode::Normal208Hz => 208.,
+            AccelerometerMode::HighPerformance416Hz => 416.,
+            AccelerometerMode::HighPerformance833Hz => 833.,
+            AccelerometerMode::HighPerformance1660Hz => 1660.,
+            AccelerometerMode::HighPerformance3330Hz => 3330.,
+            AccelerometerMode::HighPerformance6660Hz => 6660.,
+        }
+    }
+
+    /// The approximate typical current draw of the LSM6DS33's accelerometer in this mode.
+    fn typical_current_ua(self) -> u32 {
+        match self {
+            AccelerometerMode::PowerDown => 0,
+            AccelerometerMode::LowPower13Hz => 9,
+            AccelerometerMode::LowPower26Hz => 17,
+            AccelerometerMode::LowPower52Hz => 31,
+            AccelerometerMode::Normal104Hz => 44,
+            AccelerometerMode::Normal208Hz => 85,
+            AccelerometerMode::HighPerformance416Hz
+            | AccelerometerMode::HighPerformance833Hz
+            | AccelerometerMode::HighPerformance1660Hz
+            | AccelerometerMode::HighPerformance3330Hz
+            | AccelerometerMode::HighPerformance6660Hz => 240,
+        }
+    }
 }
 
 /// Different modes and frequency that the gyroscope can run at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GyroscopeMode {
     PowerDown,
     LowPower13Hz,
@@ -68,103 +216,1257 @@ impl GyroscopeMode {
             GyroscopeMode::HighPerformance1660Hz => 0b1000,
         }
     }
+
+    fn from_bitcode(bits: u8) -> Option<Self> {
+        [
+            GyroscopeMode::PowerDown,
+            GyroscopeMode::LowPower13Hz,
+            GyroscopeMode::LowPower26Hz,
+            GyroscopeMode::LowPower52Hz,
+            GyroscopeMode::Normal104Hz,
+            GyroscopeMode::Normal208Hz,
+            GyroscopeMode::HighPerformance416Hz,
+            GyroscopeMode::HighPerformance833Hz,
+            GyroscopeMode::HighPerformance1660Hz,
+        ]
+        .iter()
+        .copied()
+        .find(|mode| mode.to_bitcode() == bits)
+    }
+
+    /// The nominal output data rate of this mode in Hz, or 0 when powered down.
+    pub fn odr_hz(self) -> f32 {
+        match self {
+            GyroscopeMode::PowerDown => 0.,
+            GyroscopeMode::LowPower13Hz => 12.5,
+            GyroscopeMode::LowPower26Hz => 26.,
+            GyroscopeMode::LowPower52Hz => 52.,
+            GyroscopeMode::Normal104Hz => 104.,
+            GyroscopeMode::Normal208Hz => 208.,
+            GyroscopeMode::HighPerformance416Hz => 416.,
+            GyroscopeMode::HighPerformance833Hz => 833.,
+            GyroscopeMode::HighPerformance1660Hz => 1660.,
+        }
+    }
+
+    /// The approximate typical current draw of the LSM6DS33's gyroscope in this mode.
+    fn typical_current_ua(self) -> u32 {
+        match self {
+            GyroscopeMode::PowerDown => 0,
+            GyroscopeMode::LowPower13Hz => 340,
+            GyroscopeMode::LowPower26Hz => 390,
+            GyroscopeMode::LowPower52Hz => 500,
+            GyroscopeMode::Normal104Hz => 620,
+            GyroscopeMode::Normal208Hz => 885,
+            GyroscopeMode::HighPerformance416Hz
+            | GyroscopeMode::HighPerformance833Hz
+            | GyroscopeMode::HighPerformance1660Hz => 1010,
+        }
+    }
 }
 
-pub struct LSM6<E, I: Read<Error = E> + Write<Error = E> + WriteRead<Error = E>> {
-    address: u8,
-    i2c: I,
+/// The members of the LSM6 family that this driver can talk to.
+/// The register map used throughout the driver is the LSM6DS33's,
+/// which the other parts share for everything that isn't checked against `LSM6::variant`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChipVariant {
+    Lsm6ds33,
+    Lsm6dsl,
+    Lsm6dsm,
+    Lsm6dso,
+    /// The LSM6DSO with a machine learning core. It shares the LSM6DSO's WHO_AM_I value and register map,
+    /// so it has to be declared with `LSM6::new_variant`.
+    Lsm6dsox,
 }
 
-impl<E, I: Clone + Read<Error = E> + Write<Error = E> + WriteRead<Error = E>> Clone for LSM6<E, I> {
-    fn clone(&self) -> Self {
-        LSM6 {
-            address: self.address.clone(),
-            i2c: self.i2c.clone(),
+impl ChipVariant {
+    /// The value of this part's WHO_AM_I register.
+    pub fn who_am_i(self) -> u8 {
+        match self {
+            ChipVariant::Lsm6ds33 => 0x69,
+            ChipVariant::Lsm6dsl | ChipVariant::Lsm6dsm => 0x6A,
+            ChipVariant::Lsm6dso | ChipVariant::Lsm6dsox => 0x6C,
+        }
+    }
+
+    /// The part with the given WHO_AM_I value.
+    /// The LSM6DSL and LSM6DSM share a value, so this returns `Lsm6dsl` for both,
+    /// and likewise `Lsm6dso` for the LSM6DSO and LSM6DSOX;
+    /// use `LSM6::new_variant` to declare an LSM6DSM or LSM6DSOX.
+    pub fn from_who_am_i(id: u8) -> Option<Self> {
+        match id {
+            0x69 => Some(ChipVariant::Lsm6ds33),
+            0x6A => Some(ChipVariant::Lsm6dsl),
+            0x6C => Some(ChipVariant::Lsm6dso),
+            _ => None,
+        }
+    }
+
+    /// Converts a raw temperature reading from this part into degrees Celsius.
+    /// A reading of 0 is 25 °C, with 16 LSB per degree on the LSM6DS33 and 256 on the others.
+    /// This needs no device, so it can be used to convert logged raw data offline.
+    pub fn to_celsius(self, raw: i16) -> f32 {
+        let lsb_per_degree = match self {
+            ChipVariant::Lsm6ds33 => 16.,
+            _ => 256.,
+        };
+        25. + raw as f32 / lsb_per_degree
+    }
+
+    /// Whether this part uses the LSM6DSO's register map where it differs from the LSM6DS33's,
+    /// such as its tagged FIFO and embedded function bank.
+    pub(crate) fn has_dso_registers(self) -> bool {
+        matches!(self, ChipVariant::Lsm6dso | ChipVariant::Lsm6dsox)
+    }
+}
+
+/// Bits that clear themselves after being written, and so can't be verified.
+const SELF_CLEARING_BITS: [(u8, u8); 2] = [
+    // BOOT and SW_RESET
+    (registers::CTRL3_C, 0b10000001),
+    // PEDO_RST_STEP
+    (registers::CTRL10_C, 0b10),
+];
+
+/// Registers whose reads change the device's state, and so can't be safely retried:
+/// the FIFO outputs, which a read consumes, and the interrupt sources, which a read clears.
+/// ALL_INT_SRC is only an interrupt source on the LSM6DSO.
+const NON_IDEMPOTENT_READS: [u8; 7] = [
+    registers::FIFO_DATA_OUT_L,
+    registers::lsm6dso::FIFO_DATA_OUT_TAG,
+    registers::WAKE_UP_SRC,
+    registers::TAP_SRC,
+    registers::D6D_SRC,
+    registers::FUNC_SRC,
+    registers::lsm6dso::ALL_INT_SRC,
+];
+
+/// The slave address of the LSM6, selected by the level of its SA0 pin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SlaveAddr {
+    /// SA0 is tied low (0x6A).
+    Low,
+    /// SA0 is tied high (0x6B).
+    High,
+}
+
+impl SlaveAddr {
+    fn to_address(self) -> u8 {
+        match self {
+            SlaveAddr::Low => LSM6_SA0_LOW_ADDRESS,
+            SlaveAddr::High => LSM6_SA0_HIGH_ADDRESS,
+        }
+    }
+}
+
+/// Output data rates shared by the sensors and the FIFO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataRate {
+    Hz13,
+    Hz26,
+    Hz52,
+    Hz104,
+    Hz208,
+    Hz416,
+    Hz833,
+    Hz1660,
+    Hz3330,
+    Hz6660,
+}
+
+impl DataRate {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            DataRate::Hz13 => 1,
+            DataRate::Hz26 => 0b10,
+            DataRate::Hz52 => 0b11,
+            DataRate::Hz104 => 0b100,
+            DataRate::Hz208 => 0b101,
+            DataRate::Hz416 => 0b110,
+            DataRate::Hz833 => 0b111,
+            DataRate::Hz1660 => 0b1000,
+            DataRate::Hz3330 => 0b1001,
+            DataRate::Hz6660 => 0b1010,
+        }
+    }
+
+    fn from_bitcode(bits: u8) -> Option<Self> {
+        [
+            DataRate::Hz13,
+            DataRate::Hz26,
+            DataRate::Hz52,
+            DataRate::Hz104,
+            DataRate::Hz208,
+            DataRate::Hz416,
+            DataRate::Hz833,
+            DataRate::Hz1660,
+            DataRate::Hz3330,
+            DataRate::Hz6660,
+        ]
+        .iter()
+        .copied()
+        .find(|rate| rate.to_bitcode() == bits)
+    }
+}
+
+/// Different full-scale ranges that the accelerometer can measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccelerometerScale {
+    G2,
+    G4,
+    G8,
+    G16,
+}
+
+impl AccelerometerScale {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            AccelerometerScale::G2 => 0,
+            AccelerometerScale::G4 => 0b10,
+            AccelerometerScale::G8 => 0b11,
+            AccelerometerScale::G16 => 1,
+        }
+    }
+
+    fn from_bitcode(bits: u8) -> Self {
+        match bits & 0b11 {
+            0 => AccelerometerScale::G2,
+            0b10 => AccelerometerScale::G4,
+            0b11 => AccelerometerScale::G8,
+            _ => AccelerometerScale::G16,
+        }
+    }
+
+    /// The typical sensitivity in micro-g per LSB.
+    /// This is the value every conversion in the driver is based on.
+    pub fn micro_g_per_lsb(self) -> i32 {
+        match self {
+            AccelerometerScale::G2 => 61,
+            AccelerometerScale::G4 => 122,
+            AccelerometerScale::G8 => 244,
+            AccelerometerScale::G16 => 488,
+        }
+    }
+
+    /// The full-scale range in milli-g.
+    pub fn full_scale_mg(self) -> u32 {
+        match self {
+            AccelerometerScale::G2 => 2000,
+            AccelerometerScale::G4 => 4000,
+            AccelerometerScale::G8 => 8000,
+            AccelerometerScale::G16 => 16000,
+        }
+    }
+
+    /// The typical sensitivity in milli-g per LSB.
+    pub fn sensitivity_mg_per_lsb(self) -> f32 {
+        self.micro_g_per_lsb() as f32 / 1000.
+    }
+
+    /// Converts a raw accelerometer reading taken at this scale into g.
+    /// This needs no device, so it can be used to convert logged raw data offline.
+    pub fn to_g(&self, raw: i16) -> f32 {
+        raw as f32 * self.sensitivity_mg_per_lsb() / 1000.
+    }
+}
+
+/// Different full-scale ranges that the gyroscope can measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GyroscopeScale {
+    Dps125,
+    Dps245,
+    Dps500,
+    Dps1000,
+    Dps2000,
+}
+
+impl GyroscopeScale {
+    /// The FS_G and FS_125 bits of CTRL2_G.
+    fn to_bitcode(self) -> u8 {
+        match self {
+            GyroscopeScale::Dps125 => 1,
+            GyroscopeScale::Dps245 => 0,
+            GyroscopeScale::Dps500 => 0b10,
+            GyroscopeScale::Dps1000 => 0b100,
+            GyroscopeScale::Dps2000 => 0b110,
+        }
+    }
+
+    fn from_bitcode(bits: u8) -> Self {
+        if bits & 1 == 1 {
+            return GyroscopeScale::Dps125;
+        }
+        match bits & 0b110 {
+            0 => GyroscopeScale::Dps245,
+            0b10 => GyroscopeScale::Dps500,
+            0b100 => GyroscopeScale::Dps1000,
+            _ => GyroscopeScale::Dps2000,
+        }
+    }
+
+    /// The typical sensitivity in eighths of a milli-degree per second per LSB.
+    /// This is the value every conversion in the driver is based on.
+    pub fn eighth_mdps_per_lsb(self) -> i32 {
+        match self {
+            GyroscopeScale::Dps125 => 35,
+            GyroscopeScale::Dps245 => 70,
+            GyroscopeScale::Dps500 => 140,
+            GyroscopeScale::Dps1000 => 280,
+            GyroscopeScale::Dps2000 => 560,
+        }
+    }
+
+    /// The typical sensitivity in milli-degrees per second per LSB.
+    pub fn sensitivity_mdps_per_lsb(self) -> f32 {
+        self.eighth_mdps_per_lsb() as f32 / 8.
+    }
+
+    /// Converts a raw gyroscope reading taken at this scale into degrees per second.
+    /// This needs no device, so it can be used to convert logged raw data offline.
+    pub fn to_dps(&self, raw: i16) -> f32 {
+        raw as f32 * self.sensitivity_mdps_per_lsb() / 1000.
+    }
+}
+
+/// Errors that can occur while using the LSM6.
+/// Bus errors from the i2c implementor are wrapped in `Error::Bus`,
+/// so `?` can be used on them directly inside the driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// The i2c implementor returned an error.
+    Bus(E),
+    /// No device answered at either of the LSM6's slave addresses.
+    NotDetected,
+    /// A device answered at `address`, but its WHO_AM_I register read `found`.
+    /// Over SPI, `address` is 0.
+    WrongChipId { address: u8, found: u8 },
+    /// An interrupt pin passed to the driver could not be read.
+    Pin,
+    /// The feature is not available on the connected `ChipVariant`.
+    Unsupported,
+    /// The device did not produce data in the expected time.
+    Timeout,
+    /// The settings given to `ConfigBuilder` contradict each other,
+    /// or the sensors are not set up for the requested operation.
+    InvalidConfig,
+    /// The register at this address is reserved.
+    ReservedRegister(u8),
+    /// The register can only be read.
+    ReadOnlyRegister(Register),
+    /// The gyroscope can't run at this rate, since it tops out at `DataRate::Hz1660`.
+    GyroRateUnavailable(DataRate),
+    /// CTRL1_XL, CTRL2_G or CTRL3_C no longer holds what the driver set, found by `LSM6::check`,
+    /// which also gives the CTRL3_C it read.
+    /// The device has most likely reset, so it needs configuring again, e.g. with `LSM6::reapply`.
+    ConfigurationLost { ctrl3_c: u8 },
+    /// With write verification on, register `reg` read back as `read` after `wrote` was written to it.
+    VerificationFailed { reg: u8, wrote: u8, read: u8 },
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(e: E) -> Self {
+        Error::Bus(e)
+    }
+}
+
+/// The outcome of reading the WHO_AM_I register at one slave address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AddressProbe<E> {
+    pub address: u8,
+    /// The WHO_AM_I value that came back, or the error if the transaction failed.
+    pub result: Result<u8, E>,
+}
+
+impl<E> AddressProbe<E> {
+    /// Whether an LSM6 answered at this address.
+    pub fn is_lsm6(&self) -> bool {
+        self.variant().is_some()
+    }
+
+    /// The variant matching the WHO_AM_I value that came back, if any.
+    pub fn variant(&self) -> Option<ChipVariant> {
+        match self.result {
+            Ok(id) => ChipVariant::from_who_am_i(id),
+            Err(_) => None,
+        }
+    }
+}
+
+/// What was found at each of the LSM6's slave addresses, as returned by `probe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ProbeReport<E> {
+    /// The probe of the SA0 high address (0x6B), which `LSM6::new` tries first.
+    pub high: AddressProbe<E>,
+    /// The probe of the SA0 low address (0x6A).
+    pub low: AddressProbe<E>,
+}
+
+impl<E> ProbeReport<E> {
+    /// The address `LSM6::new` would pick, if any.
+    pub fn lsm6_address(&self) -> Option<u8> {
+        if self.high.is_lsm6() {
+            Some(self.high.address)
+        } else if self.low.is_lsm6() {
+            Some(self.low.address)
+        } else {
+            None
+        }
+    }
+}
+
+/// An LSM6 found on the bus by `detect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DetectedLsm6 {
+    pub address: u8,
+    /// The raw value of the WHO_AM_I register.
+    pub who_am_i: u8,
+    /// The part `who_am_i` belongs to, or `None` if no `ChipVariant` has that value.
+    pub variant: Option<ChipVariant>,
+}
+
+/// A set of measurements along the x, y and z axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl Vector3<i16> {
+    /// The squared magnitude of a raw reading, which is enough to compare against a threshold without a square root.
+    /// This is accumulated in an `i64`, since it can reach 3 × 32768², which overflows an `i32`.
+    pub fn magnitude_squared(&self) -> i64 {
+        let (x, y, z) = (self.x as i64, self.y as i64, self.z as i64);
+        x * x + y * y + z * z
+    }
+
+    /// How far the magnitude of a raw accelerometer reading taken at `scale` is from 1 g, in g.
+    /// At rest this should be close to 0, which makes it a quick check of a calibration,
+    /// and in free fall it approaches -1.
+    #[cfg(any(feature = "micromath", feature = "libm"))]
+    pub fn deviation_from_1g(&self, scale: AccelerometerScale) -> f32 {
+        let g = Vector3 {
+            x: scale.to_g(self.x),
+            y: scale.to_g(self.y),
+            z: scale.to_g(self.z),
+        };
+        g.magnitude() - 1.
+    }
+}
+
+impl Vector3<f32> {
+    /// The squared magnitude, which is enough to compare against a threshold without a square root.
+    pub fn magnitude_squared(&self) -> f32 {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    /// The magnitude, in the same units as the components.
+    #[cfg(any(feature = "micromath", feature = "libm"))]
+    pub fn magnitude(&self) -> f32 {
+        math::sqrt(self.magnitude_squared())
+    }
+}
+
+#[cfg(feature = "mint")]
+impl<T> From<Vector3<T>> for mint::Vector3<T> {
+    fn from(v: Vector3<T>) -> Self {
+        mint::Vector3 {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+impl<T> From<mint::Vector3<T>> for Vector3<T> {
+    fn from(v: mint::Vector3<T>) -> Self {
+        Vector3 {
+            x: v.x,
+            y: v.y,
+            z: v.z,
         }
     }
 }
 
-impl<E, I: Read<Error = E> + Write<Error = E> + WriteRead<Error = E>> LSM6<E, I> {
+/// A snapshot of every sensor output, taken in a single burst read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Measurement {
+    pub accel: Vector3<i16>,
+    pub gyro: Vector3<i16>,
+    pub temp: i16,
+}
+
+/// Lets a driver borrow a bus instead of owning it, as in `LSM6::new(BusRef(&mut i2c))`.
+/// embedded-hal 0.2 doesn't implement its i2c traits for `&mut` references, so this forwards them.
+/// Proxies from bus-sharing crates already implement the traits and can be given to the driver directly.
+pub struct BusRef<'a, I>(pub &'a mut I);
+
+impl<I: Write> Write for BusRef<'_, I> {
+    type Error = I::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(address, bytes)
+    }
+}
+
+impl<I: WriteRead> WriteRead for BusRef<'_, I> {
+    type Error = I::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.0.write_read(address, bytes, buffer)
+    }
+}
+
+/// A driver for an LSM6 on an i2c bus, or on an SPI bus through `interface::SpiInterface`.
+///
+/// The driver keeps no global state, so several can exist at once,
+/// such as two LSM6s on one bus with SA0 high and low (see `LSM6::new_with_address`).
+/// To share a bus, give each driver its own handle to it, e.g. a proxy from a bus-sharing crate,
+/// or lend it the bus with `BusRef`.
+/// `LSM6` is `Clone` when its bus handle is, but each clone keeps its own copy of the
+/// cached modes and scales, so configuring the device through one clone is not seen by the others.
+#[derive(Clone)]
+pub struct LSM6<I> {
+    address: u8,
+    bus: I,
+    variant: ChipVariant,
+    accel_mode: AccelerometerMode,
+    accel_scale: AccelerometerScale,
+    gyro_mode: GyroscopeMode,
+    gyro_scale: GyroscopeScale,
+    verify_writes: bool,
+    retries: u8,
+    retry_count: u32,
+    caching: bool,
+    cache: [u8; cache::CACHE_SIZE],
+    cache_valid: u32,
+    embedded_bank: bool,
+    auto_check_every: u16,
+    reads_since_check: u16,
+    resets_detected: u32,
+    fifo_pattern: u16,
+    fifo_set_words: u16,
+    auto_increment: bool,
+    mounting: AxisMapping,
+    paused: Option<(AccelerometerMode, GyroscopeMode)>,
+}
+
+impl<E, I: Write<Error = E> + WriteRead<Error = E>> LSM6<I> {
     /// Create a new `LSMD6` from an i2c implementor.
     /// This function will automatically set the slave address.
     /// This will also set the CTR3_C register of the LSM6 to 4,
     /// but it will NOT set the mode of either sensor or turn them on.
-    pub fn new(mut i2c: I) -> Result<Option<Self>, E> {
-        // Get the correct address for the lsm6 that is being used
-        let address = if test_lsm6_addr(&mut i2c, LSM6_SA0_HIGH_ADDRESS)? {
-            LSM6_SA0_HIGH_ADDRESS
-        } else if test_lsm6_addr(&mut i2c, LSM6_SA0_LOW_ADDRESS)? {
-            LSM6_SA0_LOW_ADDRESS
-        } else {
-            return Ok(None);
-        };
+    /// The driver assumes both sensors are at their power-on defaults
+    /// (powered down, ±2 g and ±245 dps) until a mode or scale is set.
+    /// If a device answers but reports the wrong WHO_AM_I value,
+    /// `Error::WrongChipId` is returned for the first such address.
+    /// Designs with SA0 tied to a fixed level should use `LSM6::new_with_address` instead,
+    /// which avoids probing the other address.
+    /// Any bus error stops the probe, including a NACK at the first address;
+    /// `LSM6::new_with_classifier` can skip NACKed addresses instead.
+    /// A bus error is always returned as `Error::Bus`, never as a missing device,
+    /// so init code can tell a stuck bus (worth recovering and retrying) from an absent or
+    /// unexpected sensor (`Error::NotDetected` or `Error::WrongChipId`).
+    /// A WHO_AM_I of 0xFF usually means SDA is held high with nothing driving it.
+    /// Any of the parts in `ChipVariant` are accepted, and the one found can be checked with `LSM6::variant`.
+    pub fn new(mut i2c: I) -> Result<Self, Error<E>> {
+        let (address, variant) = find(&mut i2c, ChipVariant::from_who_am_i, |_| false)?;
+        Self::with_address(i2c, address, variant)
+    }
+
+    /// Create a new `LSM6` like `LSM6::new`, for use right after the LSM6 is powered on.
+    /// This first waits the 20 ms the LSM6 needs to boot, then probes for it up to `attempts` times (at least once),
+    /// 5 ms apart, as long as the probe fails with a bus error (e.g. the device NACKs while still booting).
+    /// A device that answers with the wrong WHO_AM_I value fails immediately without retrying.
+    pub fn new_with_delay<D: DelayMs<u8>>(
+        mut i2c: I,
+        delay: &mut D,
+        attempts: u8,
+    ) -> Result<Self, Error<E>> {
+        delay.delay_ms(20);
+        let mut attempt = 1;
+        loop {
+            match find(&mut i2c, ChipVariant::from_who_am_i, |_| false) {
+                Ok((address, variant)) => return Self::with_address(i2c, address, variant),
+                Err(Error::Bus(_)) if attempt < attempts => {
+                    attempt += 1;
+                    delay.delay_ms(5);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
 
+    /// Create a new `LSM6` like `LSM6::new`, but only accept the given variant.
+    /// This is needed to tell apart parts that share a WHO_AM_I value, like the LSM6DSL and LSM6DSM.
+    pub fn new_variant(mut i2c: I, variant: ChipVariant) -> Result<Self, Error<E>> {
+        let (address, variant) = find(
+            &mut i2c,
+            |id| {
+                if id == variant.who_am_i() {
+                    Some(variant)
+                } else {
+                    None
+                }
+            },
+            |_| false,
+        )?;
+        Self::with_address(i2c, address, variant)
+    }
+
+    /// Create a new `LSM6` like `LSM6::new`, but carry on probing when an address isn't acknowledged.
+    /// Many HALs report a NACK as an error, which makes `LSM6::new` give up at the first address
+    /// if the LSM6 is at the second. `is_nack` tells those errors apart from genuine bus faults,
+    /// which are still returned straight away, since a stuck bus shouldn't look like a missing device.
+    /// If neither address is acknowledged, `Error::NotDetected` is returned.
+    /// For embedded-hal 1.0 buses wrapped in `interface::Eh1`, `interface::is_nack` does this with `Error::kind`.
+    pub fn new_with_classifier(mut i2c: I, is_nack: impl Fn(&E) -> bool) -> Result<Self, Error<E>> {
+        let (address, variant) = find(&mut i2c, ChipVariant::from_who_am_i, is_nack)?;
+        Self::with_address(i2c, address, variant)
+    }
+
+    /// Create a new `LSM6` that only talks to the given slave address.
+    /// This still checks the WHO_AM_I register and sets the CTRL3_C register like `LSM6::new`,
+    /// but never touches the other address, so it should be preferred when SA0 is hard-wired.
+    pub fn new_with_address(mut i2c: I, address: SlaveAddr) -> Result<Self, Error<E>> {
+        let address = address.to_address();
+        let found = read_who_am_i(&mut i2c, address)?;
+        let variant = identify(address, found)?;
+        Self::with_address(i2c, address, variant)
+    }
+
+    /// Checks whether an LSM6 is present on a borrowed bus without constructing a driver,
+    /// returning its address and variant if one is found.
+    /// Both addresses are tried in the same order as `LSM6::new`, and only WHO_AM_I is read,
+    /// so nothing is written to the device.
+    /// Like `LSM6::new`, this stops at the first bus error, so on a HAL that reports a NACK as an error
+    /// an LSM6 at the second address isn't found: use `LSM6::probe_with_classifier` there,
+    /// or the free `probe` function to see what happened at each address.
+    pub fn probe(i2c: &mut I) -> Result<Option<(u8, ChipVariant)>, E> {
+        Self::probe_with_classifier(i2c, |_| false)
+    }
+
+    /// Checks whether an LSM6 is present like `LSM6::probe`, but carries on to the other address
+    /// when one isn't acknowledged, like `LSM6::new_with_classifier`.
+    pub fn probe_with_classifier(
+        i2c: &mut I,
+        is_nack: impl Fn(&E) -> bool,
+    ) -> Result<Option<(u8, ChipVariant)>, E> {
+        Ok(detect_with_classifier(i2c, is_nack)?
+            .and_then(|found| Some((found.address, found.variant?))))
+    }
+
+    /// Rebuilds a driver from a bus previously returned by `LSM6::release`.
+    /// This does not probe for the device or write to it, so its configuration is left untouched.
+    /// Instead, WHO_AM_I, CTRL1_XL and CTRL2_G are read in one burst to recover
+    /// the variant and the cached modes and scales.
+    /// Reserved mode values are treated as powered down.
+    pub fn reattach(mut i2c: I, address: SlaveAddr) -> Result<Self, Error<E>> {
+        let address = address.to_address();
+        let mut regs = [0; 3];
+        i2c.write_read(address, &[registers::WHO_AM_I], &mut regs)?;
+        let variant = identify(address, regs[0])?;
+
+        let mut this = Self::from_parts(i2c, address, variant);
+        this.restore_modes(regs[1], regs[2]);
+        Ok(this)
+    }
+}
+
+impl<S, CS, SE, PE> LSM6<SpiInterface<S, CS>>
+where
+    S: spi::Transfer<u8, Error = SE> + spi::Write<u8, Error = SE>,
+    CS: OutputPin<Error = PE>,
+{
+    /// Create a new `LSM6` on an SPI bus, with `cs` as its chip select pin.
+    /// Instead of probing addresses, this checks the WHO_AM_I register,
+    /// returning `Error::WrongChipId` with an address of 0 if it doesn't match any `ChipVariant`.
+    /// Like `LSM6::new`, this sets the CTRL3_C register to 4 and doesn't turn on either sensor.
+    pub fn new_spi(spi: S, cs: CS) -> Result<Self, Error<SpiError<SE, PE>>> {
+        Self::from_interface(SpiInterface::new(spi, cs), 0)
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<S: embedded_hal_1::spi::SpiDevice> LSM6<interface::Eh1Spi<S>> {
+    /// Create a new `LSM6` on an embedded-hal 1.0 SPI device.
+    /// This checks the WHO_AM_I register and sets the CTRL3_C register like `LSM6::new_spi`.
+    pub fn new_spi_device(spi: S) -> Result<Self, Error<S::Error>> {
+        Self::from_interface(interface::Eh1Spi(spi), 0)
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Create a new `LSM6` on any `RegisterInterface`, such as a custom transport.
+    /// `address` is passed through to the interface, and is the slave address for i2c-like transports;
+    /// other transports can ignore it and be given 0.
+    /// Instead of probing addresses, this checks the WHO_AM_I register,
+    /// returning `Error::WrongChipId` if it doesn't match any `ChipVariant`.
+    /// Like `LSM6::new`, this sets the CTRL3_C register to 4 and doesn't turn on either sensor.
+    pub fn from_interface(mut bus: I, address: u8) -> Result<Self, Error<E>> {
+        let found = bus.read_register(address, registers::WHO_AM_I)?;
+        let variant = identify(address, found)?;
+        Self::with_address(bus, address, variant)
+    }
+
+    fn with_address(bus: I, address: u8, variant: ChipVariant) -> Result<Self, Error<E>> {
         // Set automatic register incrementing between reads
-        let mut this = Self { address, i2c };
-        this.set_register(registers::CTRL3_C, 4)?;
+        let mut this = Self::from_parts(bus, address, variant);
+        this.set_register_raw(registers::CTRL3_C, 4)?;
+
+        Ok(this)
+    }
+
+    /// Builds a driver assuming the device is at its power-on defaults, without touching the bus.
+    fn from_parts(bus: I, address: u8, variant: ChipVariant) -> Self {
+        Self {
+            address,
+            bus,
+            variant,
+            accel_mode: AccelerometerMode::PowerDown,
+            accel_scale: AccelerometerScale::G2,
+            gyro_mode: GyroscopeMode::PowerDown,
+            gyro_scale: GyroscopeScale::Dps245,
+            verify_writes: false,
+            retries: 0,
+            retry_count: 0,
+            caching: true,
+            cache: [0; cache::CACHE_SIZE],
+            cache_valid: 0,
+            embedded_bank: false,
+            auto_check_every: 0,
+            reads_since_check: 0,
+            resets_detected: 0,
+            fifo_pattern: 0,
+            fifo_set_words: 0,
+            auto_increment: true,
+            mounting: AxisMapping::IDENTITY,
+            paused: None,
+        }
+    }
+
+    /// Builds a driver for the same device and with the same state, but using a different bus.
+    fn with_bus<J>(&self, bus: J) -> LSM6<J> {
+        LSM6 {
+            address: self.address,
+            bus,
+            variant: self.variant,
+            accel_mode: self.accel_mode,
+            accel_scale: self.accel_scale,
+            gyro_mode: self.gyro_mode,
+            gyro_scale: self.gyro_scale,
+            verify_writes: self.verify_writes,
+            retries: self.retries,
+            retry_count: self.retry_count,
+            caching: self.caching,
+            cache: self.cache,
+            cache_valid: self.cache_valid,
+            embedded_bank: self.embedded_bank,
+            auto_check_every: self.auto_check_every,
+            reads_since_check: self.reads_since_check,
+            resets_detected: self.resets_detected,
+            fifo_pattern: self.fifo_pattern,
+            fifo_set_words: self.fifo_set_words,
+            auto_increment: self.auto_increment,
+            mounting: self.mounting,
+            paused: self.paused,
+        }
+    }
+
+    /// Sets the driver's modes and scales from the contents of CTRL1_XL and CTRL2_G.
+    /// Reserved mode values are treated as powered down.
+    fn restore_modes(&mut self, ctrl1_xl: u8, ctrl2_g: u8) {
+        self.accel_mode =
+            AccelerometerMode::from_bitcode(ctrl1_xl >> 4).unwrap_or(AccelerometerMode::PowerDown);
+        self.accel_scale = AccelerometerScale::from_bitcode(ctrl1_xl >> 2);
+        self.gyro_mode =
+            GyroscopeMode::from_bitcode(ctrl2_g >> 4).unwrap_or(GyroscopeMode::PowerDown);
+        self.gyro_scale = GyroscopeScale::from_bitcode(ctrl2_g >> 1);
+    }
 
-        Ok(Some(this))
+    /// Consumes the driver and gives back the bus, leaving the device as it is.
+    /// The driver can be rebuilt later with `LSM6::reattach`.
+    pub fn release(self) -> I {
+        self.bus
     }
 
     /// Turns on both sensors in high performance mode.
-    pub fn init_default(&mut self) -> Result<(), E> {
-        self.set_accel_mode(AccelerometerMode::HighPerformance1660Hz)?;
-        self.set_gyro_mode(GyroscopeMode::HighPerformance1660Hz)
+    pub fn init_default(&mut self) -> Result<(), Error<E>> {
+        ConfigBuilder::new()
+            .accel(AccelerometerMode::HighPerformance1660Hz, self.accel_scale)
+            .gyro(GyroscopeMode::HighPerformance1660Hz, self.gyro_scale)
+            .apply(self)
+            .map_err(|e| e.error)
+    }
+
+    /// Runs both sensors at `rate`, keeping their current scales, as sensor fusion expects.
+    /// Both CTRL1_XL and CTRL2_G are written in one burst.
+    /// The gyroscope only goes up to `DataRate::Hz1660`, so faster rates return
+    /// `Error::GyroRateUnavailable` without writing anything, rather than running the sensors apart.
+    /// Rates up to `DataRate::Hz52` are low-power modes, which return `Error::InvalidConfig`
+    /// unless both sensors' high performance modes are off; see `ConfigBuilder::apply`.
+    pub fn set_matched_odr(&mut self, rate: DataRate) -> Result<(), Error<E>> {
+        let (accel, gyro) = match (
+            AccelerometerMode::from_bitcode(rate.to_bitcode()),
+            GyroscopeMode::from_bitcode(rate.to_bitcode()),
+        ) {
+            (Some(accel), Some(gyro)) => (accel, gyro),
+            _ => return Err(Error::GyroRateUnavailable(rate)),
+        };
+        ConfigBuilder::new()
+            .accel(accel, self.accel_scale)
+            .gyro(gyro, self.gyro_scale)
+            .apply(self)
+            .map_err(|e| e.error)
+    }
+
+    /// Stops both sensors sampling, remembering their modes so `LSM6::resume` can restart them as they were.
+    /// Both are powered down in one burst, keeping their scales and every other setting.
+    /// Pausing again before resuming keeps the modes remembered the first time.
+    pub fn pause(&mut self) -> Result<(), Error<E>> {
+        let modes = self.paused.unwrap_or((self.accel_mode, self.gyro_mode));
+        ConfigBuilder::new()
+            .accel(AccelerometerMode::PowerDown, self.accel_scale)
+            .gyro(GyroscopeMode::PowerDown, self.gyro_scale)
+            .apply(self)
+            .map_err(|e| e.error)?;
+        self.paused = Some(modes);
+        Ok(())
+    }
+
+    /// Restarts both sensors in the modes they had when `LSM6::pause` was called,
+    /// with their current scales. Modes set while paused are overridden.
+    /// This does nothing if the sensors aren't paused.
+    pub fn resume(&mut self) -> Result<(), Error<E>> {
+        if let Some((accel, gyro)) = self.paused {
+            ConfigBuilder::new()
+                .accel(accel, self.accel_scale)
+                .gyro(gyro, self.gyro_scale)
+                .apply(self)
+                .map_err(|e| e.error)?;
+            self.paused = None;
+        }
+        Ok(())
+    }
+
+    /// Whether the sensors are paused by `LSM6::pause`.
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
     }
 
     /// Powers down both sensors.
-    pub fn full_power_down(&mut self) -> Result<(), E> {
+    pub fn full_power_down(&mut self) -> Result<(), Error<E>> {
         self.set_accel_mode(AccelerometerMode::PowerDown)?;
         self.set_gyro_mode(GyroscopeMode::PowerDown)
     }
 
-    /// This overwrites the CTRL1_XL register.
-    pub fn set_accel_mode(&mut self, mode: AccelerometerMode) -> Result<(), E> {
-        self.set_register(registers::CTRL1_XL, mode.to_bitcode() << 4)
+    /// Sets up the lowest-current configuration that still measures acceleration,
+    /// returning the resulting `LSM6::estimated_current_ua`.
+    /// This turns off the embedded functions (on the variants with a FUNC_EN bit) and the FIFO,
+    /// disables the accelerometer's high performance mode with the XL_HM_MODE bit of CTRL6_C,
+    /// then turns on BDU, runs the accelerometer at `AccelerometerMode::LowPower13Hz`
+    /// with its current scale, and powers down the gyroscope.
+    /// On the LSM6DSO only the FIFO_MODE bits of FIFO_CTRL4 are cleared, keeping its batching rates.
+    pub fn setup_low_power(&mut self) -> Result<u32, Error<E>> {
+        let builder = ConfigBuilder::new()
+            .accel(AccelerometerMode::LowPower13Hz, self.accel_scale)
+            .gyro(GyroscopeMode::PowerDown, self.gyro_scale)
+            .bdu(true);
+        let builder = if self.variant.has_dso_registers() {
+            self.modify_register(registers::lsm6dso::FIFO_CTRL4, 0b111, 0)?;
+            builder
+        } else {
+            self.clear_bits(registers::CTRL10_C, 0b100)?;
+            builder.fifo(FifoMode::Bypass, DataRate::Hz13)
+        };
+        self.set_bits(registers::CTRL6_C, 0b10000)?;
+        builder.apply(self).map_err(|e| e.error)?;
+        Ok(self.estimated_current_ua())
+    }
+
+    /// This overwrites the CTRL1_XL register, keeping the current scale.
+    pub fn set_accel_mode(&mut self, mode: AccelerometerMode) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL1_XL, ctrl1_xl(mode, self.accel_scale))?;
+        self.accel_mode = mode;
+        Ok(())
+    }
+
+    /// This overwrites the CTRL2_G register, keeping the current scale.
+    pub fn set_gyro_mode(&mut self, mode: GyroscopeMode) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL2_G, ctrl2_g(mode, self.gyro_scale))?;
+        self.gyro_mode = mode;
+        Ok(())
+    }
+
+    /// This overwrites the CTRL1_XL register, keeping the current mode.
+    pub fn set_accel_scale(&mut self, scale: AccelerometerScale) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL1_XL, ctrl1_xl(self.accel_mode, scale))?;
+        self.accel_scale = scale;
+        Ok(())
+    }
+
+    /// This overwrites the CTRL2_G register, keeping the current mode.
+    pub fn set_gyro_scale(&mut self, scale: GyroscopeScale) -> Result<(), Error<E>> {
+        self.set_register_raw(registers::CTRL2_G, ctrl2_g(self.gyro_mode, scale))?;
+        self.gyro_scale = scale;
+        Ok(())
+    }
+
+    /// Sets or clears only the FS_125 bit of CTRL2_G, keeping the ODR and FS_G bits as they are.
+    /// While FS_125 is set, the gyroscope runs at ±125 dps whatever FS_G[1:0] say,
+    /// and clearing it goes back to the scale those bits select.
+    /// The driver's gyroscope scale is updated to match.
+    pub fn set_gyro_fs_125(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        let bit = 0b10;
+        let prev = self.modify_register(registers::CTRL2_G, bit, if enabled { bit } else { 0 })?;
+        let new = if enabled { prev | bit } else { prev & !bit };
+        self.gyro_scale = GyroscopeScale::from_bitcode(new >> 1);
+        Ok(())
+    }
+
+    /// Estimates the current drawn by both sensors in their last set modes, in microamps.
+    /// This adds up approximate typical values for the LSM6DS33 and is only meant for planning,
+    /// e.g. sizing a battery; it ignores the embedded functions, the FIFO and the bus.
+    pub fn estimated_current_ua(&self) -> u32 {
+        self.accel_mode.typical_current_ua() + self.gyro_mode.typical_current_ua()
+    }
+
+    /// Estimates how long after the last mode or filter change to wait before trusting the outputs,
+    /// in microseconds. This is the longer of the two sensors' delays, or 0 if both are powered down.
+    /// Each sensor discards about 2 samples after an ODR change, and an enabled accelerometer LPF2
+    /// or gyroscope high-pass filter adds the time its output takes to settle within 1%.
+    /// The filter settings come from the shadow cache, since this doesn't touch the bus;
+    /// filters the cache doesn't know about count as off, so call `LSM6::sync_cache` first
+    /// if they were set outside the driver. The gyroscope's high-pass filter settles slowly,
+    /// taking up to a minute and a half at the LSM6DS33's lowest cutoff.
+    pub fn filter_settling_time_us(&self) -> u32 {
+        let accel = if self.accel_mode == AccelerometerMode::PowerDown {
+            0
+        } else {
+            samples_us(2 + self.accel_lpf2_samples(), self.accel_mode.odr_hz())
+        };
+        let gyro = if self.gyro_mode == GyroscopeMode::PowerDown {
+            0
+        } else {
+            samples_us(2, self.gyro_mode.odr_hz()).saturating_add(self.gyro_hpf_settling_us())
+        };
+        accel.max(gyro)
+    }
+
+    /// The samples the accelerometer's LPF2 takes to settle within 1%, or 0 if it is off.
+    fn accel_lpf2_samples(&self) -> u32 {
+        let ctrl8_xl = self.cached(registers::CTRL8_XL).unwrap_or(0);
+        // The cutoff is ODR / ratio
+        let ratio = if self.variant.has_dso_registers() {
+            if self.cached(registers::CTRL1_XL).unwrap_or(0) & 0b10 == 0 {
+                return 0;
+            }
+            [4, 10, 20, 45, 100, 200, 400, 800][(ctrl8_xl >> 5) as usize]
+        } else {
+            if ctrl8_xl & 0b10000000 == 0 {
+                return 0;
+            }
+            [50, 100, 9, 400][(ctrl8_xl >> 5 & 0b11) as usize]
+        };
+        // A first order filter settles within 1% after ln(100) / 2π ≈ 0.733 of ODR / cutoff samples
+        (ratio * 733_u32).div_ceil(1000)
+    }
+
+    /// The time the gyroscope's high-pass filter takes to settle within 1%, or 0 if it is off.
+    fn gyro_hpf_settling_us(&self) -> u32 {
+        let ctrl7_g = self.cached(registers::CTRL7_G).unwrap_or(0);
+        if ctrl7_g & 0b1000000 == 0 {
+            return 0;
+        }
+        let cutoff_uhz: u64 = if self.variant == ChipVariant::Lsm6ds33 {
+            [8_100, 32_400, 2_070_000, 16_320_000][(ctrl7_g >> 4 & 0b11) as usize]
+        } else {
+            [16_000, 65_000, 260_000, 1_040_000][(ctrl7_g >> 4 & 0b11) as usize]
+        };
+        // 0.733 s / cutoff, in microseconds
+        (733_000_000_000 / cutoff_uhz) as u32
+    }
+
+    /// The slave address this driver talks to.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// The part this driver is talking to.
+    pub fn variant(&self) -> ChipVariant {
+        self.variant
+    }
+
+    /// The scale the accelerometer was last set to.
+    pub fn accel_scale(&self) -> AccelerometerScale {
+        self.accel_scale
     }
 
-    /// This overwrites the CTRL2_G register.
-    pub fn set_gyro_mode(&mut self, mode: GyroscopeMode) -> Result<(), E> {
-        self.set_register(registers::CTRL2_G, mode.to_bitcode() << 4)
+    /// The scale the gyroscope was last set to.
+    pub fn gyro_scale(&self) -> GyroscopeScale {
+        self.gyro_scale
     }
 
     /// Sets which axes of the accelerometer are enabled. 
     /// The result of `LSM6::read_accel` will remain structurally the same,
     /// although the output it gives for a disabled axis should be ignored.
-    /// This overwrites the CTRL9_XL register.
-    pub fn set_accel_axes(&mut self, x: bool, y: bool, z: bool) -> Result<(), E> {
-        self.set_register(
-            registers::CTRL9_XL,
-            if x { 0b100000 } else { 0 } | if y { 0b10000 } else { 0 } | if z { 0b1000 } else { 0 },
-        )
+    /// The rest of the CTRL9_XL register is kept as it is.
+    /// Only the LSM6DS33 has these bits: on the other variants they are DEN settings,
+    /// so this returns `Error::Unsupported` there.
+    pub fn set_accel_axes(&mut self, x: bool, y: bool, z: bool) -> Result<(), Error<E>> {
+        if self.variant != ChipVariant::Lsm6ds33 {
+            return Err(Error::Unsupported);
+        }
+        self.modify_register(registers::CTRL9_XL, 0b111000, axes_bits(x, y, z))?;
+        Ok(())
     }
 
     /// Sets which axes of the gyroscope are enabled. 
     /// The result of `LSM6::read_gyro` will remain structurally the same,
     /// although the output it gives for a disabled axis should be ignored.
-    /// This overwrites the CTRL10_C register.
-    pub fn set_gyro_axes(&mut self, x: bool, y: bool, z: bool) -> Result<(), E> {
-        let prev = self.read_register(registers::CTRL10_C)?;
-        self.set_register(
-            registers::CTRL10_C,
-            if x { 0b100000 } else { 0 }
-                | if y { 0b10000 } else { 0 }
-                | if z { 0b1000 } else { 0 }
-                | (prev & 7),
-        )
+    /// The rest of the CTRL10_C register is kept as it is.
+    /// Only the LSM6DS33 has these bits: on the other variants they are timestamp,
+    /// pedometer and tilt settings, so this returns `Error::Unsupported` there.
+    pub fn set_gyro_axes(&mut self, x: bool, y: bool, z: bool) -> Result<(), Error<E>> {
+        if self.variant != ChipVariant::Lsm6ds33 {
+            return Err(Error::Unsupported);
+        }
+        self.modify_register(registers::CTRL10_C, 0b111000, axes_bits(x, y, z))?;
+        Ok(())
+    }
+
+    /// Puts the gyroscope to sleep or wakes it up, using the SLEEP_G bit of CTRL4_C.
+    /// While asleep the gyroscope stops sampling but keeps its mode and scale,
+    /// and its drive circuitry stays on, so it resumes within a few samples.
+    /// Resuming from `GyroscopeMode::PowerDown` instead takes the full gyroscope turn-on time,
+    /// which is tens of milliseconds, so sleeping is better for duty-cycled use.
+    /// The rest of the CTRL4_C register is kept as it is.
+    pub fn set_gyro_sleep(&mut self, sleep: bool) -> Result<(), Error<E>> {
+        self.modify_register(
+            registers::CTRL4_C,
+            0b1000000,
+            if sleep { 0b1000000 } else { 0 },
+        )?;
+        Ok(())
+    }
+
+    /// Sets which data-ready signals are routed to the INT1 pin.
+    /// The rest of the INT1_CTRL register is kept as it is.
+    pub fn set_int1_data_ready(&mut self, accel: bool, gyro: bool) -> Result<(), Error<E>> {
+        self.modify_register(
+            registers::INT1_CTRL,
+            0b11,
+            if gyro { 0b10 } else { 0 } | if accel { 1 } else { 0 },
+        )?;
+        Ok(())
+    }
+
+    /// Releases any latched interrupts by reading the source registers, discarding which events fired.
+    /// This reads WAKE_UP_SRC, TAP_SRC and D6D_SRC in one burst, and on the LSM6DSO
+    /// also ALL_INT_SRC, which comes right before them.
+    /// The embedded function sources (e.g. FUNC_SRC) are not read.
+    pub fn clear_interrupts(&mut self) -> Result<(), Error<E>> {
+        let mut sources = [0; 4];
+        if self.variant.has_dso_registers() {
+            self.read_registers(registers::lsm6dso::ALL_INT_SRC, &mut sources)
+        } else {
+            self.read_registers(registers::WAKE_UP_SRC, &mut sources[1..])
+        }
+    }
+
+    /// Reads the WHO_AM_I register, which identifies the part.
+    /// This is useful for logging, and for reporting parts that `ChipVariant` doesn't know about.
+    pub fn who_am_i(&mut self) -> Result<u8, Error<E>> {
+        self.read_register_raw(registers::WHO_AM_I)
+    }
+
+    /// Reads the registers listed in `registers::dump_layout` for this part into `out`, in that order,
+    /// returning how many were read. Reserved addresses are never read, and contiguous
+    /// registers are read in bursts. If `out` is shorter than the layout, only the start of it is read.
+    /// Note that reading the source registers (e.g. WAKE_UP_SRC) clears any latched interrupts.
+    pub fn dump_registers(&mut self, out: &mut [u8]) -> Result<usize, Error<E>> {
+        let layout = registers::dump_layout(self.variant);
+        let len = out.len().min(layout.len());
+        let mut start = 0;
+        while start < len {
+            let mut end = start + 1;
+            while end < len && layout[end].0 == layout[end - 1].0 + 1 {
+                end += 1;
+            }
+            self.read_registers(layout[start].0, &mut out[start..end])?;
+            start = end;
+        }
+        Ok(len)
+    }
+
+    /// Sets whether every register write made by the driver, including `LSM6::set_register`,
+    /// is read back and checked, returning `Error::VerificationFailed` on a mismatch.
+    /// Bits that clear themselves once written (BOOT, SW_RESET and PEDO_RST_STEP) are not checked.
+    /// This is off by default, since it doubles the bus traffic of every write.
+    pub fn set_write_verification(&mut self, enabled: bool) {
+        self.verify_writes = enabled;
     }
 
     /// Set one of the LSM6's register to a certain value.
     /// Be wary when using this manually, as you may override
-    /// an important setting.
-    pub fn set_register(&mut self, reg: u8, value: u8) -> Result<(), E> {
-        self.i2c.write(self.address, &[reg, value])
+    /// an important setting. The shadow cache is updated with the new value.
+    /// `reg` can be a `Register` or a raw address, and writes to reserved or read-only
+    /// registers are refused with `Error::ReservedRegister` or `Error::ReadOnlyRegister`.
+    pub fn set_register<R: IntoRegister>(&mut self, reg: R, value: u8) -> Result<(), Error<E>> {
+        let reg = reg.into_register().map_err(Error::ReservedRegister)?;
+        if reg.is_read_only() {
+            return Err(Error::ReadOnlyRegister(reg));
+        }
+        self.set_register_raw(reg.addr(), value)
+    }
+
+    /// Sets the register at `reg` to a certain value without checking that it is writable.
+    /// This is needed for registers outside the LSM6DS33's map, such as those of other variants
+    /// or of the embedded function bank.
+    pub fn set_register_raw(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_registers(&[reg, value])
+    }
+
+    /// Writes `bytes[1..]` to consecutive registers starting at `bytes[0]` in one transaction,
+    /// verifying them afterwards if write verification is on.
+    /// If the 2nd bit (0-indexed) of the CTRL_3C register has been cleared through the driver,
+    /// the registers are written one transaction at a time instead.
+    fn write_registers(&mut self, bytes: &[u8]) -> Result<(), Error<E>> {
+        if !self.auto_increment && bytes.len() > 2 {
+            for (reg, &value) in (bytes[0]..).zip(&bytes[1..]) {
+                self.write_registers(&[reg, value])?;
+            }
+            return Ok(());
+        }
+        let result = self.retry(|bus, address| bus.write_registers(address, bytes));
+        trace::write(self.address, self.embedded_bank, bytes, &result);
+        result?;
+        for (reg, &value) in (bytes[0]..).zip(&bytes[1..]) {
+            self.update_cache(reg, value);
+            if reg == registers::CTRL3_C {
+                self.auto_increment = value & 0b100 != 0;
+            }
+        }
+        if self.verify_writes {
+            for (reg, &wrote) in (bytes[0]..).zip(&bytes[1..]) {
+                let mask = !SELF_CLEARING_BITS
+                    .iter()
+                    .find(|(r, _)| *r == reg)
+                    .map_or(0, |(_, bits)| *bits);
+                let read = self.read_register_raw(reg)?;
+                if read & mask != wrote & mask {
+                    return Err(Error::VerificationFailed { reg, wrote, read });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads consecutive registers starting at `start` in one transaction.
+    /// Reads that cover the FIFO output or an interrupt source register in the main bank are never retried,
+    /// since a failed read may still have consumed data or cleared events.
+    /// This only works if the 2nd bit (0-indexed) of the CTRL_3C register is set to 1.
+    fn read_registers(&mut self, start: u8, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        let end = start as usize + buffer.len();
+        let non_idempotent = !self.embedded_bank
+            && NON_IDEMPOTENT_READS.iter().any(|&reg| {
+                (start as usize..end).contains(&(reg as usize))
+                    && (reg != registers::lsm6dso::ALL_INT_SRC || self.variant.has_dso_registers())
+            });
+        let result = if non_idempotent {
+            self.bus
+                .read_registers(self.address, start, buffer)
+                .map_err(Error::Bus)
+        } else {
+            self.retry(|bus, address| bus.read_registers(address, start, buffer))
+        };
+        trace::read(self.address, self.embedded_bank, start, buffer, &result);
+        result
+    }
+
+    /// Runs a bus transaction, repeating it up to the configured number of retries if it fails.
+    fn retry<T>(&mut self, mut f: impl FnMut(&mut I, u8) -> Result<T, E>) -> Result<T, Error<E>> {
+        let mut attempt = 0;
+        loop {
+            match f(&mut self.bus, self.address) {
+                Ok(value) => return Ok(value),
+                Err(_) if attempt < self.retries => {
+                    attempt += 1;
+                    self.retry_count = self.retry_count.saturating_add(1);
+                }
+                Err(e) => return Err(Error::Bus(e)),
+            }
+        }
+    }
+
+    /// Sets how many times a failed bus transaction is retried before its error is returned.
+    /// This applies to every register access the driver makes except reads of the FIFO output
+    /// and of the latched interrupt sources (WAKE_UP_SRC, TAP_SRC, D6D_SRC, FUNC_SRC and the LSM6DSO's ALL_INT_SRC),
+    /// where a failed transaction may already have consumed data or cleared events, so their errors are
+    /// returned at once. The default is 0.
+    pub fn set_retry(&mut self, attempts: u8) {
+        self.retries = attempts;
+    }
+
+    /// How many retries have been made since the driver was created.
+    pub fn retry_count(&self) -> u32 {
+        self.retry_count
+    }
+
+    /// Changes the bits of the register at `reg` that are set in `mask` to those of `value`,
+    /// keeping the rest as they are, and returns the register's previous value.
+    /// The previous value comes from the shadow cache when possible, and the write is verified
+    /// like any other when write verification is on. Like `LSM6::set_register_raw`, `reg` is not checked.
+    pub fn modify_register(&mut self, reg: u8, mask: u8, value: u8) -> Result<u8, Error<E>> {
+        let prev = self.read_cached(reg)?;
+        self.write_registers(&[reg, masked(prev, mask, value)])?;
+        Ok(prev)
+    }
+
+    /// Sets the bits of the register at `reg` that are set in `bits`, returning its previous value.
+    pub fn set_bits(&mut self, reg: u8, bits: u8) -> Result<u8, Error<E>> {
+        self.modify_register(reg, bits, bits)
+    }
+
+    /// Clears the bits of the register at `reg` that are set in `bits`, returning its previous value.
+    pub fn clear_bits(&mut self, reg: u8, bits: u8) -> Result<u8, Error<E>> {
+        self.modify_register(reg, bits, 0)
     }
 
     /// Read one of the LSM6's registers.
-    pub fn read_register(&mut self, reg: u8) -> Result<u8, E> {
+    /// `reg` can be a `Register` or a raw address, and reads of reserved addresses
+    /// are refused with `Error::ReservedRegister`.
+    pub fn read_register<R: IntoRegister>(&mut self, reg: R) -> Result<u8, Error<E>> {
+        let reg = reg.into_register().map_err(Error::ReservedRegister)?;
+        self.read_register_raw(reg.addr())
+    }
+
+    /// Reads the register at `reg` without checking that it exists.
+    /// This is needed for registers outside the LSM6DS33's map, such as those of other variants
+    /// or of the embedded function bank.
+    pub fn read_register_raw(&mut self, reg: u8) -> Result<u8, Error<E>> {
         let mut resp = [0];
-        self.i2c.write_read(self.address, &[reg], &mut resp)?;
+        self.read_registers(reg, &mut resp)?;
         Ok(resp[0])
     }
 
@@ -174,12 +1476,13 @@ impl<E, I: Read<Error = E> + Write<Error = E> + WriteRead<Error = E>> LSM6<E, I>
     /// This method of extracting measurements only works if bit 2 (0-indexed) of the CTRL_3C register is set to 1
     /// (which automatically happens in `LSMG::new`). It also assumes that the data is given in little endian, which is true
     /// when bit 1 of the CTRL_3C register is set to 0. 
-    pub fn read_gyro(&mut self) -> Result<Option<(i16, i16, i16)>, E> {
-        if self.read_register(registers::STATUS_REG)? & 0b10 != 0b10 {
+    pub fn read_gyro(&mut self) -> Result<Option<(i16, i16, i16)>, Error<E>> {
+        self.auto_check()?;
+        if self.read_register_raw(registers::STATUS_REG)? & 0b10 != 0b10 {
             return Ok(None);
         }
         self.incremental_read_measurements(registers::OUTX_L_G)
-            .map(|o| Some(o))
+            .map(Some)
     }
 
     /// Reads the latest gyroscopic data, returning `Ok(None)` if any is not ready.
@@ -188,30 +1491,365 @@ impl<E, I: Read<Error = E> + Write<Error = E> + WriteRead<Error = E>> LSM6<E, I>
     /// This method of extracting measurements only works if the 2nd bit (0-indexed) of the CTRL_3C register is set to 1
     /// (which automatically happens in `LSMG::new`). It also assumes that the data is given in little endian, which is true
     /// when bit 1 of the CTRL_3C register is set to 0. 
-    pub fn read_accel(&mut self) -> Result<Option<(i16, i16, i16)>, E> {
-        if self.read_register(registers::STATUS_REG)? & 0b1 != 1 {
+    pub fn read_accel(&mut self) -> Result<Option<(i16, i16, i16)>, Error<E>> {
+        self.auto_check()?;
+        if self.read_register_raw(registers::STATUS_REG)? & 0b1 != 1 {
+            return Ok(None);
+        }
+        self.incremental_read_measurements(registers::OUTX_L_XL)
+            .map(Some)
+    }
+
+    /// Reads the latest acceleration data if `pin` is high, returning `Ok(None)` otherwise.
+    /// This skips the STATUS_REG read of `LSM6::read_accel`, so `pin` must be connected to INT1
+    /// with the accelerometer data-ready signal routed to it (see `LSM6::set_int1_data_ready`)
+    /// and INT1 must be active high, which is the default.
+    pub fn read_accel_on_pin<P: InputPin>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<Option<(i16, i16, i16)>, Error<E>> {
+        if !pin.is_high().map_err(|_| Error::Pin)? {
             return Ok(None);
         }
         self.incremental_read_measurements(registers::OUTX_L_XL)
-            .map(|o| Some(o))
+            .map(Some)
+    }
+
+    /// Reads the latest acceleration data in g, using the current scale.
+    /// This has the same requirements and `None` behavior as `LSM6::read_accel`.
+    pub fn read_accel_g(&mut self) -> Result<Option<(f32, f32, f32)>, Error<E>> {
+        Ok(self.read_accel_both()?.map(|(_, scaled)| scaled))
+    }
+
+    /// Reads the latest acceleration data once, returning it both raw and in g.
+    /// This has the same requirements and `None` behavior as `LSM6::read_accel`.
+    #[allow(clippy::type_complexity)]
+    pub fn read_accel_both(
+        &mut self,
+    ) -> Result<Option<((i16, i16, i16), (f32, f32, f32))>, Error<E>> {
+        let scale = self.accel_scale;
+        Ok(self
+            .read_accel()?
+            .map(|(x, y, z)| ((x, y, z), (scale.to_g(x), scale.to_g(y), scale.to_g(z)))))
+    }
+
+    /// Reads the latest gyroscopic data in degrees per second, using the current scale.
+    /// This has the same requirements and `None` behavior as `LSM6::read_gyro`.
+    pub fn read_gyro_dps(&mut self) -> Result<Option<(f32, f32, f32)>, Error<E>> {
+        let scale = self.gyro_scale;
+        Ok(self
+            .read_gyro()?
+            .map(|(x, y, z)| (scale.to_dps(x), scale.to_dps(y), scale.to_dps(z))))
+    }
+
+    /// Reads the latest raw temperature, returning `Ok(None)` if there is no new temperature data.
+    /// `ChipVariant::to_celsius` converts it.
+    pub fn read_temperature(&mut self) -> Result<Option<i16>, Error<E>> {
+        if self.read_register_raw(registers::STATUS_REG)? & 0b100 == 0 {
+            return Ok(None);
+        }
+        let mut values = [0; 2];
+        self.read_registers(registers::OUT_TEMP_L, &mut values)?;
+        Ok(Some(combine_le(values[0], values[1])))
+    }
+
+    /// Reads the latest temperature in degrees Celsius.
+    /// This has the same `None` behavior as `LSM6::read_temperature`.
+    pub fn read_temperature_celsius(&mut self) -> Result<Option<f32>, Error<E>> {
+        let variant = self.variant;
+        Ok(self.read_temperature()?.map(|raw| variant.to_celsius(raw)))
+    }
+
+    /// Reads the latest acceleration data in milli-g, using integer math and the current scale.
+    /// Values are rounded to the nearest milli-g, with halves rounded away from zero.
+    /// This has the same requirements and `None` behavior as `LSM6::read_accel`.
+    pub fn read_accel_milli_g(&mut self) -> Result<Option<(i32, i32, i32)>, Error<E>> {
+        let ug = self.accel_scale.micro_g_per_lsb();
+        Ok(self.read_accel()?.map(|(x, y, z)| {
+            (
+                div_round(x as i32 * ug, 1000),
+                div_round(y as i32 * ug, 1000),
+                div_round(z as i32 * ug, 1000),
+            )
+        }))
+    }
+
+    /// Reads the latest gyroscopic data in milli-degrees per second, using integer math and the current scale.
+    /// Values are rounded to the nearest milli-degree per second, with halves rounded away from zero.
+    /// This has the same requirements and `None` behavior as `LSM6::read_gyro`.
+    pub fn read_gyro_milli_dps(&mut self) -> Result<Option<(i32, i32, i32)>, Error<E>> {
+        let eighths = self.gyro_scale.eighth_mdps_per_lsb();
+        Ok(self.read_gyro()?.map(|(x, y, z)| {
+            (
+                div_round(x as i32 * eighths, 8),
+                div_round(y as i32 * eighths, 8),
+                div_round(z as i32 * eighths, 8),
+            )
+        }))
+    }
+
+    /// Reads the latest temperature, gyroscopic and acceleration data in one burst,
+    /// returning `Ok(None)` unless all three are ready.
+    /// This has the same CTRL_3C requirements as `LSM6::read_accel` and `LSM6::read_gyro`,
+    /// but only checks the STATUS_REG once and reads OUT_TEMP_L through OUTZ_H_XL in a single transaction.
+    pub fn read_measurement(&mut self) -> Result<Option<Measurement>, Error<E>> {
+        if self.read_register_raw(registers::STATUS_REG)? & 0b111 != 0b111 {
+            return Ok(None);
+        }
+
+        let mut values = [0; 14];
+        self.read_registers(registers::OUT_TEMP_L, &mut values)?;
+        let measurement = decode_measurement(&values);
+        Ok(Some(self.mounting.apply_measurement(measurement)))
+    }
+
+    /// Reads the accelerometer and gyroscope outputs from the same sampling instant,
+    /// returning `Ok(None)` unless both have new data.
+    /// Both sensors have to run at the same output data rate, so that they sample together,
+    /// or `Error::InvalidConfig` is returned. BDU is turned on first if it is off, so that no output
+    /// is updated partway through the read, and then OUT_TEMP_L through OUTZ_H_XL are read in a single burst.
+    /// Together these guarantee that the gyroscope and accelerometer values are one coherent sample set.
+    /// The temperature comes from the same burst, but is updated less often, so it may be from an earlier sample.
+    pub fn read_all_synchronized(&mut self) -> Result<Option<Measurement>, Error<E>> {
+        if self.accel_mode == AccelerometerMode::PowerDown
+            || self.accel_mode.to_bitcode() != self.gyro_mode.to_bitcode()
+        {
+            return Err(Error::InvalidConfig);
+        }
+        if self.read_cached(registers::CTRL3_C)? & 0b1000000 == 0 {
+            self.set_bits(registers::CTRL3_C, 0b1000000)?;
+        }
+        if self.read_register_raw(registers::STATUS_REG)? & 0b11 != 0b11 {
+            return Ok(None);
+        }
+
+        let mut values = [0; 14];
+        self.read_registers(registers::OUT_TEMP_L, &mut values)?;
+        let measurement = decode_measurement(&values);
+        Ok(Some(self.mounting.apply_measurement(measurement)))
     }
 
     /// This method of extracting measurements only works if the 2nd bit (0-indexed) of the CTRL_3C register is set to 1.
-    fn incremental_read_measurements(&mut self, start_reg: u8) -> Result<(i16, i16, i16), E> {
+    /// The measurements are remapped to the mounting orientation.
+    fn incremental_read_measurements(&mut self, start_reg: u8) -> Result<(i16, i16, i16), Error<E>> {
         let mut values = [0; 6];
-        self.i2c
-            .write_read(self.address, &[start_reg], &mut values)?;
+        self.read_registers(start_reg, &mut values)?;
+        Ok(self.mounting.decode(&values))
+    }
+}
+
+/// Reads the WHO_AM_I register at both of the LSM6's slave addresses and reports what happened at each.
+/// Unlike `LSM6::new`, this never writes to the bus and carries on to the second address
+/// even if the first transaction fails, so it is suitable for diagnosing why construction fails.
+pub fn probe<I: WriteRead>(i2c: &mut I) -> ProbeReport<I::Error> {
+    ProbeReport {
+        high: AddressProbe {
+            address: LSM6_SA0_HIGH_ADDRESS,
+            result: read_who_am_i(i2c, LSM6_SA0_HIGH_ADDRESS),
+        },
+        low: AddressProbe {
+            address: LSM6_SA0_LOW_ADDRESS,
+            result: read_who_am_i(i2c, LSM6_SA0_LOW_ADDRESS),
+        },
+    }
+}
+
+/// Gets the correct address for the lsm6 that is being used, along with its variant.
+/// Addresses whose errors `is_nack` accepts are skipped rather than failing the search.
+fn find<I: WriteRead>(
+    i2c: &mut I,
+    identify: impl Fn(u8) -> Option<ChipVariant>,
+    is_nack: impl Fn(&I::Error) -> bool,
+) -> Result<(u8, ChipVariant), Error<I::Error>> {
+    let mut search = Search::new();
+    for &address in &PROBE_ORDER {
+        let result = read_who_am_i(i2c, address);
+        if let Some(found) = search.step(address, result, &identify, &is_nack) {
+            return found;
+        }
+    }
+    Err(search.end())
+}
+
+/// The slave addresses an LSM6 is looked for at, in the order they are tried.
+pub(crate) const PROBE_ORDER: [u8; 2] = [LSM6_SA0_HIGH_ADDRESS, LSM6_SA0_LOW_ADDRESS];
+
+/// A search for an LSM6 across `PROBE_ORDER`, fed the outcome of reading WHO_AM_I at each address in turn.
+/// This is shared by `LSM6` and `AsyncLSM6`, which only differ in how they read the bus.
+pub(crate) struct Search<E> {
+    wrong_id: Option<Error<E>>,
+}
+
+impl<E> Search<E> {
+    pub(crate) fn new() -> Self {
+        Search { wrong_id: None }
+    }
+
+    /// Takes the outcome of reading WHO_AM_I at `address`, returning the result of the search if it ends there.
+    /// A NACK moves on to the next address and any other bus error ends the search,
+    /// while a device that `identify` doesn't recognize is remembered for `Search::end`.
+    pub(crate) fn step(
+        &mut self,
+        address: u8,
+        result: Result<u8, E>,
+        identify: &impl Fn(u8) -> Option<ChipVariant>,
+        is_nack: &impl Fn(&E) -> bool,
+    ) -> Option<Result<(u8, ChipVariant), Error<E>>> {
+        let found = match result {
+            Ok(found) => found,
+            Err(e) if is_nack(&e) => return None,
+            Err(e) => return Some(Err(Error::Bus(e))),
+        };
+        if let Some(variant) = identify(found) {
+            return Some(Ok((address, variant)));
+        }
+        self.wrong_id
+            .get_or_insert(Error::WrongChipId { address, found });
+        None
+    }
+
+    /// The error the search ends with when no address had an LSM6:
+    /// the first wrong WHO_AM_I value, or `Error::NotDetected` if nothing answered.
+    pub(crate) fn end(self) -> Error<E> {
+        self.wrong_id.unwrap_or(Error::NotDetected)
+    }
+}
+
+/// The variant a WHO_AM_I value read at `address` belongs to, or `Error::WrongChipId`.
+pub(crate) fn identify<E>(address: u8, found: u8) -> Result<ChipVariant, Error<E>> {
+    ChipVariant::from_who_am_i(found).ok_or(Error::WrongChipId { address, found })
+}
+
+/// `prev` with the bits set in `mask` replaced by those of `value`.
+pub(crate) fn masked(prev: u8, mask: u8, value: u8) -> u8 {
+    prev & !mask | value & mask
+}
+
+/// Looks for an LSM6 at both of its slave addresses in the same order as `LSM6::new`.
+/// This only ever reads the WHO_AM_I register, so it is safe to use on a bus with
+/// a sensor in an unknown state, but unlike `probe` it stops at the first bus error.
+/// A part that `ChipVariant` doesn't know about is reported with a `variant` of `None`,
+/// unless a known part answers at the other address.
+pub fn detect<I: WriteRead>(i2c: &mut I) -> Result<Option<DetectedLsm6>, I::Error> {
+    detect_with_classifier(i2c, |_| false)
+}
+
+/// Looks for an LSM6 like `detect`, but carries on to the other address when one isn't acknowledged,
+/// like `LSM6::new_with_classifier`. Bus errors that `is_nack` doesn't accept are still returned straight away.
+pub fn detect_with_classifier<I: WriteRead>(
+    i2c: &mut I,
+    is_nack: impl Fn(&I::Error) -> bool,
+) -> Result<Option<DetectedLsm6>, I::Error> {
+    let mut search = Search::new();
+    for &address in &PROBE_ORDER {
+        let result = read_who_am_i(i2c, address);
+        match search.step(address, result, &ChipVariant::from_who_am_i, &is_nack) {
+            Some(Ok((address, variant))) => {
+                return Ok(Some(DetectedLsm6 {
+                    address,
+                    who_am_i: variant.who_am_i(),
+                    variant: Some(variant),
+                }))
+            }
+            Some(Err(Error::Bus(e))) => return Err(e),
+            _ => {}
+        }
+    }
+    match search.end() {
+        Error::WrongChipId { address, found } => Ok(Some(DetectedLsm6 {
+            address,
+            who_am_i: found,
+            variant: None,
+        })),
+        _ => Ok(None),
+    }
+}
+
+/// Combines the low and high bytes of a little endian (the default) two's complement output into an `i16`.
+/// This is useful for decoding bytes read out of the LSM6 by other means, such as raw FIFO data.
+pub fn combine_le(low: u8, high: u8) -> i16 {
+    i16::from_le_bytes([low, high])
+}
+
+/// Combines the bytes of a big endian two's complement output into an `i16`,
+/// which is how outputs are laid out when bit 1 of the CTRL_3C register is set to 1.
+pub fn combine_be(high: u8, low: u8) -> i16 {
+    i16::from_be_bytes([high, low])
+}
+
+/// The CTRL1_XL value for an accelerometer mode and scale.
+fn ctrl1_xl(mode: AccelerometerMode, scale: AccelerometerScale) -> u8 {
+    mode.to_bitcode() << 4 | scale.to_bitcode() << 2
+}
+
+/// The CTRL2_G value for a gyroscope mode and scale.
+fn ctrl2_g(mode: GyroscopeMode, scale: GyroscopeScale) -> u8 {
+    mode.to_bitcode() << 4 | scale.to_bitcode() << 1
+}
+
+/// The axis enable bits of CTRL9_XL and CTRL10_C on the LSM6DS33.
+fn axes_bits(x: bool, y: bool, z: bool) -> u8 {
+    (x as u8) << 5 | (y as u8) << 4 | (z as u8) << 3
+}
+
+/// Decodes the axis enable bits of CTRL9_XL or CTRL10_C, the inverse of `axes_bits`.
+fn decode_axes(bits: u8) -> (bool, bool, bool) {
+    (
+        bits & 0b100000 != 0,
+        bits & 0b10000 != 0,
+        bits & 0b1000 != 0,
+    )
+}
+
+/// Decodes OUT_TEMP_L through OUTZ_H_XL.
+fn decode_measurement(values: &[u8; 14]) -> Measurement {
+    let word = |i: usize| combine_le(values[i], values[i + 1]);
+    Measurement {
+        temp: word(0),
+        gyro: Vector3 {
+            x: word(2),
+            y: word(4),
+            z: word(6),
+        },
+        accel: Vector3 {
+            x: word(8),
+            y: word(10),
+            z: word(12),
+        },
+    }
+}
+
+/// Decodes three consecutive little endian outputs, such as the x, y and z axes of a sensor.
+fn decode_xyz(values: &[u8; 6]) -> (i16, i16, i16) {
+    (
+        combine_le(values[0], values[1]),
+        combine_le(values[2], values[3]),
+        combine_le(values[4], values[5]),
+    )
+}
+
+/// How long `samples` samples take at `odr_hz`, in microseconds, rounded up.
+fn samples_us(samples: u32, odr_hz: f32) -> u32 {
+    let us = samples as f32 * 1_000_000. / odr_hz;
+    let truncated = us as u32;
+    if (truncated as f32) < us {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
 
-        Ok((
-            (values[1] as i16) << 8 | values[0] as i16,
-            (values[3] as i16) << 8 | values[2] as i16,
-            (values[5] as i16) << 8 | values[4] as i16,
-        ))
+/// Divides `value` by a positive `divisor`, rounding halves away from zero.
+fn div_round(value: i32, divisor: i32) -> i32 {
+    if value < 0 {
+        (value - divisor / 2) / divisor
+    } else {
+        (value + divisor / 2) / divisor
     }
 }
 
-fn test_lsm6_addr<I: WriteRead>(i2c: &mut I, address: u8) -> Result<bool, I::Error> {
-    let mut resp = [LSM6_WHO_ID + 1];
+fn read_who_am_i<I: WriteRead>(i2c: &mut I, address: u8) -> Result<u8, I::Error> {
+    let mut resp = [0];
     i2c.write_read(address, &[registers::WHO_AM_I], &mut resp)?;
-    Ok(resp[0] == LSM6_WHO_ID)
+    Ok(resp[0])
 }
@@ -0,0 +1,36 @@
+//! The float functions that `core` doesn't provide, from whichever of `micromath` and `libm` is enabled.
+//! `micromath` is smaller and faster, but its approximations can be off by a degree or two in angles,
+//! so `libm` is used if both are enabled.
+
+#[cfg(not(feature = "libm"))]
+use micromath::F32Ext;
+
+#[cfg(not(feature = "libm"))]
+pub(crate) fn sqrt(x: f32) -> f32 {
+    F32Ext::sqrt(x)
+}
+
+#[cfg(feature = "libm")]
+pub(crate) fn sqrt(x: f32) -> f32 {
+    libm::sqrtf(x)
+}
+
+#[cfg(not(feature = "libm"))]
+pub(crate) fn atan2(y: f32, x: f32) -> f32 {
+    F32Ext::atan2(y, x)
+}
+
+#[cfg(feature = "libm")]
+pub(crate) fn atan2(y: f32, x: f32) -> f32 {
+    libm::atan2f(y, x)
+}
+
+#[cfg(all(feature = "fusion", not(feature = "libm")))]
+pub(crate) fn sin_cos(x: f32) -> (f32, f32) {
+    (F32Ext::sin(x), F32Ext::cos(x))
+}
+
+#[cfg(all(feature = "fusion", feature = "libm"))]
+pub(crate) fn sin_cos(x: f32) -> (f32, f32) {
+    libm::sincosf(x)
+}
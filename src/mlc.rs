@@ -0,0 +1,100 @@
+//! The LSM6DSOX's machine learning core (MLC), which runs decision trees on the sensor data.
+
+use crate::{
+    ctrl1_xl, ctrl2_g, interface::RegisterInterface, registers, ChipVariant, Error, InterruptPin,
+    LSM6,
+};
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Loads a machine learning core configuration, given as the (register, value) writes
+    /// of a `.ucf` file from ST's tools, in order.
+    /// These scripts switch banks themselves by writing FUNC_CFG_ACCESS, so the driver follows along
+    /// to keep the shadow cache right, and switches back to the main bank at the end if the script didn't,
+    /// even if a write fails. Writes to CTRL1_XL and CTRL2_G in the main bank update the driver's modes and scales.
+    /// If a write fails, the writes after it are not attempted.
+    /// On anything but the LSM6DSOX, this returns `Error::Unsupported` without writing anything.
+    pub fn load_mlc_config(&mut self, script: &[(u8, u8)]) -> Result<(), Error<E>> {
+        self.require_mlc()?;
+        let mut ctrl1 = ctrl1_xl(self.accel_mode, self.accel_scale);
+        let mut ctrl2 = ctrl2_g(self.gyro_mode, self.gyro_scale);
+        let mut result = Ok(());
+        for &(reg, value) in script {
+            result = self.set_register_raw(reg, value);
+            if result.is_err() {
+                break;
+            }
+            match reg {
+                // Either the embedded function or the sensor hub bank
+                registers::FUNC_CFG_ACCESS => self.embedded_bank = value & 0b11000000 != 0,
+                registers::CTRL1_XL if !self.embedded_bank => ctrl1 = value,
+                registers::CTRL2_G if !self.embedded_bank => ctrl2 = value,
+                _ => {}
+            }
+        }
+        let exit = if self.embedded_bank {
+            self.embedded_bank = false;
+            self.set_register_raw(registers::FUNC_CFG_ACCESS, 0)
+        } else {
+            Ok(())
+        };
+        result?;
+        exit?;
+        self.restore_modes(ctrl1, ctrl2);
+        Ok(())
+    }
+
+    /// Turns the machine learning core on or off with the MLC_EN bit of EMB_FUNC_EN_B.
+    /// Configurations from ST's tools usually turn it on themselves.
+    pub fn enable_mlc(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        self.require_mlc()?;
+        self.with_embedded_bank(|this| {
+            let en = this.read_register_raw(registers::lsm6dso::EMB_FUNC_EN_B)?;
+            let en = if enabled { en | 0b10000 } else { en & !0b10000 };
+            this.set_register_raw(registers::lsm6dso::EMB_FUNC_EN_B, en)
+        })
+    }
+
+    /// Routes the interrupts of the decision trees in `trees` to `pin`,
+    /// where bit 0 stands for the first tree and bit 7 for the eighth.
+    /// This overwrites MLC_INT1 or MLC_INT2 in the embedded function bank, and if any tree is routed,
+    /// sets the INT1_EMB_FUNC or INT2_EMB_FUNC bit of MD1_CFG or MD2_CFG.
+    /// That bit is shared with the other embedded functions, so it isn't cleared when no tree is routed.
+    pub fn route_mlc_interrupt(&mut self, pin: InterruptPin, trees: u8) -> Result<(), Error<E>> {
+        self.require_mlc()?;
+        let (mlc_int, md_cfg) = match pin {
+            InterruptPin::Int1 => (registers::lsm6dso::MLC_INT1, registers::MD1_CFG),
+            InterruptPin::Int2 => (registers::lsm6dso::MLC_INT2, registers::MD2_CFG),
+        };
+        self.with_embedded_bank(|this| this.set_register_raw(mlc_int, trees))?;
+        if trees != 0 {
+            self.set_bits(md_cfg, 0b10)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the outputs of the eight decision trees, MLC0_SRC through MLC7_SRC, in one burst.
+    /// What each value means is set by the configuration that was loaded.
+    pub fn read_mlc_outputs(&mut self) -> Result<[u8; 8], Error<E>> {
+        self.require_mlc()?;
+        let mut outputs = [0; 8];
+        self.with_embedded_bank(|this| {
+            this.read_registers(registers::lsm6dso::MLC0_SRC, &mut outputs)
+        })?;
+        Ok(outputs)
+    }
+
+    /// Reads MLC_STATUS_MAINPAGE, whose bit n is set when the output of decision tree n + 1 has changed,
+    /// without switching banks.
+    pub fn read_mlc_status(&mut self) -> Result<u8, Error<E>> {
+        self.require_mlc()?;
+        self.read_register_raw(registers::lsm6dso::MLC_STATUS_MAINPAGE)
+    }
+
+    fn require_mlc(&self) -> Result<(), Error<E>> {
+        if self.variant == ChipVariant::Lsm6dsox {
+            Ok(())
+        } else {
+            Err(Error::Unsupported)
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{interface::RegisterInterface, registers, AccelerometerMode, ChipVariant, Error, LSM6};
+
+/// What happens to the sensors when the LSM6 detects inactivity, set with `LSM6::set_inactivity_mode`.
+/// In every mode but `Disabled`, the accelerometer drops to 12.5 Hz low-power mode, and both sensors
+/// go back to their configured modes as soon as activity is detected again.
+/// The gyroscope modes differ in how quickly it has valid data again after waking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InactivityMode {
+    /// Inactivity doesn't change anything.
+    Disabled,
+    /// Only the accelerometer drops to low power. The gyroscope keeps running,
+    /// so there is no wake latency beyond the accelerometer's next sample at its configured rate.
+    AccelLowPower,
+    /// The gyroscope also goes to sleep, which keeps its drive circuitry running.
+    /// It has valid data again within a few samples of waking.
+    GyroSleep,
+    /// The gyroscope also powers down, saving the most current.
+    /// It has to start up again after waking, which takes its full turn-on time (tens of milliseconds).
+    GyroPowerDown,
+}
+
+impl InactivityMode {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            InactivityMode::Disabled => 0,
+            InactivityMode::AccelLowPower => 1,
+            InactivityMode::GyroSleep => 0b10,
+            InactivityMode::GyroPowerDown => 0b11,
+        }
+    }
+}
+
+/// The tap recognition timing applied by `LSM6::configure_tap_timing_ms`, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TapTiming {
+    /// The longest an over-threshold event can last and still count as a tap.
+    pub shock_ms: f32,
+    /// How long the acceleration has to stay quiet after a tap.
+    pub quiet_ms: f32,
+    /// The longest time between the two taps of a double tap.
+    pub duration_ms: f32,
+}
+
+/// The number of ODR periods each code of the SHOCK, QUIET and DUR fields of INT_DUR2 stands for.
+/// A code of 0 stands for a shorter default, not for 0 periods.
+const SHOCK_PERIODS: [u32; 4] = [4, 8, 16, 24];
+const QUIET_PERIODS: [u32; 4] = [2, 4, 8, 12];
+const DUR_PERIODS: [u32; 16] = [
+    16, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448, 480,
+];
+
+/// The code whose time at `odr_hz` is nearest to `ms`, and that time.
+fn nearest_code(ms: u16, odr_hz: f32, periods: &[u32]) -> (u8, f32) {
+    let wanted = ms as f32 * odr_hz / 1000.;
+    let (code, periods) = periods
+        .iter()
+        .enumerate()
+        .min_by(|a, b| {
+            let a = (*a.1 as f32 - wanted).abs();
+            let b = (*b.1 as f32 - wanted).abs();
+            a.partial_cmp(&b).unwrap_or(core::cmp::Ordering::Equal)
+        })
+        .unwrap_or((0, &periods[0]));
+    (code as u8, *periods as f32 * 1000. / odr_hz)
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Sets up the wake-up interrupt, which fires when the acceleration on any axis
+    /// (after the slope filter) goes over `threshold_mg`.
+    /// The threshold has a resolution of 1/64 of the accelerometer's full scale,
+    /// so it is rounded to the nearest step and kept between 1 and 63 steps.
+    /// This sets the wake-up threshold and duration, enables the embedded interrupts on the variants
+    /// that need it, runs the accelerometer at `AccelerometerMode::LowPower52Hz` with its current scale,
+    /// and finally routes the interrupt to INT1 if `route_to_int1` is set.
+    /// Otherwise, wake-up events can be polled in WAKE_UP_SRC.
+    /// If a step fails, its error is returned and the steps after it are not attempted.
+    pub fn setup_wake_on_motion(
+        &mut self,
+        threshold_mg: u16,
+        route_to_int1: bool,
+    ) -> Result<(), Error<E>> {
+        let full_scale = self.accel_scale.full_scale_mg();
+        let steps = (threshold_mg as u32 * 64 + full_scale / 2) / full_scale;
+        self.modify_register(registers::WAKE_UP_THS, 0b111111, steps.clamp(1, 63) as u8)?;
+        self.clear_bits(registers::WAKE_UP_DUR, 0b1100000)?;
+        if self.variant != ChipVariant::Lsm6ds33 {
+            // INTERRUPTS_ENABLE, which the LSM6DS33 doesn't have
+            self.set_bits(registers::TAP_CFG, 0b10000000)?;
+        }
+        self.set_accel_mode(AccelerometerMode::LowPower52Hz)?;
+        if route_to_int1 {
+            self.set_bits(registers::MD1_CFG, 0b100000)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the tap recognition windows of INT_DUR2 in milliseconds rather than in ODR periods,
+    /// at the accelerometer's current output data rate, returning the times that were actually applied.
+    /// Each field only has a few steps, which are multiples of the ODR period,
+    /// so each time is set to the nearest step, which also keeps it within the achievable range.
+    /// `duration_ms` only matters for double taps.
+    /// The steps scale with the ODR, so this has to be called again after changing the accelerometer's mode.
+    /// With the accelerometer powered down, there is no ODR to convert with,
+    /// so this returns `Error::InvalidConfig` without writing anything.
+    pub fn configure_tap_timing_ms(
+        &mut self,
+        shock_ms: u16,
+        quiet_ms: u16,
+        duration_ms: u16,
+    ) -> Result<TapTiming, Error<E>> {
+        let odr_hz = self.accel_mode.odr_hz();
+        if odr_hz == 0. {
+            return Err(Error::InvalidConfig);
+        }
+        let (shock, shock_ms) = nearest_code(shock_ms, odr_hz, &SHOCK_PERIODS);
+        let (quiet, quiet_ms) = nearest_code(quiet_ms, odr_hz, &QUIET_PERIODS);
+        let (duration, duration_ms) = nearest_code(duration_ms, odr_hz, &DUR_PERIODS);
+        self.write_registers(&[registers::INT_DUR2, duration << 4 | quiet << 2 | shock])?;
+        Ok(TapTiming {
+            shock_ms,
+            quiet_ms,
+            duration_ms,
+        })
+    }
+
+    /// Sets what happens to the sensors on inactivity, using the INACT_EN bits of TAP_CFG
+    /// (TAP_CFG2 on the LSM6DSO), and enables the embedded interrupts that inactivity detection needs.
+    /// Inactivity is detected when the acceleration stays under the wake-up threshold
+    /// (see `LSM6::setup_wake_on_motion`) for the sleep duration in WAKE_UP_DUR.
+    /// The driver's modes are left as configured, since the device restores them on activity.
+    /// The LSM6DS33 has no INACT_EN bits, so this returns `Error::Unsupported` there.
+    pub fn set_inactivity_mode(&mut self, mode: InactivityMode) -> Result<(), Error<E>> {
+        if self.variant == ChipVariant::Lsm6ds33 {
+            return Err(Error::Unsupported);
+        }
+        self.modify_register(registers::TAP_CFG, 0b1100000, mode.to_bitcode() << 5)?;
+        if mode != InactivityMode::Disabled {
+            // INTERRUPTS_ENABLE
+            self.set_bits(registers::TAP_CFG, 0b10000000)?;
+        }
+        Ok(())
+    }
+
+    /// Sets up the pedometer, which counts steps and can signal each one.
+    /// The step detection algorithm expects the accelerometer to run at 26 Hz, so this sets
+    /// `AccelerometerMode::LowPower26Hz` with the current scale; ±2 g is the scale it is tuned for.
+    /// The pedometer is enabled first, which on the LSM6DSO happens in the embedded function bank.
+    /// If `route_to_int1` is set, the step detector interrupt is routed to INT1.
+    /// The count can then be read with `LSM6::read_step_count`.
+    pub fn setup_pedometer(&mut self, route_to_int1: bool) -> Result<(), Error<E>> {
+        match self.variant {
+            ChipVariant::Lsm6ds33 => {
+                // PEDO_EN, then FUNC_EN
+                self.set_bits(registers::TAP_CFG, 0b1000000)?;
+                self.set_bits(registers::CTRL10_C, 0b100)?;
+            }
+            ChipVariant::Lsm6dsl | ChipVariant::Lsm6dsm => {
+                // PEDO_EN and FUNC_EN
+                self.set_bits(registers::CTRL10_C, 0b10100)?;
+            }
+            ChipVariant::Lsm6dso | ChipVariant::Lsm6dsox => {
+                self.with_embedded_bank(|this| {
+                    this.set_bits(registers::lsm6dso::EMB_FUNC_EN_A, 0b1000)?;
+                    if route_to_int1 {
+                        this.set_bits(registers::lsm6dso::EMB_FUNC_INT1, 0b1000)?;
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+        self.set_accel_mode(AccelerometerMode::LowPower26Hz)?;
+        if route_to_int1 {
+            if self.variant.has_dso_registers() {
+                // INT1_EMB_FUNC
+                self.set_bits(registers::MD1_CFG, 0b10)?;
+            } else {
+                // INT1_STEP_DETECTOR
+                self.set_bits(registers::INT1_CTRL, 0b10000000)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the number of steps counted by the pedometer since it was enabled or reset.
+    /// On the LSM6DSO, the counter is in the embedded function bank, which this switches to and back from.
+    pub fn read_step_count(&mut self) -> Result<u16, Error<E>> {
+        let mut count = [0; 2];
+        if self.variant.has_dso_registers() {
+            self.with_embedded_bank(|this| {
+                this.read_registers(registers::lsm6dso::STEP_COUNTER_L, &mut count)
+            })?;
+        } else {
+            self.read_registers(registers::STEP_COUNTER_L, &mut count)?;
+        }
+        Ok(u16::from_le_bytes(count))
+    }
+}
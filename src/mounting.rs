@@ -0,0 +1,182 @@
+use crate::{decode_xyz, FifoWord, Measurement, Vector3, LSM6};
+
+/// One of the sensor's axes, possibly reversed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl Axis {
+    /// The sensor axis as 0, 1 or 2, and whether it is reversed.
+    fn index(self) -> (usize, bool) {
+        match self {
+            Axis::PosX => (0, false),
+            Axis::NegX => (0, true),
+            Axis::PosY => (1, false),
+            Axis::NegY => (1, true),
+            Axis::PosZ => (2, false),
+            Axis::NegZ => (2, true),
+        }
+    }
+}
+
+/// Why `AxisMapping::new` rejected a mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AxisMappingError {
+    /// A sensor axis was used for more than one output axis.
+    RepeatedAxis,
+    /// The mapping mirrors the frame instead of rotating it. No way of mounting the sensor does that,
+    /// and since angular velocity doesn't mirror the way acceleration does,
+    /// applying it to both sensors would leave the gyroscope disagreeing with the accelerometer.
+    Reflection,
+}
+
+/// How the sensor is mounted relative to the frame its readings should be reported in,
+/// set with `LSM6::set_mounting_orientation`.
+/// Each output axis is taken from one of the sensor's axes, possibly reversed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AxisMapping {
+    x: Axis,
+    y: Axis,
+    z: Axis,
+}
+
+impl AxisMapping {
+    /// The sensor's axes are the output axes.
+    pub const IDENTITY: Self = Self {
+        x: Axis::PosX,
+        y: Axis::PosY,
+        z: Axis::PosZ,
+    };
+    /// The sensor is turned 90° counter-clockwise about z, seen from above,
+    /// so its x axis points along the output y axis.
+    pub const Z_90: Self = Self {
+        x: Axis::NegY,
+        y: Axis::PosX,
+        z: Axis::PosZ,
+    };
+    /// The sensor is turned 180° about z.
+    pub const Z_180: Self = Self {
+        x: Axis::NegX,
+        y: Axis::NegY,
+        z: Axis::PosZ,
+    };
+    /// The sensor is turned 270° counter-clockwise (90° clockwise) about z, seen from above,
+    /// so its x axis points along the output -y axis.
+    pub const Z_270: Self = Self {
+        x: Axis::PosY,
+        y: Axis::NegX,
+        z: Axis::PosZ,
+    };
+    /// The sensor is upside down, flipped over about its x axis.
+    pub const FLIPPED_X: Self = Self {
+        x: Axis::PosX,
+        y: Axis::NegY,
+        z: Axis::NegZ,
+    };
+    /// The sensor is upside down, flipped over about its y axis.
+    pub const FLIPPED_Y: Self = Self {
+        x: Axis::NegX,
+        y: Axis::PosY,
+        z: Axis::NegZ,
+    };
+
+    /// Takes the output x, y and z axes from the given sensor axes.
+    /// Each sensor axis has to be used exactly once, and the mapping has to be a rotation,
+    /// i.e. reversing an odd number of axes needs an odd number of swaps between them.
+    pub fn new(x: Axis, y: Axis, z: Axis) -> Result<Self, AxisMappingError> {
+        let (xi, xr) = x.index();
+        let (yi, yr) = y.index();
+        let (zi, zr) = z.index();
+        if xi == yi || yi == zi || xi == zi {
+            return Err(AxisMappingError::RepeatedAxis);
+        }
+        // A permutation of 0, 1 and 2 is odd exactly when it is one swap away from the identity,
+        // i.e. when exactly one axis stays in place
+        let fixed = (xi == 0) as u8 + (yi == 1) as u8 + (zi == 2) as u8;
+        let odd_permutation = fixed == 1;
+        let odd_reversals = (xr as u8 + yr as u8 + zr as u8) % 2 == 1;
+        if odd_permutation != odd_reversals {
+            return Err(AxisMappingError::Reflection);
+        }
+        Ok(Self { x, y, z })
+    }
+
+    /// The sensor axes the output x, y and z axes are taken from.
+    pub fn axes(&self) -> (Axis, Axis, Axis) {
+        (self.x, self.y, self.z)
+    }
+
+    /// Remaps a reading from the sensor's axes to the output axes.
+    /// Reversing -32768 saturates at 32767.
+    pub fn apply(&self, reading: (i16, i16, i16)) -> (i16, i16, i16) {
+        let sensor = [reading.0, reading.1, reading.2];
+        let take = |axis: Axis| {
+            let (i, reversed) = axis.index();
+            if reversed {
+                sensor[i].saturating_neg()
+            } else {
+                sensor[i]
+            }
+        };
+        (take(self.x), take(self.y), take(self.z))
+    }
+
+    /// Decodes an x, y and z output read from the sensor, and remaps it like `AxisMapping::apply`.
+    pub(crate) fn decode(&self, values: &[u8; 6]) -> (i16, i16, i16) {
+        self.apply(decode_xyz(values))
+    }
+
+    fn apply_vector(&self, v: Vector3<i16>) -> Vector3<i16> {
+        let (x, y, z) = self.apply((v.x, v.y, v.z));
+        Vector3 { x, y, z }
+    }
+
+    pub(crate) fn apply_measurement(&self, measurement: Measurement) -> Measurement {
+        Measurement {
+            accel: self.apply_vector(measurement.accel),
+            gyro: self.apply_vector(measurement.gyro),
+            temp: measurement.temp,
+        }
+    }
+
+    pub(crate) fn apply_fifo_word(&self, word: FifoWord) -> FifoWord {
+        match word {
+            FifoWord::Gyroscope(xyz) => FifoWord::Gyroscope(self.apply(xyz)),
+            FifoWord::Accelerometer(xyz) => FifoWord::Accelerometer(self.apply(xyz)),
+            other => other,
+        }
+    }
+}
+
+impl Default for AxisMapping {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl<I> LSM6<I> {
+    /// The mounting orientation set with `LSM6::set_mounting_orientation`.
+    pub fn mounting_orientation(&self) -> AxisMapping {
+        self.mounting
+    }
+
+    /// Sets how the sensor is mounted, so that readings are reported in the frame of whatever it is mounted on.
+    /// The same mapping is applied to the accelerometer and gyroscope, to raw and scaled readings,
+    /// to FIFO samples, to `Measurement`s and to calibration offsets.
+    /// The device itself isn't touched, so register-level settings such as per-axis interrupt
+    /// enables and the user offset registers still use the sensor's own axes.
+    /// The mapping is `AxisMapping::IDENTITY` until this is called.
+    pub fn set_mounting_orientation(&mut self, mapping: AxisMapping) {
+        self.mounting = mapping;
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{interface::RegisterInterface, Error, Vector3, LSM6};
+
+/// Which face of the sensor points up, as found by `LSM6::detect_orientation`.
+/// The names assume the sensor lies in a device like a phone, with its z axis out of the screen
+/// and its y axis towards the top edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// The z axis points up.
+    FaceUp,
+    /// The z axis points down.
+    FaceDown,
+    /// The y axis points up.
+    PortraitUp,
+    /// The y axis points down.
+    PortraitDown,
+    /// The x axis points up.
+    LandscapeUp,
+    /// The x axis points down.
+    LandscapeDown,
+}
+
+impl Orientation {
+    /// Finds the face pointing up from an accelerometer reading in g, taken while the sensor isn't accelerating.
+    /// The axis with the most gravity on it decides, but only if it sees at least `threshold_g`;
+    /// otherwise the sensor is between faces and this returns `None`.
+    /// `threshold_g` sets the dead-zone: 0.5 g or less never gives `None`,
+    /// and e.g. 0.8 g needs the sensor to be within about 37° of a face.
+    pub fn from_accel(accel_g: Vector3<f32>, threshold_g: f32) -> Option<Self> {
+        let (x, y, z) = (accel_g.x.abs(), accel_g.y.abs(), accel_g.z.abs());
+        let (value, positive, negative) = if z >= x && z >= y {
+            (accel_g.z, Orientation::FaceUp, Orientation::FaceDown)
+        } else if y >= x {
+            (
+                accel_g.y,
+                Orientation::PortraitUp,
+                Orientation::PortraitDown,
+            )
+        } else {
+            (
+                accel_g.x,
+                Orientation::LandscapeUp,
+                Orientation::LandscapeDown,
+            )
+        };
+        if value.abs() < threshold_g {
+            None
+        } else if value > 0. {
+            Some(positive)
+        } else {
+            Some(negative)
+        }
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Reads the latest acceleration data and finds the face pointing up with `Orientation::from_accel`.
+    /// This is done in software, so it works on every variant, whether or not it has the embedded 6D function.
+    /// This returns `Ok(None)` both when `LSM6::read_accel` would and when the sensor is between faces.
+    pub fn detect_orientation(
+        &mut self,
+        threshold_g: f32,
+    ) -> Result<Option<Orientation>, Error<E>> {
+        Ok(self
+            .read_accel_g()?
+            .and_then(|(x, y, z)| Orientation::from_accel(Vector3 { x, y, z }, threshold_g)))
+    }
+}
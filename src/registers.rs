@@ -1,3 +1,5 @@
+use crate::ChipVariant;
+
 pub const FUNC_CFG_ACCESS: u8 = 0x01;
 pub const FIFO_CTRL1: u8 = 0x06;
 pub const FIFO_CTRL2: u8 = 0x07;
@@ -58,3 +60,507 @@ pub const WAKE_UP_DUR: u8 = 0x5C;
 pub const FREE_FALL: u8 = 0x5D;
 pub const MD1_CFG: u8 = 0x5E;
 pub const MD2_CFG: u8 = 0x5F;
+
+/// The registers above, with metadata about each.
+/// Any address not listed here is reserved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Register {
+    FuncCfgAccess = FUNC_CFG_ACCESS,
+    FifoCtrl1 = FIFO_CTRL1,
+    FifoCtrl2 = FIFO_CTRL2,
+    FifoCtrl3 = FIFO_CTRL3,
+    FifoCtrl4 = FIFO_CTRL4,
+    FifoCtrl5 = FIFO_CTRL5,
+    OrientCfgG = ORIENT_CFG_G,
+    Int1Ctrl = INT1_CTRL,
+    Int2Ctrl = INT2_CTRL,
+    WhoAmI = WHO_AM_I,
+    Ctrl1Xl = CTRL1_XL,
+    Ctrl2G = CTRL2_G,
+    Ctrl3C = CTRL3_C,
+    Ctrl4C = CTRL4_C,
+    Ctrl5C = CTRL5_C,
+    Ctrl6C = CTRL6_C,
+    Ctrl7G = CTRL7_G,
+    Ctrl8Xl = CTRL8_XL,
+    Ctrl9Xl = CTRL9_XL,
+    Ctrl10C = CTRL10_C,
+    WakeUpSrc = WAKE_UP_SRC,
+    TapSrc = TAP_SRC,
+    D6dSrc = D6D_SRC,
+    StatusReg = STATUS_REG,
+    OutTempL = OUT_TEMP_L,
+    OutTempH = OUT_TEMP_H,
+    OutxLG = OUTX_L_G,
+    OutxHG = OUTX_H_G,
+    OutyLG = OUTY_L_G,
+    OutyHG = OUTY_H_G,
+    OutzLG = OUTZ_L_G,
+    OutzHG = OUTZ_H_G,
+    OutxLXl = OUTX_L_XL,
+    OutxHXl = OUTX_H_XL,
+    OutyLXl = OUTY_L_XL,
+    OutyHXl = OUTY_H_XL,
+    OutzLXl = OUTZ_L_XL,
+    OutzHXl = OUTZ_H_XL,
+    FifoStatus1 = FIFO_STATUS1,
+    FifoStatus2 = FIFO_STATUS2,
+    FifoStatus3 = FIFO_STATUS3,
+    FifoStatus4 = FIFO_STATUS4,
+    FifoDataOutL = FIFO_DATA_OUT_L,
+    FifoDataOutH = FIFO_DATA_OUT_H,
+    Timestamp0Reg = TIMESTAMP0_REG,
+    Timestamp1Reg = TIMESTAMP1_REG,
+    Timestamp2Reg = TIMESTAMP2_REG,
+    StepTimestampL = STEP_TIMESTAMP_L,
+    StepTimestampH = STEP_TIMESTAMP_H,
+    StepCounterL = STEP_COUNTER_L,
+    StepCounterH = STEP_COUNTER_H,
+    FuncSrc = FUNC_SRC,
+    TapCfg = TAP_CFG,
+    TapThs6d = TAP_THS_6D,
+    IntDur2 = INT_DUR2,
+    WakeUpThs = WAKE_UP_THS,
+    WakeUpDur = WAKE_UP_DUR,
+    FreeFall = FREE_FALL,
+    Md1Cfg = MD1_CFG,
+    Md2Cfg = MD2_CFG,
+}
+
+impl Register {
+    /// Every register, in address order.
+    pub const ALL: [Register; 60] = [
+        Register::FuncCfgAccess,
+        Register::FifoCtrl1,
+        Register::FifoCtrl2,
+        Register::FifoCtrl3,
+        Register::FifoCtrl4,
+        Register::FifoCtrl5,
+        Register::OrientCfgG,
+        Register::Int1Ctrl,
+        Register::Int2Ctrl,
+        Register::WhoAmI,
+        Register::Ctrl1Xl,
+        Register::Ctrl2G,
+        Register::Ctrl3C,
+        Register::Ctrl4C,
+        Register::Ctrl5C,
+        Register::Ctrl6C,
+        Register::Ctrl7G,
+        Register::Ctrl8Xl,
+        Register::Ctrl9Xl,
+        Register::Ctrl10C,
+        Register::WakeUpSrc,
+        Register::TapSrc,
+        Register::D6dSrc,
+        Register::StatusReg,
+        Register::OutTempL,
+        Register::OutTempH,
+        Register::OutxLG,
+        Register::OutxHG,
+        Register::OutyLG,
+        Register::OutyHG,
+        Register::OutzLG,
+        Register::OutzHG,
+        Register::OutxLXl,
+        Register::OutxHXl,
+        Register::OutyLXl,
+        Register::OutyHXl,
+        Register::OutzLXl,
+        Register::OutzHXl,
+        Register::FifoStatus1,
+        Register::FifoStatus2,
+        Register::FifoStatus3,
+        Register::FifoStatus4,
+        Register::FifoDataOutL,
+        Register::FifoDataOutH,
+        Register::Timestamp0Reg,
+        Register::Timestamp1Reg,
+        Register::Timestamp2Reg,
+        Register::StepTimestampL,
+        Register::StepTimestampH,
+        Register::StepCounterL,
+        Register::StepCounterH,
+        Register::FuncSrc,
+        Register::TapCfg,
+        Register::TapThs6d,
+        Register::IntDur2,
+        Register::WakeUpThs,
+        Register::WakeUpDur,
+        Register::FreeFall,
+        Register::Md1Cfg,
+        Register::Md2Cfg,
+    ];
+
+    /// The address of the register.
+    pub fn addr(self) -> u8 {
+        self as u8
+    }
+
+    /// The register at `addr`, or `None` if the address is reserved.
+    pub fn from_addr(addr: u8) -> Option<Self> {
+        Self::ALL.iter().copied().find(|reg| reg.addr() == addr)
+    }
+
+    /// The name of the register as written in the datasheet.
+    pub fn name(self) -> &'static str {
+        match self {
+            Register::FuncCfgAccess => "FUNC_CFG_ACCESS",
+            Register::FifoCtrl1 => "FIFO_CTRL1",
+            Register::FifoCtrl2 => "FIFO_CTRL2",
+            Register::FifoCtrl3 => "FIFO_CTRL3",
+            Register::FifoCtrl4 => "FIFO_CTRL4",
+            Register::FifoCtrl5 => "FIFO_CTRL5",
+            Register::OrientCfgG => "ORIENT_CFG_G",
+            Register::Int1Ctrl => "INT1_CTRL",
+            Register::Int2Ctrl => "INT2_CTRL",
+            Register::WhoAmI => "WHO_AM_I",
+            Register::Ctrl1Xl => "CTRL1_XL",
+            Register::Ctrl2G => "CTRL2_G",
+            Register::Ctrl3C => "CTRL3_C",
+            Register::Ctrl4C => "CTRL4_C",
+            Register::Ctrl5C => "CTRL5_C",
+            Register::Ctrl6C => "CTRL6_C",
+            Register::Ctrl7G => "CTRL7_G",
+            Register::Ctrl8Xl => "CTRL8_XL",
+            Register::Ctrl9Xl => "CTRL9_XL",
+            Register::Ctrl10C => "CTRL10_C",
+            Register::WakeUpSrc => "WAKE_UP_SRC",
+            Register::TapSrc => "TAP_SRC",
+            Register::D6dSrc => "D6D_SRC",
+            Register::StatusReg => "STATUS_REG",
+            Register::OutTempL => "OUT_TEMP_L",
+            Register::OutTempH => "OUT_TEMP_H",
+            Register::OutxLG => "OUTX_L_G",
+            Register::OutxHG => "OUTX_H_G",
+            Register::OutyLG => "OUTY_L_G",
+            Register::OutyHG => "OUTY_H_G",
+            Register::OutzLG => "OUTZ_L_G",
+            Register::OutzHG => "OUTZ_H_G",
+            Register::OutxLXl => "OUTX_L_XL",
+            Register::OutxHXl => "OUTX_H_XL",
+            Register::OutyLXl => "OUTY_L_XL",
+            Register::OutyHXl => "OUTY_H_XL",
+            Register::OutzLXl => "OUTZ_L_XL",
+            Register::OutzHXl => "OUTZ_H_XL",
+            Register::FifoStatus1 => "FIFO_STATUS1",
+            Register::FifoStatus2 => "FIFO_STATUS2",
+            Register::FifoStatus3 => "FIFO_STATUS3",
+            Register::FifoStatus4 => "FIFO_STATUS4",
+            Register::FifoDataOutL => "FIFO_DATA_OUT_L",
+            Register::FifoDataOutH => "FIFO_DATA_OUT_H",
+            Register::Timestamp0Reg => "TIMESTAMP0_REG",
+            Register::Timestamp1Reg => "TIMESTAMP1_REG",
+            Register::Timestamp2Reg => "TIMESTAMP2_REG",
+            Register::StepTimestampL => "STEP_TIMESTAMP_L",
+            Register::StepTimestampH => "STEP_TIMESTAMP_H",
+            Register::StepCounterL => "STEP_COUNTER_L",
+            Register::StepCounterH => "STEP_COUNTER_H",
+            Register::FuncSrc => "FUNC_SRC",
+            Register::TapCfg => "TAP_CFG",
+            Register::TapThs6d => "TAP_THS_6D",
+            Register::IntDur2 => "INT_DUR2",
+            Register::WakeUpThs => "WAKE_UP_THS",
+            Register::WakeUpDur => "WAKE_UP_DUR",
+            Register::FreeFall => "FREE_FALL",
+            Register::Md1Cfg => "MD1_CFG",
+            Register::Md2Cfg => "MD2_CFG",
+        }
+    }
+
+    /// Whether the register can only be read.
+    pub fn is_read_only(self) -> bool {
+        matches!(
+            self,
+            Register::WhoAmI
+                | Register::WakeUpSrc
+                | Register::TapSrc
+                | Register::D6dSrc
+                | Register::StatusReg
+                | Register::OutTempL
+                | Register::OutTempH
+                | Register::OutxLG
+                | Register::OutxHG
+                | Register::OutyLG
+                | Register::OutyHG
+                | Register::OutzLG
+                | Register::OutzHG
+                | Register::OutxLXl
+                | Register::OutxHXl
+                | Register::OutyLXl
+                | Register::OutyHXl
+                | Register::OutzLXl
+                | Register::OutzHXl
+                | Register::FifoStatus1
+                | Register::FifoStatus2
+                | Register::FifoStatus3
+                | Register::FifoStatus4
+                | Register::FifoDataOutL
+                | Register::FifoDataOutH
+                | Register::Timestamp0Reg
+                | Register::Timestamp1Reg
+                | Register::StepTimestampL
+                | Register::StepTimestampH
+                | Register::StepCounterL
+                | Register::StepCounterH
+                | Register::FuncSrc
+        )
+    }
+}
+
+/// Something that names a register: either a `Register` or a raw address,
+/// which is checked against the reserved addresses.
+pub trait IntoRegister {
+    /// The register, or the address if it is reserved.
+    fn into_register(self) -> Result<Register, u8>;
+}
+
+impl IntoRegister for Register {
+    fn into_register(self) -> Result<Register, u8> {
+        Ok(self)
+    }
+}
+
+impl IntoRegister for u8 {
+    fn into_register(self) -> Result<Register, u8> {
+        Register::from_addr(self).ok_or(self)
+    }
+}
+
+/// The registers read by `LSM6::dump_registers` on the LSM6DS33, in the order they appear in the dump,
+/// so that the byte at each offset of a dump can be labelled.
+/// This is every register above except FIFO_DATA_OUT_L and FIFO_DATA_OUT_H, since reading them consumes FIFO data.
+pub const DUMP_LAYOUT: [(u8, &str); 58] = [
+    (FUNC_CFG_ACCESS, "FUNC_CFG_ACCESS"),
+    (FIFO_CTRL1, "FIFO_CTRL1"),
+    (FIFO_CTRL2, "FIFO_CTRL2"),
+    (FIFO_CTRL3, "FIFO_CTRL3"),
+    (FIFO_CTRL4, "FIFO_CTRL4"),
+    (FIFO_CTRL5, "FIFO_CTRL5"),
+    (ORIENT_CFG_G, "ORIENT_CFG_G"),
+    (INT1_CTRL, "INT1_CTRL"),
+    (INT2_CTRL, "INT2_CTRL"),
+    (WHO_AM_I, "WHO_AM_I"),
+    (CTRL1_XL, "CTRL1_XL"),
+    (CTRL2_G, "CTRL2_G"),
+    (CTRL3_C, "CTRL3_C"),
+    (CTRL4_C, "CTRL4_C"),
+    (CTRL5_C, "CTRL5_C"),
+    (CTRL6_C, "CTRL6_C"),
+    (CTRL7_G, "CTRL7_G"),
+    (CTRL8_XL, "CTRL8_XL"),
+    (CTRL9_XL, "CTRL9_XL"),
+    (CTRL10_C, "CTRL10_C"),
+    (WAKE_UP_SRC, "WAKE_UP_SRC"),
+    (TAP_SRC, "TAP_SRC"),
+    (D6D_SRC, "D6D_SRC"),
+    (STATUS_REG, "STATUS_REG"),
+    (OUT_TEMP_L, "OUT_TEMP_L"),
+    (OUT_TEMP_H, "OUT_TEMP_H"),
+    (OUTX_L_G, "OUTX_L_G"),
+    (OUTX_H_G, "OUTX_H_G"),
+    (OUTY_L_G, "OUTY_L_G"),
+    (OUTY_H_G, "OUTY_H_G"),
+    (OUTZ_L_G, "OUTZ_L_G"),
+    (OUTZ_H_G, "OUTZ_H_G"),
+    (OUTX_L_XL, "OUTX_L_XL"),
+    (OUTX_H_XL, "OUTX_H_XL"),
+    (OUTY_L_XL, "OUTY_L_XL"),
+    (OUTY_H_XL, "OUTY_H_XL"),
+    (OUTZ_L_XL, "OUTZ_L_XL"),
+    (OUTZ_H_XL, "OUTZ_H_XL"),
+    (FIFO_STATUS1, "FIFO_STATUS1"),
+    (FIFO_STATUS2, "FIFO_STATUS2"),
+    (FIFO_STATUS3, "FIFO_STATUS3"),
+    (FIFO_STATUS4, "FIFO_STATUS4"),
+    (TIMESTAMP0_REG, "TIMESTAMP0_REG"),
+    (TIMESTAMP1_REG, "TIMESTAMP1_REG"),
+    (TIMESTAMP2_REG, "TIMESTAMP2_REG"),
+    (STEP_TIMESTAMP_L, "STEP_TIMESTAMP_L"),
+    (STEP_TIMESTAMP_H, "STEP_TIMESTAMP_H"),
+    (STEP_COUNTER_L, "STEP_COUNTER_L"),
+    (STEP_COUNTER_H, "STEP_COUNTER_H"),
+    (FUNC_SRC, "FUNC_SRC"),
+    (TAP_CFG, "TAP_CFG"),
+    (TAP_THS_6D, "TAP_THS_6D"),
+    (INT_DUR2, "INT_DUR2"),
+    (WAKE_UP_THS, "WAKE_UP_THS"),
+    (WAKE_UP_DUR, "WAKE_UP_DUR"),
+    (FREE_FALL, "FREE_FALL"),
+    (MD1_CFG, "MD1_CFG"),
+    (MD2_CFG, "MD2_CFG"),
+];
+
+/// The layout of `LSM6::dump_registers` on `variant`.
+/// The LSM6DSL and LSM6DSM share the LSM6DS33's, although a few of their registers have other names.
+pub fn dump_layout(variant: ChipVariant) -> &'static [(u8, &'static str)] {
+    if variant.has_dso_registers() {
+        &lsm6dso::DUMP_LAYOUT
+    } else {
+        &DUMP_LAYOUT
+    }
+}
+
+/// Sensor hub and synchronization registers of the LSM6DSL and LSM6DSM that the LSM6DS33 doesn't have.
+pub mod lsm6dsl {
+    /// In embedded function bank A.
+    pub const SLV0_ADD: u8 = 0x02;
+    /// In embedded function bank A.
+    pub const SLV0_SUBADD: u8 = 0x03;
+    /// In embedded function bank A. The address, sub-address and configuration of slaves 1 to 3 follow it.
+    pub const SLAVE0_CONFIG: u8 = 0x04;
+    /// In embedded function bank A. MAG_SI_XY through MAG_SI_ZZ follow it, row by row.
+    pub const MAG_SI_XX: u8 = 0x24;
+    /// In embedded function bank A. MAG_OFFX_H through MAG_OFFZ_H follow it.
+    pub const MAG_OFFX_L: u8 = 0x2D;
+    /// SENSOR_SYNC_RES_RATIO follows it.
+    pub const SENSOR_SYNC_TIME_FRAME: u8 = 0x04;
+    pub const MASTER_CONFIG: u8 = 0x1A;
+    /// SENSORHUB2_REG through SENSORHUB12_REG follow it.
+    pub const SENSORHUB1_REG: u8 = 0x2E;
+    /// SENSORHUB14_REG through SENSORHUB18_REG follow it.
+    pub const SENSORHUB13_REG: u8 = 0x4D;
+    /// The magnetometer data with hard-iron and soft-iron correction applied.
+    /// OUT_MAG_RAW_X_H through OUT_MAG_RAW_Z_H follow it.
+    pub const OUT_MAG_RAW_X_L: u8 = 0x66;
+}
+
+/// Registers of the LSM6DSO that differ from the LSM6DS33.
+pub mod lsm6dso {
+    /// In the embedded function bank.
+    pub const PAGE_SEL: u8 = 0x02;
+    /// In the sensor hub bank. SENSOR_HUB_2 through SENSOR_HUB_18 follow it.
+    pub const SENSOR_HUB_1: u8 = 0x02;
+    /// In the embedded function bank.
+    pub const EMB_FUNC_EN_A: u8 = 0x04;
+    /// In the embedded function bank.
+    pub const EMB_FUNC_EN_B: u8 = 0x05;
+    /// In the embedded function bank.
+    pub const PAGE_ADDRESS: u8 = 0x08;
+    /// In the embedded function bank.
+    pub const PAGE_VALUE: u8 = 0x09;
+    /// In the embedded function bank.
+    pub const EMB_FUNC_INT1: u8 = 0x0A;
+    /// In the embedded function bank. FSM_INT1_B follows it.
+    pub const FSM_INT1_A: u8 = 0x0B;
+    /// In the embedded function bank, LSM6DSOX only.
+    pub const MLC_INT1: u8 = 0x0D;
+    /// In the embedded function bank. FSM_INT2_B follows it.
+    pub const FSM_INT2_A: u8 = 0x0F;
+    /// In the embedded function bank, LSM6DSOX only.
+    pub const MLC_INT2: u8 = 0x11;
+    /// In the embedded function bank.
+    pub const EMB_FUNC_STATUS: u8 = 0x12;
+    /// In the sensor hub bank.
+    pub const MASTER_CONFIG: u8 = 0x14;
+    /// In the sensor hub bank.
+    pub const SLV0_ADD: u8 = 0x15;
+    /// In the sensor hub bank.
+    pub const SLV0_SUBADD: u8 = 0x16;
+    /// In the sensor hub bank. The address, sub-address and configuration of slaves 1 to 3 follow it.
+    pub const SLV0_CONFIG: u8 = 0x17;
+    /// In the embedded function bank.
+    pub const PAGE_RW: u8 = 0x17;
+    /// In the embedded function bank. FSM_ENABLE_B follows it.
+    pub const FSM_ENABLE_A: u8 = 0x46;
+    /// In the embedded function bank. FSM_OUTS2 through FSM_OUTS16 follow it.
+    pub const FSM_OUTS1: u8 = 0x4C;
+    /// In the embedded function bank.
+    pub const EMB_FUNC_ODR_CFG_B: u8 = 0x5F;
+    /// In the embedded function bank.
+    pub const STEP_COUNTER_L: u8 = 0x62;
+    /// In the embedded function bank, LSM6DSOX only. MLC1_SRC through MLC7_SRC follow it.
+    pub const MLC0_SRC: u8 = 0x70;
+    pub const FIFO_CTRL1: u8 = 0x07;
+    pub const FIFO_CTRL2: u8 = 0x08;
+    pub const FIFO_CTRL3: u8 = 0x09;
+    pub const FIFO_CTRL4: u8 = 0x0A;
+    /// COUNTER_BDR_REG2 follows it.
+    pub const COUNTER_BDR_REG1: u8 = 0x0B;
+    /// TAP_CFG1 follows it, and TAP_CFG2 is at the LSM6DS33's TAP_CFG.
+    pub const TAP_CFG0: u8 = 0x56;
+    pub const ALL_INT_SRC: u8 = 0x1A;
+    /// FSM_STATUS_B_MAINPAGE follows it.
+    pub const FSM_STATUS_A_MAINPAGE: u8 = 0x36;
+    /// LSM6DSOX only.
+    pub const MLC_STATUS_MAINPAGE: u8 = 0x38;
+    pub const STATUS_MASTER_MAINPAGE: u8 = 0x39;
+    pub const FIFO_DATA_OUT_TAG: u8 = 0x78;
+
+    /// The registers read by `LSM6::dump_registers` on the LSM6DSO and LSM6DSOX, like `super::DUMP_LAYOUT`.
+    /// The reserved addresses between them are skipped, as are MLC_STATUS_MAINPAGE,
+    /// which is reserved on the LSM6DSO, and the FIFO outputs.
+    pub const DUMP_LAYOUT: [(u8, &str); 69] = [
+        (0x01, "FUNC_CFG_ACCESS"),
+        (0x02, "PIN_CTRL"),
+        (0x07, "FIFO_CTRL1"),
+        (0x08, "FIFO_CTRL2"),
+        (0x09, "FIFO_CTRL3"),
+        (0x0A, "FIFO_CTRL4"),
+        (0x0B, "COUNTER_BDR_REG1"),
+        (0x0C, "COUNTER_BDR_REG2"),
+        (0x0D, "INT1_CTRL"),
+        (0x0E, "INT2_CTRL"),
+        (0x0F, "WHO_AM_I"),
+        (0x10, "CTRL1_XL"),
+        (0x11, "CTRL2_G"),
+        (0x12, "CTRL3_C"),
+        (0x13, "CTRL4_C"),
+        (0x14, "CTRL5_C"),
+        (0x15, "CTRL6_C"),
+        (0x16, "CTRL7_G"),
+        (0x17, "CTRL8_XL"),
+        (0x18, "CTRL9_XL"),
+        (0x19, "CTRL10_C"),
+        (0x1A, "ALL_INT_SRC"),
+        (0x1B, "WAKE_UP_SRC"),
+        (0x1C, "TAP_SRC"),
+        (0x1D, "D6D_SRC"),
+        (0x1E, "STATUS_REG"),
+        (0x20, "OUT_TEMP_L"),
+        (0x21, "OUT_TEMP_H"),
+        (0x22, "OUTX_L_G"),
+        (0x23, "OUTX_H_G"),
+        (0x24, "OUTY_L_G"),
+        (0x25, "OUTY_H_G"),
+        (0x26, "OUTZ_L_G"),
+        (0x27, "OUTZ_H_G"),
+        (0x28, "OUTX_L_A"),
+        (0x29, "OUTX_H_A"),
+        (0x2A, "OUTY_L_A"),
+        (0x2B, "OUTY_H_A"),
+        (0x2C, "OUTZ_L_A"),
+        (0x2D, "OUTZ_H_A"),
+        (0x35, "EMB_FUNC_STATUS_MAINPAGE"),
+        (0x36, "FSM_STATUS_A_MAINPAGE"),
+        (0x37, "FSM_STATUS_B_MAINPAGE"),
+        (0x39, "STATUS_MASTER_MAINPAGE"),
+        (0x3A, "FIFO_STATUS1"),
+        (0x3B, "FIFO_STATUS2"),
+        (0x40, "TIMESTAMP0"),
+        (0x41, "TIMESTAMP1"),
+        (0x42, "TIMESTAMP2"),
+        (0x43, "TIMESTAMP3"),
+        (0x56, "TAP_CFG0"),
+        (0x57, "TAP_CFG1"),
+        (0x58, "TAP_CFG2"),
+        (0x59, "TAP_THS_6D"),
+        (0x5A, "INT_DUR2"),
+        (0x5B, "WAKE_UP_THS"),
+        (0x5C, "WAKE_UP_DUR"),
+        (0x5D, "FREE_FALL"),
+        (0x5E, "MD1_CFG"),
+        (0x5F, "MD2_CFG"),
+        (0x62, "I3C_BUS_AVB"),
+        (0x63, "INTERNAL_FREQ_FINE"),
+        (0x6F, "INT_OIS"),
+        (0x70, "CTRL1_OIS"),
+        (0x71, "CTRL2_OIS"),
+        (0x72, "CTRL3_OIS"),
+        (0x73, "X_OFS_USR"),
+        (0x74, "Y_OFS_USR"),
+        (0x75, "Z_OFS_USR"),
+    ];
+}
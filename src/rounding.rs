@@ -0,0 +1,66 @@
+use crate::{interface::RegisterInterface, registers, Error, LSM6};
+
+/// Which output registers a burst read wraps around, set with `LSM6::set_rounding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rounding {
+    /// Bursts run on through the register map, which is the default.
+    None,
+    /// Bursts wrap from OUTZ_H_XL (0x2D) back to OUTX_L_XL (0x28).
+    Accel,
+    /// Bursts wrap from OUTZ_H_G (0x27) back to OUTX_L_G (0x22).
+    Gyro,
+    /// Bursts wrap from OUTZ_H_XL (0x2D) back to OUTX_L_G (0x22), so they alternate gyroscope and accelerometer samples.
+    GyroAccel,
+}
+
+impl Rounding {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            Rounding::None => 0,
+            Rounding::Accel => 1,
+            Rounding::Gyro => 0b10,
+            Rounding::GyroAccel => 0b11,
+        }
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Sets which output registers burst reads wrap around, so one long burst, such as a DMA transfer,
+    /// reads sample after sample without the register address being sent again.
+    /// Each read of the data is a new sample only if the sensor has produced one since,
+    /// so with block data update on the burst should be paced by the data-ready interrupt.
+    /// This is the ROUNDING field of CTRL5_C, whose other bits are kept.
+    ///
+    /// With `include_status`, the ROUNDING_STATUS bit of CTRL7_G also puts the source registers in the round:
+    /// with `Rounding::GyroAccel`, a burst from WAKE_UP_SRC (0x1B) reads TAP_SRC, D6D_SRC, STATUS_REG,
+    /// the temperature and the gyroscope and accelerometer outputs through OUTZ_H_XL (0x2D),
+    /// then wraps back to WAKE_UP_SRC, so every 19 bytes hold one set of status and samples.
+    /// Reading the source registers this way clears their latched interrupts like any other read.
+    /// The LSM6DSO and LSM6DSOX can't do this, so there `include_status` is `Error::Unsupported`,
+    /// and nothing is written.
+    pub fn set_rounding(
+        &mut self,
+        rounding: Rounding,
+        include_status: bool,
+    ) -> Result<(), Error<E>> {
+        let status_rounding = !self.variant.has_dso_registers();
+        if include_status && !status_rounding {
+            return Err(Error::Unsupported);
+        }
+        // The field is two bits wide from bit 5 on the LSM6DSO, and three bits on the others,
+        // where the LSM6DSL and LSM6DSM use the extra values to round the sensor hub outputs
+        let mask = if status_rounding {
+            0b11100000
+        } else {
+            0b1100000
+        };
+        self.modify_register(registers::CTRL5_C, mask, rounding.to_bitcode() << 5)?;
+        if status_rounding {
+            let bit = if include_status { 0b100 } else { 0 };
+            self.modify_register(registers::CTRL7_G, 0b100, bit)?;
+        }
+        Ok(())
+    }
+}
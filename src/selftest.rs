@@ -0,0 +1,208 @@
+use embedded_hal::blocking::delay::DelayMs;
+
+use crate::{
+    div_round, interface::RegisterInterface, registers, AccelerometerScale, ChipVariant, Error,
+    GyroscopeScale, LSM6,
+};
+
+/// The range that the self-test output change must fall in on every axis.
+/// This is in milli-g for the accelerometer and milli-degrees per second for the gyroscope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfTestLimits {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl SelfTestLimits {
+    /// The datasheet limits for the accelerometer self-test of the given variant.
+    pub fn accel_default(variant: ChipVariant) -> Self {
+        match variant {
+            ChipVariant::Lsm6dso | ChipVariant::Lsm6dsox => SelfTestLimits { min: 50, max: 1700 },
+            _ => SelfTestLimits { min: 90, max: 1700 },
+        }
+    }
+
+    /// The datasheet limits for the gyroscope self-test of the given variant.
+    pub fn gyro_default(_variant: ChipVariant) -> Self {
+        SelfTestLimits {
+            min: 150_000,
+            max: 700_000,
+        }
+    }
+
+    fn contains(&self, delta: (i32, i32, i32)) -> bool {
+        let range = self.min..=self.max;
+        range.contains(&delta.0) && range.contains(&delta.1) && range.contains(&delta.2)
+    }
+}
+
+/// The outcome of a self-test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfTestResult {
+    /// The absolute output change on each axis when the self-test was enabled,
+    /// in milli-g for the accelerometer and milli-degrees per second for the gyroscope.
+    pub delta: (i32, i32, i32),
+    /// Whether every axis was within the limits used.
+    pub passed: bool,
+}
+
+/// How many samples are averaged with the self-test off and on.
+const SELF_TEST_SAMPLES: i32 = 5;
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Runs the accelerometer self-test at 52 Hz and ±2 g, following the datasheet procedure.
+    /// `limits` defaults to `SelfTestLimits::accel_default` for the detected variant.
+    /// The CTRL1_XL through CTRL10_C registers are restored afterwards, even if the test fails partway.
+    /// This takes roughly half a second.
+    pub fn self_test_accel<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        limits: Option<SelfTestLimits>,
+    ) -> Result<SelfTestResult, Error<E>> {
+        let limits = limits.unwrap_or_else(|| SelfTestLimits::accel_default(self.variant));
+        // 52 Hz, ±2 g with the gyroscope off
+        let delta = self.with_saved_ctrl(|this| {
+            this.run_self_test(delay, 0x30, 0, 0b01, 200, registers::OUTX_L_XL, 0b1)
+        })?;
+        let ug = AccelerometerScale::G2.micro_g_per_lsb();
+        let delta = (
+            div_round(delta.0 * ug, 1000),
+            div_round(delta.1 * ug, 1000),
+            div_round(delta.2 * ug, 1000),
+        );
+        Ok(SelfTestResult {
+            delta,
+            passed: limits.contains(delta),
+        })
+    }
+
+    /// Runs the gyroscope self-test at 208 Hz and ±2000 dps, following the datasheet procedure.
+    /// `limits` defaults to `SelfTestLimits::gyro_default` for the detected variant.
+    /// The CTRL1_XL through CTRL10_C registers are restored afterwards, even if the test fails partway.
+    /// This takes roughly a second, most of which is waiting for the gyroscope to settle.
+    pub fn self_test_gyro<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        limits: Option<SelfTestLimits>,
+    ) -> Result<SelfTestResult, Error<E>> {
+        let limits = limits.unwrap_or_else(|| SelfTestLimits::gyro_default(self.variant));
+        // 208 Hz, ±2000 dps with the accelerometer off
+        let delta = self.with_saved_ctrl(|this| {
+            this.run_self_test(delay, 0, 0x5C, 0b100, 800, registers::OUTX_L_G, 0b10)
+        })?;
+        let eighths = GyroscopeScale::Dps2000.eighth_mdps_per_lsb();
+        let delta = (
+            div_round(delta.0 * eighths, 8),
+            div_round(delta.1 * eighths, 8),
+            div_round(delta.2 * eighths, 8),
+        );
+        Ok(SelfTestResult {
+            delta,
+            passed: limits.contains(delta),
+        })
+    }
+
+    /// Saves CTRL1_XL through CTRL10_C, runs `f`, then writes them back in one burst.
+    fn with_saved_ctrl<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, Error<E>>,
+    ) -> Result<T, Error<E>> {
+        let mut saved = [0; 11];
+        saved[0] = registers::CTRL1_XL;
+        self.read_registers(registers::CTRL1_XL, &mut saved[1..])?;
+        let result = f(self);
+        self.write_registers(&saved)?;
+        result
+    }
+
+    /// Returns the raw absolute output change with the self-test enabled.
+    #[allow(clippy::too_many_arguments)]
+    fn run_self_test<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        ctrl1_xl: u8,
+        ctrl2_g: u8,
+        ctrl5_c: u8,
+        settle_ms: u16,
+        output: u8,
+        ready: u8,
+    ) -> Result<(i32, i32, i32), Error<E>> {
+        // CTRL1_XL through CTRL10_C, with BDU, IF_INC and every axis enabled
+        self.write_registers(&[
+            registers::CTRL1_XL,
+            ctrl1_xl,
+            ctrl2_g,
+            0x44,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0x38,
+            0x38,
+        ])?;
+        wait_ms(delay, settle_ms);
+        let off = self.average_samples(delay, output, ready)?;
+
+        self.set_register_raw(registers::CTRL5_C, ctrl5_c)?;
+        wait_ms(delay, settle_ms);
+        let on = self.average_samples(delay, output, ready)?;
+
+        Ok((
+            (on.0 - off.0).abs(),
+            (on.1 - off.1).abs(),
+            (on.2 - off.2).abs(),
+        ))
+    }
+
+    /// Discards the first sample and averages the next `SELF_TEST_SAMPLES`.
+    fn average_samples<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        output: u8,
+        ready: u8,
+    ) -> Result<(i32, i32, i32), Error<E>> {
+        let mut sum = (0, 0, 0);
+        for i in 0..=SELF_TEST_SAMPLES {
+            wait_ready(self, delay, ready)?;
+            let (x, y, z) = self.incremental_read_measurements(output)?;
+            if i > 0 {
+                sum.0 += x as i32;
+                sum.1 += y as i32;
+                sum.2 += z as i32;
+            }
+        }
+        Ok((
+            sum.0 / SELF_TEST_SAMPLES,
+            sum.1 / SELF_TEST_SAMPLES,
+            sum.2 / SELF_TEST_SAMPLES,
+        ))
+    }
+}
+
+/// Waits up to 100 ms for the `ready` bits of STATUS_REG to be set.
+pub(crate) fn wait_ready<E, I: RegisterInterface<Error = E>, D: DelayMs<u8>>(
+    lsm6: &mut LSM6<I>,
+    delay: &mut D,
+    ready: u8,
+) -> Result<(), Error<E>> {
+    let mut attempts = 0;
+    while lsm6.read_register_raw(registers::STATUS_REG)? & ready == 0 {
+        attempts += 1;
+        if attempts > 100 {
+            return Err(Error::Timeout);
+        }
+        delay.delay_ms(1);
+    }
+    Ok(())
+}
+
+fn wait_ms<D: DelayMs<u8>>(delay: &mut D, mut ms: u16) {
+    while ms > 0 {
+        let step = ms.min(u8::MAX as u16);
+        delay.delay_ms(step as u8);
+        ms -= step;
+    }
+}
@@ -0,0 +1,312 @@
+//! The sensor hub, which makes the LSM6 an I2C master on its auxiliary bus, so it can read an external sensor
+//! such as a magnetometer in step with its own samples. The LSM6DS33 doesn't have one.
+//!
+//! With `SensorHubTrigger::DataReady`, each read cycle starts at an accelerometer sample, not a gyroscope one,
+//! and the external data lands a bus transfer later, when `EmbFuncStatus::sensor_hub_end_op` is set.
+//! On the LSM6DSO the cycles are also limited to the SHUB_ODR rate of SLV0_CONFIG, which is left at 104 Hz.
+//! Both sensors run off the same oscillator, so external samples only line up with the gyroscope's
+//! when it runs at the accelerometer's rate or a whole multiple of it.
+
+use embedded_hal::blocking::delay::DelayMs;
+
+use crate::{decode_xyz, interface::RegisterInterface, registers, ChipVariant, Error, LSM6};
+
+/// What starts each of the sensor hub's read cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SensorHubTrigger {
+    /// The accelerometer's data-ready signal, so the external sensor is read once per accelerometer sample,
+    /// whatever rate the gyroscope runs at.
+    DataReady,
+    /// A signal on the INT2 pin, which has to be set up as an input.
+    Int2,
+}
+
+/// The settings of the sensor hub's I2C master, applied by `LSM6::enable_sensor_hub`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SensorHubConfig {
+    /// Turns on the internal pull-ups of the auxiliary bus, for boards without external ones.
+    pub pull_ups: bool,
+    pub trigger: SensorHubTrigger,
+}
+
+impl Default for SensorHubConfig {
+    fn default() -> Self {
+        SensorHubConfig {
+            pull_ups: false,
+            trigger: SensorHubTrigger::DataReady,
+        }
+    }
+}
+
+/// A read that the sensor hub makes from an external sensor in every cycle,
+/// set up with `LSM6::configure_sensor_hub_slave0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SensorHubSlave {
+    /// The external sensor's 7-bit I2C address.
+    pub address: u8,
+    /// The first register to read.
+    pub register: u8,
+    /// How many consecutive registers to read, from 1 to 7.
+    pub len: u8,
+}
+
+/// One of the four external sensors the sensor hub can read in each cycle.
+/// The bytes read through each slot are stored one after the other, in slot order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SlaveSlot {
+    Slave0,
+    Slave1,
+    Slave2,
+    Slave3,
+}
+
+impl SlaveSlot {
+    fn index(self) -> usize {
+        match self {
+            SlaveSlot::Slave0 => 0,
+            SlaveSlot::Slave1 => 1,
+            SlaveSlot::Slave2 => 2,
+            SlaveSlot::Slave3 => 3,
+        }
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Sets up the read that the sensor hub makes through slave 0, overwriting its address,
+    /// sub-address and configuration registers in one burst, in the bank that holds them.
+    /// The external sensor has to be set to auto-increment its register address for reads of more than one byte.
+    /// This should be done with the sensor hub off, before `LSM6::enable_sensor_hub`,
+    /// so that the master never runs with a half-written slave configuration.
+    /// A `len` outside 1 to 7 is `Error::InvalidConfig`, and on the LSM6DS33 this is `Error::Unsupported`.
+    pub fn configure_sensor_hub_slave0(&mut self, slave: SensorHubSlave) -> Result<(), Error<E>> {
+        self.require_sensor_hub()?;
+        if !(1..=7).contains(&slave.len) || slave.address > 0x7F {
+            return Err(Error::InvalidConfig);
+        }
+        let slv0_add = if self.variant.has_dso_registers() {
+            registers::lsm6dso::SLV0_ADD
+        } else {
+            registers::lsm6dsl::SLV0_ADD
+        };
+        // The lowest bit of SLV0_ADD selects a read
+        let bytes = [slv0_add, slave.address << 1 | 1, slave.register, slave.len];
+        self.with_sensor_hub_bank(|this| this.write_registers(&bytes))
+    }
+
+    /// Turns on the sensor hub's I2C master with `config`, which then reads the external sensor
+    /// in every cycle. On the LSM6DSL and LSM6DSM, this also sets the FUNC_EN bit of CTRL10_C that the hub needs.
+    /// Only slave 0 is used, and pass-through mode is turned off.
+    pub fn enable_sensor_hub(&mut self, config: SensorHubConfig) -> Result<(), Error<E>> {
+        self.require_sensor_hub()?;
+        let pull_ups = config.pull_ups;
+        let start_on_int2 = config.trigger == SensorHubTrigger::Int2;
+        if self.variant.has_dso_registers() {
+            // START_CONFIG, PASS_THROUGH_MODE, SHUB_PU_EN, MASTER_ON and AUX_SENS_ON, which is 0 for one slave
+            let bits = if start_on_int2 { 0b100000 } else { 0 }
+                | if pull_ups { 0b1000 } else { 0 }
+                | 0b100;
+            self.with_sensor_hub_bank(|this| {
+                let reg = registers::lsm6dso::MASTER_CONFIG;
+                let value = this.read_register_raw(reg)?;
+                this.set_register_raw(reg, value & !0b111111 | bits)
+            })
+        } else {
+            // FUNC_EN
+            self.set_bits(registers::CTRL10_C, 0b100)?;
+            // START_CONFIG, PULL_UP_EN, PASS_THROUGH_MODE and MASTER_ON
+            let bits =
+                if start_on_int2 { 0b10000 } else { 0 } | if pull_ups { 0b1000 } else { 0 } | 1;
+            self.modify_register(registers::lsm6dsl::MASTER_CONFIG, 0b11101, bits)?;
+            Ok(())
+        }
+    }
+
+    /// Turns off the sensor hub's I2C master, clearing only its MASTER_ON bit.
+    /// The sensors, their modes and the embedded functions keep running as they are.
+    pub fn disable_sensor_hub(&mut self) -> Result<(), Error<E>> {
+        self.require_sensor_hub()?;
+        if self.variant.has_dso_registers() {
+            self.with_sensor_hub_bank(|this| {
+                let reg = registers::lsm6dso::MASTER_CONFIG;
+                let value = this.read_register_raw(reg)?;
+                this.set_register_raw(reg, value & !0b100)
+            })
+        } else {
+            self.clear_bits(registers::lsm6dsl::MASTER_CONFIG, 1)?;
+            Ok(())
+        }
+    }
+
+    /// Reads the first `out.len()` bytes the sensor hub has stored, up to 18,
+    /// from SENSORHUB1_REG onward (SENSOR_HUB_1 in the sensor hub bank on the LSM6DSO).
+    /// More than 18 bytes is `Error::InvalidConfig`.
+    pub fn read_sensor_hub(&mut self, out: &mut [u8]) -> Result<(), Error<E>> {
+        self.require_sensor_hub()?;
+        self.read_sensor_hub_at(0, out)
+    }
+
+    /// Reads the bytes the sensor hub last read through `slot` into the start of `out`, returning how many there were.
+    /// The window is found from the number of bytes each slot is configured to read,
+    /// which is read from their configuration registers first, so it is right however the slots were set up.
+    /// A slot that reads nothing gives 0 bytes. The data may be from the cycle before if it is read
+    /// while a cycle is under way, so `EmbFuncStatus::sensor_hub_end_op` should be checked first for fresh data.
+    /// An `out` shorter than the slot's data, or data past the 18 output registers, is `Error::InvalidConfig`.
+    pub fn read_external_sensor(
+        &mut self,
+        slot: SlaveSlot,
+        out: &mut [u8],
+    ) -> Result<usize, Error<E>> {
+        self.require_sensor_hub()?;
+        let slv0_add = if self.variant.has_dso_registers() {
+            registers::lsm6dso::SLV0_ADD
+        } else {
+            registers::lsm6dsl::SLV0_ADD
+        };
+        let mut slaves = [0; 12];
+        let slaves = &mut slaves[..3 * (slot.index() + 1)];
+        self.with_sensor_hub_bank(|this| this.read_registers(slv0_add, slaves))?;
+        // The lowest three bits of each configuration register are the number of bytes to read
+        let mut lens = slaves.chunks(3).map(|slave| (slave[2] & 0b111) as usize);
+        let offset: usize = lens.by_ref().take(slot.index()).sum();
+        let len = lens.next().unwrap_or(0);
+        if out.len() < len || offset + len > 18 {
+            return Err(Error::InvalidConfig);
+        }
+        self.read_sensor_hub_at(offset, &mut out[..len])?;
+        Ok(len)
+    }
+
+    /// Stores what the sensor hub reads through `slot` in the FIFO, or stops storing it,
+    /// with the BATCH_EXT_SENS_EN bit of the slot's SLVx_CONFIG. It shows up as `FifoWord::ExternalSensor`.
+    /// `LSM6::configure_sensor_hub_slave0` overwrites this bit for slot 0, so this should be called after it.
+    /// Only the LSM6DSO and LSM6DSOX tag their FIFO entries, so anywhere else this returns `Error::Unsupported`.
+    pub fn set_sensor_hub_batching(
+        &mut self,
+        slot: SlaveSlot,
+        enabled: bool,
+    ) -> Result<(), Error<E>> {
+        if !self.variant.has_dso_registers() {
+            return Err(Error::Unsupported);
+        }
+        let reg = registers::lsm6dso::SLV0_CONFIG + 3 * slot.index() as u8;
+        self.with_sensor_hub_bank(|this| {
+            let config = this.read_register_raw(reg)?;
+            let config = if enabled {
+                config | 0b1000
+            } else {
+                config & !0b1000
+            };
+            this.set_register_raw(reg, config)
+        })
+    }
+
+    /// Reads the first six bytes the sensor hub has stored as three little endian values,
+    /// such as the x, y and z axes of a magnetometer like the LIS3MDL read from its OUT_X_L register.
+    /// The values are in the external sensor's own units and axes.
+    pub fn read_sensor_hub_xyz(&mut self) -> Result<(i16, i16, i16), Error<E>> {
+        let mut bytes = [0; 6];
+        self.read_sensor_hub(&mut bytes)?;
+        Ok(decode_xyz(&bytes))
+    }
+
+    /// Reads the sensor hub's outputs from `offset` bytes in, where `offset + out.len()` is at most 18.
+    fn read_sensor_hub_at(&mut self, offset: usize, out: &mut [u8]) -> Result<(), Error<E>> {
+        if offset + out.len() > 18 {
+            return Err(Error::InvalidConfig);
+        }
+        if out.is_empty() {
+            return Ok(());
+        }
+        if self.variant.has_dso_registers() {
+            return self.with_sensor_hub_bank(|this| {
+                this.read_registers(registers::lsm6dso::SENSOR_HUB_1 + offset as u8, out)
+            });
+        }
+        // SENSORHUB13_REG onwards aren't next to the first twelve
+        let (first, rest) = out.split_at_mut(out.len().min(12usize.saturating_sub(offset)));
+        if !first.is_empty() {
+            self.read_registers(registers::lsm6dsl::SENSORHUB1_REG + offset as u8, first)?;
+        }
+        if !rest.is_empty() {
+            let rest_offset = offset.max(12) - 12;
+            self.read_registers(
+                registers::lsm6dsl::SENSORHUB13_REG + rest_offset as u8,
+                rest,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Bridges the auxiliary bus to the primary one while `f` runs, so the external sensor can be reached
+    /// at its own address through the bus given to `f`, e.g. to configure a magnetometer before the hub reads it.
+    /// This only works with the LSM6 on an I2C bus.
+    ///
+    /// The master is stopped first: its trigger is moved to INT2 so no new cycle starts, then after 5 ms,
+    /// long enough for a cycle already under way to finish, MASTER_ON is cleared and PASS_THROUGH_MODE set.
+    /// Afterwards MASTER_CONFIG is put back as it was with pass-through cleared, restarting the master if it was on.
+    /// The bridge is closed even if `f` or entering pass-through fails, and `f`'s error takes priority.
+    ///
+    /// While the bridge is open every transaction on the primary bus also reaches the auxiliary bus,
+    /// so `f` should only talk to the external sensor and not to the LSM6 itself,
+    /// and an LSM6 address that is also used on the auxiliary bus must be avoided.
+    /// On the LSM6DS33 this returns `Error::Unsupported` without touching the bus.
+    pub fn with_passthrough<D: DelayMs<u8>, T>(
+        &mut self,
+        delay: &mut D,
+        f: impl FnOnce(&mut I) -> Result<T, E>,
+    ) -> Result<T, Error<E>> {
+        self.require_sensor_hub()?;
+        let (start_config, pass_through, master_on) = if self.variant.has_dso_registers() {
+            (0b100000, 0b10000, 0b100)
+        } else {
+            (0b10000, 0b100, 1)
+        };
+        let prev = self.read_master_config()?;
+        self.write_master_config(prev | start_config)?;
+        delay.delay_ms(5);
+        let result = self
+            .write_master_config((prev | start_config | pass_through) & !master_on)
+            .and_then(|()| f(&mut self.bus).map_err(Error::Bus));
+        let exit = self.write_master_config(prev & !pass_through);
+        let value = result?;
+        exit?;
+        Ok(value)
+    }
+
+    /// Reads MASTER_CONFIG, which is in the sensor hub bank on the LSM6DSO.
+    fn read_master_config(&mut self) -> Result<u8, Error<E>> {
+        if self.variant.has_dso_registers() {
+            self.with_sensor_hub_bank(|this| {
+                this.read_register_raw(registers::lsm6dso::MASTER_CONFIG)
+            })
+        } else {
+            self.read_register_raw(registers::lsm6dsl::MASTER_CONFIG)
+        }
+    }
+
+    fn write_master_config(&mut self, value: u8) -> Result<(), Error<E>> {
+        if self.variant.has_dso_registers() {
+            self.with_sensor_hub_bank(|this| {
+                this.set_register_raw(registers::lsm6dso::MASTER_CONFIG, value)
+            })
+        } else {
+            self.set_register_raw(registers::lsm6dsl::MASTER_CONFIG, value)
+        }
+    }
+
+    fn require_sensor_hub(&self) -> Result<(), Error<E>> {
+        if self.variant == ChipVariant::Lsm6ds33 {
+            Err(Error::Unsupported)
+        } else {
+            Ok(())
+        }
+    }
+}
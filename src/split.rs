@@ -0,0 +1,123 @@
+use crate::{
+    interface::RegisterInterface, registers, AccelerometerMode, AccelerometerScale, Error,
+    GyroscopeMode, GyroscopeScale, LSM6,
+};
+
+/// The accelerometer half of a split `LSM6`, see `LSM6::split`.
+pub struct AccelHandle<I> {
+    imu: LSM6<I>,
+}
+
+/// The gyroscope half of a split `LSM6`, see `LSM6::split`.
+pub struct GyroHandle<I> {
+    imu: LSM6<I>,
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Splits the driver into independent accelerometer and gyroscope handles,
+    /// each with its own bus, so they can be owned by different tasks.
+    /// This driver's bus goes to the accelerometer and `gyro_bus` to the gyroscope,
+    /// so both should be proxies of the same shared bus.
+    /// The handles only touch their own sensor's registers, except for data-ready routing,
+    /// which shares INT1_CTRL and so shouldn't be changed from both tasks at once.
+    pub fn split_with(self, gyro_bus: I) -> (AccelHandle<I>, GyroHandle<I>) {
+        let gyro = self.with_bus(gyro_bus);
+        (AccelHandle { imu: self }, GyroHandle { imu: gyro })
+    }
+
+    /// Splits the driver like `LSM6::split_with`, cloning the bus for the gyroscope.
+    pub fn split(self) -> (AccelHandle<I>, GyroHandle<I>)
+    where
+        I: Clone,
+    {
+        let gyro_bus = self.bus.clone();
+        self.split_with(gyro_bus)
+    }
+
+    /// Reassembles a driver from the handles returned by `LSM6::split`,
+    /// keeping the accelerometer's bus and dropping the gyroscope's.
+    /// The shadow cache starts out empty, since each handle only saw its own writes.
+    pub fn join(accel: AccelHandle<I>, gyro: GyroHandle<I>) -> Self {
+        let mut imu = LSM6 {
+            gyro_mode: gyro.imu.gyro_mode,
+            gyro_scale: gyro.imu.gyro_scale,
+            ..accel.imu
+        };
+        imu.invalidate_cache();
+        imu
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> AccelHandle<I> {
+    /// See `LSM6::set_accel_mode`.
+    pub fn set_mode(&mut self, mode: AccelerometerMode) -> Result<(), Error<E>> {
+        self.imu.set_accel_mode(mode)
+    }
+
+    /// See `LSM6::set_accel_scale`.
+    pub fn set_scale(&mut self, scale: AccelerometerScale) -> Result<(), Error<E>> {
+        self.imu.set_accel_scale(scale)
+    }
+
+    /// The scale the accelerometer was last set to.
+    pub fn scale(&self) -> AccelerometerScale {
+        self.imu.accel_scale
+    }
+
+    /// See `LSM6::read_accel`.
+    pub fn read(&mut self) -> Result<Option<(i16, i16, i16)>, Error<E>> {
+        self.imu.read_accel()
+    }
+
+    /// See `LSM6::read_accel_g`.
+    pub fn read_g(&mut self) -> Result<Option<(f32, f32, f32)>, Error<E>> {
+        self.imu.read_accel_g()
+    }
+
+    /// Sets whether the accelerometer data-ready signal is routed to the INT1 pin,
+    /// keeping the rest of the INT1_CTRL register as it is.
+    /// The register is always read from the device, since the other handle may have changed it.
+    pub fn set_int1_data_ready(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        self.imu.forget_cached(registers::INT1_CTRL);
+        self.imu
+            .modify_register(registers::INT1_CTRL, 1, if enabled { 1 } else { 0 })?;
+        Ok(())
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> GyroHandle<I> {
+    /// See `LSM6::set_gyro_mode`.
+    pub fn set_mode(&mut self, mode: GyroscopeMode) -> Result<(), Error<E>> {
+        self.imu.set_gyro_mode(mode)
+    }
+
+    /// See `LSM6::set_gyro_scale`.
+    pub fn set_scale(&mut self, scale: GyroscopeScale) -> Result<(), Error<E>> {
+        self.imu.set_gyro_scale(scale)
+    }
+
+    /// The scale the gyroscope was last set to.
+    pub fn scale(&self) -> GyroscopeScale {
+        self.imu.gyro_scale
+    }
+
+    /// See `LSM6::read_gyro`.
+    pub fn read(&mut self) -> Result<Option<(i16, i16, i16)>, Error<E>> {
+        self.imu.read_gyro()
+    }
+
+    /// See `LSM6::read_gyro_dps`.
+    pub fn read_dps(&mut self) -> Result<Option<(f32, f32, f32)>, Error<E>> {
+        self.imu.read_gyro_dps()
+    }
+
+    /// Sets whether the gyroscope data-ready signal is routed to the INT1 pin,
+    /// keeping the rest of the INT1_CTRL register as it is.
+    /// The register is always read from the device, since the other handle may have changed it.
+    pub fn set_int1_data_ready(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        self.imu.forget_cached(registers::INT1_CTRL);
+        self.imu
+            .modify_register(registers::INT1_CTRL, 0b10, if enabled { 0b10 } else { 0 })?;
+        Ok(())
+    }
+}
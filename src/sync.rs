@@ -0,0 +1,113 @@
+//! Sensor synchronization on the LSM6DSL and LSM6DSM, for lining samples up with an external trigger such as a camera.
+
+use crate::{interface::RegisterInterface, registers, ChipVariant, Error, LSM6};
+
+/// The resolution of the error code that sensor synchronization computes,
+/// from the SENSOR_SYNC_RES_RATIO register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyncResolution {
+    /// 2^-11, which is the default.
+    Ratio2048,
+    /// 2^-12.
+    Ratio4096,
+    /// 2^-13.
+    Ratio8192,
+    /// 2^-14.
+    Ratio16384,
+}
+
+impl SyncResolution {
+    fn to_bitcode(self) -> u8 {
+        match self {
+            SyncResolution::Ratio2048 => 0,
+            SyncResolution::Ratio4096 => 1,
+            SyncResolution::Ratio8192 => 0b10,
+            SyncResolution::Ratio16384 => 0b11,
+        }
+    }
+}
+
+/// The sensor synchronization time frame, which is counted in steps of `SyncTimeFrame::STEP_MS`
+/// up to `SyncTimeFrame::MAX_MS`, along with the resolution of the synchronization error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncTimeFrame {
+    steps: u8,
+    resolution: SyncResolution,
+}
+
+impl SyncTimeFrame {
+    /// The length of one step of the time frame, in milliseconds.
+    pub const STEP_MS: u32 = 500;
+    /// The longest time frame, in milliseconds.
+    pub const MAX_MS: u32 = 5000;
+
+    /// Quantizes `frame_ms` to the nearest step, or returns `None` if it is over `SyncTimeFrame::MAX_MS`.
+    /// `SyncTimeFrame::frame_ms` gives the frame that will actually be set.
+    pub fn from_ms(frame_ms: u32, resolution: SyncResolution) -> Option<Self> {
+        if frame_ms > Self::MAX_MS {
+            return None;
+        }
+        Some(SyncTimeFrame {
+            steps: ((frame_ms + Self::STEP_MS / 2) / Self::STEP_MS) as u8,
+            resolution,
+        })
+    }
+
+    /// The time frame after quantization, in milliseconds.
+    pub fn frame_ms(&self) -> u32 {
+        self.steps as u32 * Self::STEP_MS
+    }
+
+    pub fn resolution(&self) -> SyncResolution {
+        self.resolution
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Sets up sensor synchronization: writes `frame` to SENSOR_SYNC_TIME_FRAME and SENSOR_SYNC_RES_RATIO in one burst,
+    /// turns on the edge-sensitive DEN trigger with the TRIG_EN bit of CTRL6_C (clearing LVL_EN and LVL2_EN),
+    /// and turns on the timestamp counter with the TIMER_EN bit of CTRL10_C.
+    /// The rest of CTRL6_C and CTRL10_C is kept.
+    /// Only the LSM6DSL and LSM6DSM have sensor synchronization, so anywhere else this returns `Error::Unsupported`.
+    ///
+    /// The trigger goes to the DEN pin. A rising edge on DEN marks the next sample the sensor produces,
+    /// so that sample is taken up to one output period after the edge: 9.6 ms at 104 Hz, or 0.6 ms at 1.66 kHz.
+    /// The marked sample has DEN stamped into the least significant bit of the axes enabled in CTRL9_XL,
+    /// in the gyroscope's output by default or the accelerometer's after `LSM6::set_den_sensor`,
+    /// so it can be told apart both in the output registers and in the FIFO, which keeps storing at its own rate.
+    /// The timestamp counter runs alongside at 25 µs per tick with the TIMER_HR bit of WAKE_UP_DUR set,
+    /// or 6.4 ms per tick otherwise, so reading TIMESTAMP0_REG onward when the stamped sample is found
+    /// gives its time to within a tick.
+    ///
+    /// The time frame is much coarser than a camera's frame period, such as 33 ms at 30 Hz,
+    /// so the per-frame alignment comes from the DEN edges rather than from it.
+    pub fn configure_sensor_sync(&mut self, frame: SyncTimeFrame) -> Result<(), Error<E>> {
+        self.require_sensor_sync()?;
+        self.write_registers(&[
+            registers::lsm6dsl::SENSOR_SYNC_TIME_FRAME,
+            frame.steps,
+            frame.resolution.to_bitcode(),
+        ])?;
+        self.modify_register(registers::CTRL6_C, 0b11100000, 0b10000000)?;
+        self.set_bits(registers::CTRL10_C, 0b100000)?;
+        Ok(())
+    }
+
+    /// Turns the DEN trigger off and clears the time frame, leaving the timestamp counter running.
+    pub fn disable_sensor_sync(&mut self) -> Result<(), Error<E>> {
+        self.require_sensor_sync()?;
+        self.clear_bits(registers::CTRL6_C, 0b11100000)?;
+        self.set_register_raw(registers::lsm6dsl::SENSOR_SYNC_TIME_FRAME, 0)
+    }
+
+    fn require_sensor_sync(&self) -> Result<(), Error<E>> {
+        match self.variant {
+            ChipVariant::Lsm6dsl | ChipVariant::Lsm6dsm => Ok(()),
+            _ => Err(Error::Unsupported),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{interface::RegisterInterface, math, Error, Vector3, LSM6};
+
+/// Static tilt angles in degrees, estimated from the direction of gravity.
+///
+/// Roll is the rotation about the x axis, in `(-180, 180]`, and pitch the rotation about the y axis,
+/// in `[-90, 90]`. Both are 0 when the sensor lies flat with z up, and pitch is positive when the
+/// x axis tilts down. As pitch approaches ±90°, y and z only see noise, so roll stops meaning anything.
+/// It is still computed from whatever is left, and is 0 once y and z are both exactly 0.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TiltAngles {
+    pub pitch: f32,
+    pub roll: f32,
+}
+
+impl TiltAngles {
+    /// The pitch and roll in radians.
+    pub fn radians(&self) -> (f32, f32) {
+        (self.pitch.to_radians(), self.roll.to_radians())
+    }
+}
+
+/// The tilt angles of an accelerometer reading taken while the sensor isn't accelerating.
+/// The reading can be in any units, e.g. from `LSM6::read_accel_g` or raw counts.
+/// This never returns NaN for finite readings; a reading of 0 on every axis, as in free fall, gives 0 for both.
+pub fn tilt_angles(accel: Vector3<f32>) -> TiltAngles {
+    let roll = if accel.y == 0. && accel.z == 0. {
+        0.
+    } else {
+        math::atan2(accel.y, accel.z)
+    };
+    let horizontal = math::sqrt(accel.y * accel.y + accel.z * accel.z);
+    let pitch = if accel.x == 0. && horizontal == 0. {
+        0.
+    } else {
+        math::atan2(-accel.x, horizontal)
+    };
+    TiltAngles {
+        pitch: pitch.to_degrees(),
+        roll: roll.to_degrees(),
+    }
+}
+
+/// The state of an `OrientationFilter`, which can be kept across a sleep and restored with
+/// `OrientationFilter::from_state`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrientationState {
+    /// The current estimate, or `None` before the first update.
+    pub angles: Option<(f32, f32)>,
+    /// The timestamp of the last `OrientationFilter::update_with_timestamp`, if any.
+    pub last_timestamp: Option<u32>,
+}
+
+/// Estimates pitch and roll by fusing gyroscope integration with the accelerometer's tilt
+/// in a complementary filter. The gyroscope tracks fast changes and the accelerometer
+/// pulls out the gyroscope's drift, with the crossover set by the time constant.
+/// The angles follow the conventions of `TiltAngles`.
+///
+/// The first update starts from the accelerometer's tilt, so there is no startup transient.
+/// While the acceleration is more than 0.5 g away from 1 g, e.g. during impacts or hard turns,
+/// the accelerometer's direction isn't gravity's, so the filter follows the gyroscope alone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientationFilter {
+    time_constant: f32,
+    state: OrientationState,
+}
+
+impl OrientationFilter {
+    /// Creates a filter with the given time constant in seconds.
+    /// Longer time constants trust the gyroscope for longer, rejecting more accelerometer noise
+    /// but correcting drift more slowly; around 0.5 to 2 s suits most robots.
+    pub fn new(time_constant: f32) -> Self {
+        Self::from_state(time_constant, OrientationState::default())
+    }
+
+    /// Creates a filter that carries on from a state saved with `OrientationFilter::state`.
+    pub fn from_state(time_constant: f32, state: OrientationState) -> Self {
+        Self {
+            time_constant,
+            state,
+        }
+    }
+
+    /// The filter's state, for saving across a sleep.
+    pub fn state(&self) -> OrientationState {
+        self.state
+    }
+
+    /// Forgets the estimate, so the next update starts from the accelerometer's tilt again.
+    pub fn reset(&mut self) {
+        self.state = OrientationState::default();
+    }
+
+    /// Adds a gyroscope reading in degrees per second and an accelerometer reading in g,
+    /// such as from `LSM6::read_gyro_dps` and `LSM6::read_accel_g`, taken `dt` seconds after the last.
+    /// Returns the new estimate. A `dt` that isn't positive and finite skips the gyroscope.
+    pub fn update(&mut self, gyro_dps: Vector3<f32>, accel_g: Vector3<f32>, dt: f32) -> TiltAngles {
+        let tilt = tilt_angles(accel_g);
+        let (pitch, roll) = match self.state.angles {
+            None => (tilt.pitch, tilt.roll),
+            Some((pitch, roll)) => {
+                let dt = if dt > 0. && dt.is_finite() { dt } else { 0. };
+                let pitch = pitch + gyro_dps.y * dt;
+                let roll = roll + gyro_dps.x * dt;
+                if (accel_g.magnitude() - 1.).abs() > 0.5 {
+                    (pitch, roll)
+                } else {
+                    let weight = dt / (self.time_constant + dt);
+                    (
+                        pitch + (tilt.pitch - pitch) * weight,
+                        roll + wrap_180(tilt.roll - roll) * weight,
+                    )
+                }
+            }
+        };
+        let angles = (pitch.clamp(-90., 90.), wrap_180(roll));
+        self.state.angles = Some(angles);
+        TiltAngles {
+            pitch: angles.0,
+            roll: angles.1,
+        }
+    }
+
+    /// Like `OrientationFilter::update`, but with the time between readings taken from the LSM6's
+    /// timestamp counter, e.g. from `FifoWord::Timestamp`, which ticks every 25 µs.
+    /// The first reading after creating or resetting the filter only starts the estimate.
+    pub fn update_with_timestamp(
+        &mut self,
+        gyro_dps: Vector3<f32>,
+        accel_g: Vector3<f32>,
+        timestamp: u32,
+    ) -> TiltAngles {
+        let dt = self
+            .state
+            .last_timestamp
+            .map_or(0., |last| timestamp.wrapping_sub(last) as f32 * 25e-6);
+        self.state.last_timestamp = Some(timestamp);
+        self.update(gyro_dps, accel_g, dt)
+    }
+}
+
+/// Wraps an angle in degrees to `(-180, 180]`.
+fn wrap_180(degrees: f32) -> f32 {
+    let wrapped = degrees % 360.;
+    if wrapped > 180. {
+        wrapped - 360.
+    } else if wrapped <= -180. {
+        wrapped + 360.
+    } else {
+        wrapped
+    }
+}
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Reads the latest acceleration data and estimates the tilt angles from it with `tilt_angles`.
+    /// This has the same requirements and `None` behavior as `LSM6::read_accel`.
+    pub fn read_tilt_angles(&mut self) -> Result<Option<TiltAngles>, Error<E>> {
+        Ok(self
+            .read_accel_g()?
+            .map(|(x, y, z)| tilt_angles(Vector3 { x, y, z })))
+    }
+}
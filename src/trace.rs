@@ -0,0 +1,113 @@
+//! Tracing of every register transfer made by `LSM6` and `AsyncLSM6`, for bringing up a board.
+//!
+//! With the `trace-log` feature, each transfer is logged at trace level through `log`,
+//! and with `trace-defmt` through `defmt`. Reads of STATUS_REG, the sensor outputs and the FIFO
+//! are left out unless `trace-data` is also enabled, since they happen at the data rate.
+//! Without either backend, these functions are empty and compile to nothing.
+
+#[cfg(any(feature = "trace-log", feature = "trace-defmt"))]
+use crate::registers::{self, Register};
+
+/// Whether a transfer starting at `start` is traced.
+#[cfg(any(feature = "trace-log", feature = "trace-defmt"))]
+fn traced(start: u8, read: bool) -> bool {
+    let data = matches!(
+        start,
+        registers::STATUS_REG
+            | registers::OUT_TEMP_L..=registers::OUTZ_H_XL
+            | registers::FIFO_STATUS1..=registers::FIFO_DATA_OUT_H
+            | registers::lsm6dso::FIFO_DATA_OUT_TAG
+    );
+    cfg!(feature = "trace-data") || !read || !data
+}
+
+/// The datasheet name of the register at `start`, or "?" for reserved registers
+/// and those in the embedded function bank.
+#[cfg(any(feature = "trace-log", feature = "trace-defmt"))]
+fn name(start: u8, embedded_bank: bool) -> &'static str {
+    match Register::from_addr(start) {
+        Some(reg) if !embedded_bank => reg.name(),
+        _ => "?",
+    }
+}
+
+/// Traces a write of `bytes[1..]` starting at register `bytes[0]`.
+#[inline(always)]
+pub(crate) fn write<T, E>(address: u8, embedded_bank: bool, bytes: &[u8], result: &Result<T, E>) {
+    #[cfg(any(feature = "trace-log", feature = "trace-defmt"))]
+    if traced(bytes[0], false) {
+        let reg = name(bytes[0], embedded_bank);
+        let outcome = if result.is_ok() { "ok" } else { "failed" };
+        #[cfg(feature = "trace-log")]
+        log::trace!(
+            "lsm6 {:#04x}: write {} ({:#04x}) {:02x?}: {}",
+            address,
+            reg,
+            bytes[0],
+            &bytes[1..],
+            outcome
+        );
+        #[cfg(feature = "trace-defmt")]
+        defmt::trace!(
+            "lsm6 {=u8:#04x}: write {=str} ({=u8:#04x}) {=[u8]:02x}: {=str}",
+            address,
+            reg,
+            bytes[0],
+            &bytes[1..],
+            outcome
+        );
+    }
+    #[cfg(not(any(feature = "trace-log", feature = "trace-defmt")))]
+    let _ = (address, embedded_bank, bytes, result);
+}
+
+/// Traces a read into `buffer` starting at register `start`.
+#[inline(always)]
+pub(crate) fn read<T, E>(
+    address: u8,
+    embedded_bank: bool,
+    start: u8,
+    buffer: &[u8],
+    result: &Result<T, E>,
+) {
+    #[cfg(any(feature = "trace-log", feature = "trace-defmt"))]
+    if traced(start, true) {
+        let reg = name(start, embedded_bank);
+        #[cfg(feature = "trace-log")]
+        match result {
+            Ok(_) => log::trace!(
+                "lsm6 {:#04x}: read {} ({:#04x}) {:02x?}: ok",
+                address,
+                reg,
+                start,
+                buffer
+            ),
+            Err(_) => log::trace!(
+                "lsm6 {:#04x}: read {} ({:#04x}) of {} bytes: failed",
+                address,
+                reg,
+                start,
+                buffer.len()
+            ),
+        }
+        #[cfg(feature = "trace-defmt")]
+        match result {
+            Ok(_) => defmt::trace!(
+                "lsm6 {=u8:#04x}: read {=str} ({=u8:#04x}) {=[u8]:02x}: ok",
+                address,
+                reg,
+                start,
+                buffer
+            ),
+            Err(_) => defmt::trace!(
+                "lsm6 {=u8:#04x}: read {=str} ({=u8:#04x}) of {=usize} bytes: failed",
+                address,
+                reg,
+                start,
+                buffer.len()
+            ),
+        }
+    }
+    #[cfg(not(any(feature = "trace-log", feature = "trace-defmt")))]
+    let _ = (address, embedded_bank, start, buffer, result);
+}
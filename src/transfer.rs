@@ -0,0 +1,144 @@
+//! Transfers for buses driven outside the driver, e.g. by DMA.
+//!
+//! The driver describes what to read with a `Transfer`, the read is done however suits the application,
+//! and the filled buffer is handed back to one of the `LSM6::parse_*` methods.
+//! These work alongside the driver's own methods, and parsing updates the driver's state
+//! the same way the matching read would.
+//! The register addresses assume the IF_INC bit of CTRL3_C is set, which the constructors do.
+
+use crate::{registers, FifoStatus, FifoWord, LSM6};
+
+/// How the bytes of a `Transfer` are laid out, and which method parses them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Layout {
+    /// STATUS_REG, where bit 0 is set when accelerometer data is ready and bit 1 when gyroscope data is.
+    Status,
+    /// X, Y and Z as little endian 16-bit words. See `LSM6::parse_xyz`.
+    Xyz,
+    /// The FIFO status registers. See `LSM6::parse_fifo_status`.
+    FifoStatus,
+    /// Whole samples from the FIFO, as for `Layout::Xyz`. See `LSM6::parse_fifo`.
+    Fifo,
+    /// Whole entries from a tagged FIFO, a tag byte followed by six data bytes each.
+    /// See `LSM6::parse_fifo_tagged`.
+    TaggedFifo,
+}
+
+/// A read of consecutive registers: `len` bytes starting at register `start`.
+/// Over i2c, this is a write of `start` followed by a read of `len` bytes from the device's address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Transfer {
+    pub start: u8,
+    pub len: usize,
+    pub layout: Layout,
+}
+
+impl Transfer {
+    /// The byte to send first over SPI, which is `start` with the read bit set.
+    pub fn spi_command(&self) -> u8 {
+        self.start | 0b10000000
+    }
+}
+
+impl<I> LSM6<I> {
+    /// The transfer of STATUS_REG.
+    pub fn status_transfer(&self) -> Transfer {
+        Transfer {
+            start: registers::STATUS_REG,
+            len: 1,
+            layout: Layout::Status,
+        }
+    }
+
+    /// The transfer of the accelerometer output, as read by `LSM6::read_accel`.
+    pub fn accel_transfer(&self) -> Transfer {
+        Transfer {
+            start: registers::OUTX_L_XL,
+            len: 6,
+            layout: Layout::Xyz,
+        }
+    }
+
+    /// The transfer of the gyroscope output, as read by `LSM6::read_gyro`.
+    pub fn gyro_transfer(&self) -> Transfer {
+        Transfer {
+            start: registers::OUTX_L_G,
+            len: 6,
+            layout: Layout::Xyz,
+        }
+    }
+
+    /// The transfer of the FIFO status registers, which are two on the LSM6DSO and four otherwise.
+    pub fn fifo_status_transfer(&self) -> Transfer {
+        Transfer {
+            start: registers::FIFO_STATUS1,
+            len: if self.variant.has_dso_registers() {
+                2
+            } else {
+                4
+            },
+            layout: Layout::FifoStatus,
+        }
+    }
+
+    /// The transfer of as many whole samples, or tagged entries on the LSM6DSO,
+    /// as `status` says are unread and fit in `max_len` bytes. `len` is 0 if there is nothing to read.
+    pub fn fifo_transfer(&self, status: &FifoStatus, max_len: usize) -> Transfer {
+        if self.variant.has_dso_registers() {
+            Transfer {
+                start: registers::lsm6dso::FIFO_DATA_OUT_TAG,
+                len: (status.unread_words as usize).min(max_len / 7) * 7,
+                layout: Layout::TaggedFifo,
+            }
+        } else {
+            Transfer {
+                start: registers::FIFO_DATA_OUT_L,
+                len: (status.unread_words as usize / 3).min(max_len / 6) * 6,
+                layout: Layout::Fifo,
+            }
+        }
+    }
+
+    /// Parses a `Layout::Xyz` transfer.
+    pub fn parse_xyz(&self, bytes: &[u8; 6]) -> (i16, i16, i16) {
+        self.mounting.decode(bytes)
+    }
+
+    /// Parses a `Layout::FifoStatus` transfer, taking the FIFO pattern position from it.
+    pub fn parse_fifo_status(&mut self, bytes: &[u8]) -> FifoStatus {
+        let status = FifoStatus::decode(self.variant, bytes);
+        if let Some(pattern) = status.pattern {
+            self.fifo_pattern = pattern;
+        }
+        status
+    }
+
+    /// Parses a `Layout::Fifo` transfer into `samples`, returning how many there were,
+    /// and moves the FIFO pattern position along by the samples parsed.
+    /// See `LSM6::read_fifo` for the ordering of samples.
+    pub fn parse_fifo(&mut self, bytes: &[u8], samples: &mut [(i16, i16, i16)]) -> usize {
+        let mut count = 0;
+        for (chunk, sample) in bytes.chunks_exact(6).zip(samples.iter_mut()) {
+            let mut values = [0; 6];
+            values.copy_from_slice(chunk);
+            *sample = self.mounting.decode(&values);
+            self.advance_fifo_pattern(3);
+            count += 1;
+        }
+        count
+    }
+
+    /// Parses a `Layout::TaggedFifo` transfer into `words`, returning how many there were.
+    pub fn parse_fifo_tagged(&self, bytes: &[u8], words: &mut [FifoWord]) -> usize {
+        let mut count = 0;
+        for (chunk, word) in bytes.chunks_exact(7).zip(words.iter_mut()) {
+            let mut entry = [0; 7];
+            entry.copy_from_slice(chunk);
+            *word = self.mounting.apply_fifo_word(FifoWord::decode(&entry));
+            count += 1;
+        }
+        count
+    }
+}
@@ -0,0 +1,93 @@
+//! Readings as `uom` quantities, behind the `uom` feature.
+//!
+//! The acceleration, angular velocity and temperature readings are available on `LSM6`,
+//! and on `AsyncLSM6` with the `async` feature too.
+//! These use the same conversions as the plain float methods (`AccelerometerScale::to_g`,
+//! `GyroscopeScale::to_dps` and `ChipVariant::to_celsius`), so the two always agree.
+//! Quantities of different dimensions can't be mixed up:
+//!
+//! ```compile_fail
+//! use uom::si::{
+//!     acceleration::standard_gravity,
+//!     f32::{Acceleration, Velocity},
+//!     velocity::meter_per_second,
+//! };
+//!
+//! let accel = Acceleration::new::<standard_gravity>(1.);
+//! let velocity = Velocity::new::<meter_per_second>(1.);
+//! let _ = accel + velocity;
+//! ```
+
+use uom::si::{
+    acceleration::standard_gravity,
+    angular_velocity::degree_per_second,
+    f32::{Acceleration, AngularVelocity, ThermodynamicTemperature},
+    thermodynamic_temperature::degree_celsius,
+};
+
+use crate::{interface::RegisterInterface, Error, Vector3, LSM6};
+
+impl<E, I: RegisterInterface<Error = E>> LSM6<I> {
+    /// Reads the latest acceleration data, using the current scale.
+    /// This has the same requirements and `None` behavior as `LSM6::read_accel`.
+    pub fn read_acceleration(&mut self) -> Result<Option<Vector3<Acceleration>>, Error<E>> {
+        Ok(self.read_accel_g()?.map(|(x, y, z)| Vector3 {
+            x: Acceleration::new::<standard_gravity>(x),
+            y: Acceleration::new::<standard_gravity>(y),
+            z: Acceleration::new::<standard_gravity>(z),
+        }))
+    }
+
+    /// Reads the latest gyroscopic data, using the current scale.
+    /// This has the same requirements and `None` behavior as `LSM6::read_gyro`.
+    pub fn read_angular_velocity(&mut self) -> Result<Option<Vector3<AngularVelocity>>, Error<E>> {
+        Ok(self.read_gyro_dps()?.map(|(x, y, z)| Vector3 {
+            x: AngularVelocity::new::<degree_per_second>(x),
+            y: AngularVelocity::new::<degree_per_second>(y),
+            z: AngularVelocity::new::<degree_per_second>(z),
+        }))
+    }
+
+    /// Reads the latest temperature.
+    /// This has the same `None` behavior as `LSM6::read_temperature`.
+    pub fn read_thermodynamic_temperature(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, Error<E>> {
+        Ok(self
+            .read_temperature_celsius()?
+            .map(ThermodynamicTemperature::new::<degree_celsius>))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<E, I: embedded_hal_async::i2c::I2c<Error = E>> crate::AsyncLSM6<I> {
+    /// See `LSM6::read_acceleration`.
+    pub async fn read_acceleration(&mut self) -> Result<Option<Vector3<Acceleration>>, Error<E>> {
+        Ok(self.read_accel_g().await?.map(|(x, y, z)| Vector3 {
+            x: Acceleration::new::<standard_gravity>(x),
+            y: Acceleration::new::<standard_gravity>(y),
+            z: Acceleration::new::<standard_gravity>(z),
+        }))
+    }
+
+    /// See `LSM6::read_angular_velocity`.
+    pub async fn read_angular_velocity(
+        &mut self,
+    ) -> Result<Option<Vector3<AngularVelocity>>, Error<E>> {
+        Ok(self.read_gyro_dps().await?.map(|(x, y, z)| Vector3 {
+            x: AngularVelocity::new::<degree_per_second>(x),
+            y: AngularVelocity::new::<degree_per_second>(y),
+            z: AngularVelocity::new::<degree_per_second>(z),
+        }))
+    }
+
+    /// See `LSM6::read_thermodynamic_temperature`.
+    pub async fn read_thermodynamic_temperature(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, Error<E>> {
+        Ok(self
+            .read_temperature_celsius()
+            .await?
+            .map(ThermodynamicTemperature::new::<degree_celsius>))
+    }
+}
@@ -0,0 +1,250 @@
+//! Checks the async driver, including futures that are dropped before they complete.
+//! Run with `--features async`.
+
+#![cfg(feature = "async")]
+
+use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use lsm6ds33::{
+    registers, AccelerometerMode, AccelerometerScale, AsyncLSM6, Axis, AxisMapping, Error,
+};
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// A simulated LSM6 answering at `address`, which records every write.
+/// Once `stall_after` counts down to zero, the next transaction is held up until it is polled again.
+struct Bus {
+    address: u8,
+    registers: [u8; 0x80],
+    writes: Vec<Vec<u8>>,
+    stall_after: Rc<Cell<Option<u32>>>,
+}
+
+impl Bus {
+    fn new(address: u8, who_am_i: u8) -> Self {
+        let mut registers = [0; 0x80];
+        registers[registers::WHO_AM_I as usize] = who_am_i;
+        Bus {
+            address,
+            registers,
+            writes: Vec::new(),
+            stall_after: Rc::new(Cell::new(None)),
+        }
+    }
+}
+
+/// A future that is pending the first time it is polled.
+struct Stall(bool);
+
+impl Future for Stall {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            Poll::Pending
+        }
+    }
+}
+
+impl ErrorType for Bus {
+    type Error = ErrorKind;
+}
+
+impl I2c for Bus {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        if address != self.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        match self.stall_after.get() {
+            Some(0) => {
+                self.stall_after.set(None);
+                Stall(false).await;
+            }
+            Some(n) => self.stall_after.set(Some(n - 1)),
+            None => {}
+        }
+        let mut reg = 0;
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    reg = bytes[0] as usize;
+                    if bytes.len() > 1 {
+                        self.registers[reg..reg + bytes.len() - 1].copy_from_slice(&bytes[1..]);
+                        self.writes.push(bytes.to_vec());
+                    }
+                }
+                Operation::Read(buffer) => {
+                    buffer.copy_from_slice(&self.registers[reg..reg + buffer.len()]);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+    future.poll(&mut Context::from_waker(Waker::noop()))
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = poll_once(future.as_mut()) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn cancelled_setter_keeps_the_cached_modes() {
+    let bus = Bus::new(0x6B, 0x69);
+    let stall_after = bus.stall_after.clone();
+    let mut lsm6 = block_on(AsyncLSM6::new(bus)).unwrap();
+    block_on(lsm6.set_accel_mode(AccelerometerMode::Normal104Hz)).unwrap();
+
+    stall_after.set(Some(0));
+    let mut setter = Box::pin(lsm6.set_accel_scale(AccelerometerScale::G8));
+    assert!(poll_once(setter.as_mut()).is_pending());
+    drop(setter);
+    assert_eq!(lsm6.accel_scale(), AccelerometerScale::G2);
+
+    // The next setter still builds on the modes from before the cancelled one
+    block_on(lsm6.set_accel_mode(AccelerometerMode::Normal208Hz)).unwrap();
+    assert_eq!(
+        lsm6.release().writes,
+        [
+            vec![registers::CTRL3_C, 4],
+            vec![registers::CTRL1_XL, 0x40],
+            vec![registers::CTRL1_XL, 0x50],
+        ]
+    );
+}
+
+#[test]
+fn cancelled_read_leaves_the_next_read_intact() {
+    let mut bus = Bus::new(0x6B, 0x69);
+    bus.registers[registers::STATUS_REG as usize] = 1;
+    bus.registers[registers::OUTX_L_XL as usize..][..6].copy_from_slice(&[1, 0, 2, 0, 3, 0]);
+    let stall_after = bus.stall_after.clone();
+    let mut lsm6 = block_on(AsyncLSM6::new(bus)).unwrap();
+    lsm6.set_mounting_orientation(AxisMapping::new(Axis::PosY, Axis::NegX, Axis::PosZ).unwrap());
+
+    // STATUS_REG is read, then the output read is held up and dropped
+    stall_after.set(Some(1));
+    let mut read = Box::pin(lsm6.read_accel());
+    assert!(poll_once(read.as_mut()).is_pending());
+    drop(read);
+
+    assert_eq!(block_on(lsm6.read_accel()), Ok(Some((2, -1, 3))));
+}
+
+#[test]
+fn classifier_skips_a_nacked_address() {
+    assert_eq!(
+        block_on(AsyncLSM6::new(Bus::new(0x6A, 0x69))).err(),
+        Some(Error::Bus(ErrorKind::NoAcknowledge(
+            NoAcknowledgeSource::Address
+        )))
+    );
+    let lsm6 = block_on(AsyncLSM6::new_with_classifier(Bus::new(0x6A, 0x69), |e| {
+        matches!(e, ErrorKind::NoAcknowledge(_))
+    }))
+    .unwrap();
+    assert_eq!(lsm6.release().writes, [vec![registers::CTRL3_C, 4]]);
+}
+
+#[test]
+fn fifo_follows_the_variant() {
+    let mut bus = Bus::new(0x6B, 0x6C);
+    bus.registers[registers::FIFO_STATUS1 as usize..][..2].copy_from_slice(&[0x34, 0b101]);
+    let mut lsm6 = block_on(AsyncLSM6::new(bus)).unwrap();
+    assert_eq!(block_on(lsm6.fifo_unread_words()), Ok(0x134));
+    assert_eq!(
+        block_on(lsm6.read_fifo(&mut [(0, 0, 0); 4])),
+        Err(Error::Unsupported)
+    );
+}
+
+#[test]
+fn temperature_follows_the_variant() {
+    let mut bus = Bus::new(0x6B, 0x69);
+    bus.registers[registers::OUT_TEMP_L as usize..][..2].copy_from_slice(&[0, 1]);
+    let mut lsm6 = block_on(AsyncLSM6::new(bus)).unwrap();
+    assert_eq!(block_on(lsm6.read_temperature()), Ok(None));
+
+    let mut bus = lsm6.release();
+    bus.registers[registers::STATUS_REG as usize] = 0b100;
+    let mut lsm6 = block_on(AsyncLSM6::new(bus)).unwrap();
+    assert_eq!(block_on(lsm6.read_temperature()), Ok(Some(256)));
+    assert_eq!(block_on(lsm6.read_temperature_celsius()), Ok(Some(41.)));
+
+    let mut bus = lsm6.release();
+    bus.registers[registers::WHO_AM_I as usize] = 0x6C;
+    let mut lsm6 = block_on(AsyncLSM6::new(bus)).unwrap();
+    assert_eq!(block_on(lsm6.read_temperature_celsius()), Ok(Some(26.)));
+}
+
+/// Collects the log records made on each thread, so tests running in parallel don't see each other's.
+#[cfg(feature = "trace-log")]
+struct Capture(std::sync::Mutex<Vec<(std::thread::ThreadId, String)>>);
+
+#[cfg(feature = "trace-log")]
+impl log::Log for Capture {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let line = (std::thread::current().id(), record.args().to_string());
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(feature = "trace-log")]
+#[test]
+fn transfers_are_traced_like_the_blocking_driver() {
+    static CAPTURE: Capture = Capture(std::sync::Mutex::new(Vec::new()));
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let mut bus = Bus::new(0x6B, 0x69);
+    bus.registers[registers::STATUS_REG as usize] = 1;
+    let mut lsm6 = block_on(AsyncLSM6::new(bus)).unwrap();
+    block_on(lsm6.set_accel_mode(AccelerometerMode::Normal104Hz)).unwrap();
+    block_on(lsm6.read_accel()).unwrap();
+
+    let thread = std::thread::current().id();
+    let lines: Vec<_> = CAPTURE
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(id, _)| *id == thread)
+        .map(|(_, line)| line.clone())
+        .collect();
+    let mut expected = vec![
+        "lsm6 0x6b: write CTRL3_C (0x12) [04]: ok",
+        "lsm6 0x6b: write CTRL1_XL (0x10) [40]: ok",
+    ];
+    // The status and output reads are only traced with `trace-data`
+    if cfg!(feature = "trace-data") {
+        expected.extend([
+            "lsm6 0x6b: read STATUS_REG (0x1e) [01]: ok",
+            "lsm6 0x6b: read OUTX_L_XL (0x28) [00, 00, 00, 00, 00, 00]: ok",
+        ]);
+    }
+    assert_eq!(lines, expected);
+}
@@ -0,0 +1,58 @@
+//! Checks the averaging and motion detection of the offset calibrations.
+
+mod common;
+
+use common::{lsm6, status, ADDRESS};
+use embedded_hal_mock::{delay::MockNoop, i2c::Transaction};
+use lsm6ds33::{registers, CalibrationError};
+
+fn sample(output: u8, (x, y, z): (i16, i16, i16)) -> Transaction {
+    let mut bytes = vec![];
+    for value in [x, y, z] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    Transaction::write_read(ADDRESS, vec![output], bytes)
+}
+
+#[test]
+fn accel_offset_is_the_average_less_one_g() {
+    let mut lsm6 = lsm6(&[
+        status(0),
+        status(1),
+        sample(registers::OUTX_L_XL, (10, -20, 16400)),
+        status(1),
+        sample(registers::OUTX_L_XL, (12, -22, 16410)),
+    ]);
+    // One g is 16393 LSB at ±2 g
+    assert_eq!(
+        lsm6.calibrate_accel(&mut MockNoop::new(), 2, 50).unwrap(),
+        (11, -21, 12)
+    );
+    lsm6.release().done();
+}
+
+#[test]
+fn accel_offset_saturates_upside_down() {
+    let mut lsm6 = lsm6(&[status(1), sample(registers::OUTX_L_XL, (0, 0, -16393))]);
+    assert_eq!(
+        lsm6.calibrate_accel(&mut MockNoop::new(), 1, 50).unwrap(),
+        (0, 0, i16::MIN)
+    );
+    lsm6.release().done();
+}
+
+#[test]
+fn gyro_calibration_stops_when_the_device_moves() {
+    let mut lsm6 = lsm6(&[
+        status(0b10),
+        sample(registers::OUTX_L_G, (3, -2, 1)),
+        status(0b10),
+        // 1000 LSB is about 8.75 dps at ±245 dps, over the 1 dps threshold
+        sample(registers::OUTX_L_G, (1000, 0, 0)),
+    ]);
+    assert!(matches!(
+        lsm6.calibrate_gyro(&mut MockNoop::new(), 10, 1000),
+        Err(CalibrationError::Moved)
+    ));
+    lsm6.release().done();
+}
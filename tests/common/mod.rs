@@ -0,0 +1,51 @@
+//! A mocked i2c bus shared by the tests.
+
+#![allow(dead_code)]
+
+use embedded_hal_mock::i2c::{Mock, Transaction};
+use lsm6ds33::{registers, ChipVariant, SlaveAddr, LSM6};
+
+/// The address the mocked LSM6 answers on, with SA0 high.
+pub const ADDRESS: u8 = 0x6B;
+
+/// The constructor's transactions for a device answering with `who_am_i`, followed by `transactions`.
+fn expectations(who_am_i: u8, transactions: &[Transaction]) -> Vec<Transaction> {
+    let mut expectations = vec![
+        Transaction::write_read(ADDRESS, vec![registers::WHO_AM_I], vec![who_am_i]),
+        Transaction::write(ADDRESS, vec![registers::CTRL3_C, 4]),
+    ];
+    expectations.extend_from_slice(transactions);
+    expectations
+}
+
+/// A driver whose bus expects the transactions of `LSM6::new_with_address`, followed by `reads`.
+pub fn lsm6(reads: &[Transaction]) -> LSM6<Mock> {
+    lsm6_with_id(0x69, reads)
+}
+
+/// Like `lsm6`, for a device whose WHO_AM_I register holds `who_am_i`.
+pub fn lsm6_with_id(who_am_i: u8, transactions: &[Transaction]) -> LSM6<Mock> {
+    let mock = Mock::new(&expectations(who_am_i, transactions));
+    LSM6::new_with_address(mock, SlaveAddr::High).unwrap()
+}
+
+/// Like `lsm6`, for a driver built with `LSM6::new_variant`.
+pub fn lsm6_variant(variant: ChipVariant, transactions: &[Transaction]) -> LSM6<Mock> {
+    let mock = Mock::new(&expectations(variant.who_am_i(), transactions));
+    LSM6::new_variant(mock, variant).unwrap()
+}
+
+/// A write of `value` to `reg`.
+pub fn write(reg: u8, value: u8) -> Transaction {
+    Transaction::write(ADDRESS, vec![reg, value])
+}
+
+/// A read starting at `reg` returning `values`.
+pub fn read(reg: u8, values: &[u8]) -> Transaction {
+    Transaction::write_read(ADDRESS, vec![reg], values.to_vec())
+}
+
+/// A read of STATUS_REG returning `value`.
+pub fn status(value: u8) -> Transaction {
+    Transaction::write_read(ADDRESS, vec![registers::STATUS_REG], vec![value])
+}
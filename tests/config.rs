@@ -0,0 +1,724 @@
+//! Checks the transactions that configuration is written in.
+
+use lsm6ds33::{
+    registers, AccelerometerMode, AccelerometerScale, ChipVariant, Config, ConfigBuilder,
+    ConfigError, ConfigStep, DataRate, DenSensor, Error, FifoMode, FilterPath, GyroscopeMode,
+    GyroscopeScale, Register, RegisterInterface, Rounding, SyncResolution, SyncTimeFrame, LSM6,
+};
+
+/// A simulated LSM6 that records every write, and the first register of every read.
+struct Recorder {
+    registers: [u8; 0x80],
+    writes: Vec<Vec<u8>>,
+    reads: Vec<u8>,
+}
+
+impl RegisterInterface for Recorder {
+    type Error = ();
+
+    fn write_registers(&mut self, _address: u8, bytes: &[u8]) -> Result<(), ()> {
+        for (i, byte) in bytes[1..].iter().enumerate() {
+            self.registers[bytes[0] as usize + i] = *byte;
+        }
+        self.writes.push(bytes.to_vec());
+        Ok(())
+    }
+
+    fn read_registers(&mut self, _address: u8, start: u8, buffer: &mut [u8]) -> Result<(), ()> {
+        self.reads.push(start);
+        let start = start as usize;
+        buffer.copy_from_slice(&self.registers[start..start + buffer.len()]);
+        Ok(())
+    }
+}
+
+fn lsm6() -> LSM6<Recorder> {
+    lsm6_with_id(0x69)
+}
+
+fn lsm6_with_id(who_am_i: u8) -> LSM6<Recorder> {
+    let mut registers = [0; 0x80];
+    registers[registers::WHO_AM_I as usize] = who_am_i;
+    let bus = Recorder {
+        registers,
+        writes: Vec::new(),
+        reads: Vec::new(),
+    };
+    LSM6::from_interface(bus, 0x6B).unwrap()
+}
+
+/// The writes made since the driver was constructed.
+fn writes(lsm6: LSM6<Recorder>) -> Vec<Vec<u8>> {
+    let mut writes = lsm6.release().writes;
+    // The constructor's write of CTRL3_C
+    writes.remove(0);
+    writes
+}
+
+#[test]
+fn apply_config_writes_consecutive_registers_in_bursts() {
+    let mut lsm6 = lsm6();
+    let config = Config {
+        fifo_ctrl1: 0x10,
+        fifo_ctrl2: 0,
+        fifo_ctrl3: 0b1001,
+        fifo_ctrl4: 0,
+        fifo_ctrl5: 0x26,
+        orient_cfg_g: 0,
+        int1_ctrl: 0b11,
+        int2_ctrl: 0,
+        ctrl1_xl: 0x40,
+        ctrl2_g: 0x40,
+        ctrl3_c: 0x44,
+        ctrl4_c: 0,
+        ctrl5_c: 0,
+        ctrl6_c: 0,
+        ctrl7_g: 0,
+        ctrl8_xl: 0,
+        ctrl9_xl: 0x38,
+        ctrl10_c: 0x38,
+        tap_cfg: 0,
+        tap_ths_6d: 0,
+        int_dur2: 0,
+        wake_up_ths: 0,
+        wake_up_dur: 0,
+        free_fall: 0,
+        md1_cfg: 0,
+        md2_cfg: 0,
+        counter_bdr_reg1: 0,
+        tap_cfg0: 0,
+        tap_cfg1: 0,
+    };
+    lsm6.apply_config(&config).unwrap();
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::INT1_CTRL, 0, 0],
+            vec![registers::MD1_CFG, 0, 0],
+            vec![registers::CTRL3_C, 0x44, 0, 0, 0, 0, 0, 0x38, 0x38],
+            vec![registers::FIFO_CTRL1, 0x10, 0, 0b1001, 0],
+            vec![registers::ORIENT_CFG_G, 0],
+            vec![registers::TAP_CFG, 0, 0, 0, 0, 0, 0],
+            vec![registers::CTRL1_XL, 0x40, 0x40],
+            vec![registers::FIFO_CTRL5, 0x26],
+            vec![registers::INT1_CTRL, 0b11, 0],
+            vec![registers::MD1_CFG, 0, 0],
+        ]
+    );
+}
+
+#[test]
+fn builder_writes_modes_and_bdu_in_one_burst() {
+    let mut lsm6 = lsm6();
+    LSM6::configure()
+        .accel(AccelerometerMode::Normal104Hz, AccelerometerScale::G4)
+        .gyro(GyroscopeMode::Normal104Hz, GyroscopeScale::Dps500)
+        .bdu(true)
+        .fifo(FifoMode::Continuous, DataRate::Hz104)
+        .apply(&mut lsm6)
+        .unwrap();
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::FIFO_CTRL5, 0x26],
+            vec![registers::CTRL1_XL, 0x48, 0x44, 0x44],
+        ]
+    );
+}
+
+#[test]
+fn writes_are_split_without_auto_increment() {
+    let mut lsm6 = lsm6();
+    lsm6.set_register(Register::Ctrl3C, 0).unwrap();
+    LSM6::configure()
+        .accel(AccelerometerMode::Normal104Hz, AccelerometerScale::G2)
+        .gyro(GyroscopeMode::Normal104Hz, GyroscopeScale::Dps245)
+        .apply(&mut lsm6)
+        .unwrap();
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::CTRL3_C, 0],
+            vec![registers::CTRL1_XL, 0x40],
+            vec![registers::CTRL2_G, 0x40],
+        ]
+    );
+}
+
+#[test]
+fn matched_odr_sets_both_sensors_together() {
+    let mut lsm6 = lsm6();
+    lsm6.set_matched_odr(DataRate::Hz208).unwrap();
+    assert_eq!(writes(lsm6), vec![vec![registers::CTRL1_XL, 0x50, 0x50]]);
+}
+
+#[test]
+fn matched_odr_refuses_rates_the_gyro_lacks() {
+    let mut lsm6 = lsm6();
+    assert!(matches!(
+        lsm6.set_matched_odr(DataRate::Hz3330),
+        Err(Error::GyroRateUnavailable(DataRate::Hz3330))
+    ));
+    assert!(writes(lsm6).is_empty());
+}
+
+#[test]
+fn settling_time_follows_modes_and_filters() {
+    let mut lsm6 = lsm6();
+    assert_eq!(lsm6.filter_settling_time_us(), 0);
+    lsm6.set_matched_odr(DataRate::Hz104).unwrap();
+    // 2 samples at 104 Hz
+    assert_eq!(lsm6.filter_settling_time_us(), 19_231);
+    // LPF2 at ODR / 50 adds 37 samples
+    lsm6.set_register(Register::Ctrl8Xl, 0x80).unwrap();
+    assert_eq!(lsm6.filter_settling_time_us(), 375_000);
+    // The gyroscope's high-pass filter at 16.32 Hz takes longer than the accelerometer without LPF2
+    lsm6.set_register(Register::Ctrl8Xl, 0).unwrap();
+    lsm6.set_register(Register::Ctrl7G, 0x70).unwrap();
+    assert_eq!(lsm6.filter_settling_time_us(), 19_231 + 44_914);
+}
+
+#[test]
+fn pause_and_resume_restore_the_modes() {
+    let mut lsm6 = lsm6();
+    lsm6.set_matched_odr(DataRate::Hz208).unwrap();
+    lsm6.pause().unwrap();
+    lsm6.pause().unwrap();
+    assert!(lsm6.is_paused());
+    lsm6.resume().unwrap();
+    lsm6.resume().unwrap();
+    assert!(!lsm6.is_paused());
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::CTRL1_XL, 0x50, 0x50],
+            vec![registers::CTRL1_XL, 0, 0],
+            vec![registers::CTRL1_XL, 0, 0],
+            vec![registers::CTRL1_XL, 0x50, 0x50],
+        ]
+    );
+}
+
+#[test]
+fn tap_timing_is_quantized_to_odr_periods() {
+    let mut lsm6 = lsm6();
+    assert!(matches!(
+        lsm6.configure_tap_timing_ms(20, 10, 200),
+        Err(Error::InvalidConfig)
+    ));
+    lsm6.set_accel_mode(AccelerometerMode::HighPerformance416Hz)
+        .unwrap();
+    // 8 periods of shock, 4 of quiet, and the longest duration, 480 periods
+    let timing = lsm6.configure_tap_timing_ms(20, 10, 2000).unwrap();
+    assert!((timing.shock_ms - 19.23).abs() < 0.01);
+    assert!((timing.quiet_ms - 9.62).abs() < 0.01);
+    assert!((timing.duration_ms - 1153.85).abs() < 0.01);
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::CTRL1_XL, 0x60],
+            vec![registers::INT_DUR2, 0xF5]
+        ]
+    );
+}
+
+#[test]
+fn config_builder_round_trips_through_the_registers() {
+    let builder = ConfigBuilder::new()
+        .accel(AccelerometerMode::Normal208Hz, AccelerometerScale::G8)
+        .gyro(GyroscopeMode::Normal104Hz, GyroscopeScale::Dps125)
+        .bdu(true)
+        .big_endian(false)
+        .accel_axes(true, false, true)
+        .gyro_axes(false, true, true)
+        .fifo(FifoMode::Continuous, DataRate::Hz104);
+    let mut lsm6 = lsm6();
+    builder.apply(&mut lsm6).unwrap();
+    assert_eq!(lsm6.read_config_builder().unwrap(), builder);
+
+    // A reserved accelerometer rate leaves it unset, and so does a FIFO without a rate
+    lsm6.set_register(Register::Ctrl1Xl, 0xB0).unwrap();
+    lsm6.set_register(Register::FifoCtrl5, 0b110).unwrap();
+    let decoded = lsm6.read_config_builder().unwrap();
+    let expected = ConfigBuilder::new()
+        .gyro(GyroscopeMode::Normal104Hz, GyroscopeScale::Dps125)
+        .bdu(true)
+        .big_endian(false)
+        .accel_axes(true, false, true)
+        .gyro_axes(false, true, true);
+    assert_eq!(decoded, expected);
+}
+
+#[test]
+fn output_filter_switches_the_output_path() {
+    let mut lsm6 = lsm6();
+    // LPF2 on, with the high-pass cutoff at ODR / 100
+    lsm6.set_register(Register::Ctrl8Xl, 0xA0).unwrap();
+    lsm6.set_accel_output_filter(FilterPath::Filtered).unwrap();
+    lsm6.set_accel_output_filter(FilterPath::Raw).unwrap();
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::CTRL8_XL, 0xA0],
+            vec![registers::CTRL8_XL, 0xA4],
+            vec![registers::CTRL8_XL, 0x20],
+        ]
+    );
+}
+
+#[test]
+fn den_stamping_sensor_is_selected_per_variant() {
+    assert!(matches!(
+        lsm6().set_den_sensor(DenSensor::Accelerometer),
+        Err(Error::Unsupported)
+    ));
+
+    let mut lsm6dsl = lsm6_with_id(0x6A);
+    lsm6dsl.set_den_sensor(DenSensor::Accelerometer).unwrap();
+    lsm6dsl.set_den_sensor(DenSensor::Both).unwrap();
+    assert_eq!(
+        writes(lsm6dsl),
+        vec![
+            vec![registers::CTRL9_XL, 0b10000],
+            vec![registers::CTRL4_C, 0],
+            vec![registers::CTRL9_XL, 0],
+            vec![registers::CTRL4_C, 0b10000000],
+        ]
+    );
+
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    lsm6dso.set_den_sensor(DenSensor::Both).unwrap();
+    assert_eq!(writes(lsm6dso), vec![vec![registers::CTRL9_XL, 0b1000]]);
+}
+
+#[test]
+fn fifo_watermark_is_clamped_to_the_variant_field() {
+    let mut lsm6 = lsm6();
+    assert_eq!(lsm6.set_fifo_watermark(5000).unwrap(), 4095);
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::FIFO_CTRL1, 0xFF],
+            vec![registers::FIFO_CTRL2, 0b1111]
+        ]
+    );
+
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    assert_eq!(lsm6dso.set_fifo_watermark(300).unwrap(), 300);
+    assert_eq!(lsm6dso.set_fifo_watermark(600).unwrap(), 511);
+    assert_eq!(
+        writes(lsm6dso),
+        vec![
+            vec![registers::lsm6dso::FIFO_CTRL1, 0x2C],
+            vec![registers::lsm6dso::FIFO_CTRL2, 1],
+            vec![registers::lsm6dso::FIFO_CTRL1, 0xFF],
+            vec![registers::lsm6dso::FIFO_CTRL2, 1],
+        ]
+    );
+}
+
+#[test]
+fn status_rounding_is_only_set_where_supported() {
+    let mut lsm6 = lsm6();
+    lsm6.set_rounding(Rounding::GyroAccel, true).unwrap();
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::CTRL5_C, 0b1100000],
+            vec![registers::CTRL7_G, 0b100]
+        ]
+    );
+
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    lsm6dso.set_rounding(Rounding::Accel, false).unwrap();
+    assert!(matches!(
+        lsm6dso.set_rounding(Rounding::Accel, true),
+        Err(Error::Unsupported)
+    ));
+    assert_eq!(writes(lsm6dso), vec![vec![registers::CTRL5_C, 0b100000]]);
+}
+
+#[test]
+fn sensor_sync_frame_is_quantized_and_written_with_the_trigger() {
+    let frame = SyncTimeFrame::from_ms(1300, SyncResolution::Ratio8192).unwrap();
+    assert_eq!(frame.frame_ms(), 1500);
+    assert_eq!(
+        SyncTimeFrame::from_ms(5001, SyncResolution::Ratio2048),
+        None
+    );
+
+    assert!(matches!(
+        lsm6().configure_sensor_sync(frame),
+        Err(Error::Unsupported)
+    ));
+    let mut lsm6dsl = lsm6_with_id(0x6A);
+    lsm6dsl.configure_sensor_sync(frame).unwrap();
+    assert_eq!(
+        writes(lsm6dsl),
+        vec![
+            vec![registers::lsm6dsl::SENSOR_SYNC_TIME_FRAME, 3, 0b10],
+            vec![registers::CTRL6_C, 0b10000000],
+            vec![registers::CTRL10_C, 0b100000],
+        ]
+    );
+}
+
+#[test]
+fn accel_axes_keep_the_rest_of_ctrl9_xl() {
+    let mut lsm6 = lsm6();
+    lsm6.set_register_raw(registers::CTRL9_XL, 0b11000100)
+        .unwrap();
+    lsm6.set_accel_axes(true, false, true).unwrap();
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::CTRL9_XL, 0b11000100],
+            vec![registers::CTRL9_XL, 0b11101100],
+        ]
+    );
+
+    let mut lsm6dsl = lsm6_with_id(0x6A);
+    assert!(matches!(
+        lsm6dsl.set_accel_axes(true, true, true),
+        Err(Error::Unsupported)
+    ));
+}
+
+#[test]
+fn gyro_axes_are_unsupported_on_the_lsm6dso() {
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    assert!(matches!(
+        lsm6dso.set_gyro_axes(true, false, true),
+        Err(Error::Unsupported)
+    ));
+    let recorder = lsm6dso.release();
+    assert_eq!(recorder.reads, [registers::WHO_AM_I]);
+    assert_eq!(recorder.writes, [vec![registers::CTRL3_C, 4]]);
+}
+
+#[test]
+fn setters_after_sync_cache_only_write() {
+    let mut lsm6 = lsm6();
+    lsm6.sync_cache().unwrap();
+    lsm6.set_gyro_sleep(true).unwrap();
+    lsm6.set_gyro_axes(true, false, true).unwrap();
+    lsm6.set_bits(registers::TAP_CFG, 0b1110).unwrap();
+    lsm6.clear_bits(registers::CTRL4_C, 0b1000000).unwrap();
+    let recorder = lsm6.release();
+    assert_eq!(
+        recorder.reads,
+        [
+            registers::WHO_AM_I,
+            registers::INT1_CTRL,
+            registers::TAP_CFG
+        ]
+    );
+    assert_eq!(
+        recorder.writes[1..],
+        [
+            vec![registers::CTRL4_C, 0b1000000],
+            vec![registers::CTRL10_C, 0b101000],
+            vec![registers::TAP_CFG, 0b1110],
+            vec![registers::CTRL4_C, 0],
+        ]
+    );
+}
+
+#[test]
+fn setters_read_every_time_with_caching_off() {
+    let mut lsm6 = lsm6();
+    lsm6.set_caching(false);
+    lsm6.sync_cache().unwrap();
+    lsm6.set_gyro_sleep(true).unwrap();
+    lsm6.set_gyro_sleep(false).unwrap();
+    assert_eq!(
+        lsm6.release().reads,
+        [registers::WHO_AM_I, registers::CTRL4_C, registers::CTRL4_C]
+    );
+}
+
+#[test]
+fn raw_writes_update_the_cache() {
+    let mut lsm6 = lsm6();
+    lsm6.set_register(Register::Ctrl4C, 0b10).unwrap();
+    lsm6.set_gyro_sleep(true).unwrap();
+    let recorder = lsm6.release();
+    assert_eq!(recorder.reads, [registers::WHO_AM_I]);
+    assert_eq!(
+        recorder.writes.last().unwrap(),
+        &[registers::CTRL4_C, 0b1000010]
+    );
+}
+
+#[test]
+fn masked_writes_keep_the_bits_outside_a_non_contiguous_mask() {
+    for &caching in &[true, false] {
+        let mut lsm6 = lsm6();
+        lsm6.set_caching(caching);
+        lsm6.set_register_raw(registers::CTRL4_C, 0b0101_0011)
+            .unwrap();
+        assert_eq!(
+            lsm6.modify_register(registers::CTRL4_C, 0b1010_0101, 0b1000_0100),
+            Ok(0b0101_0011)
+        );
+        assert_eq!(
+            lsm6.set_bits(registers::CTRL4_C, 0b1010_0101),
+            Ok(0b1101_0110)
+        );
+        assert_eq!(
+            lsm6.clear_bits(registers::CTRL4_C, 0b1010_0101),
+            Ok(0b1111_0111)
+        );
+        assert_eq!(
+            writes(lsm6),
+            [
+                vec![registers::CTRL4_C, 0b0101_0011],
+                vec![registers::CTRL4_C, 0b1101_0110],
+                vec![registers::CTRL4_C, 0b1111_0111],
+                vec![registers::CTRL4_C, 0b0101_0010],
+            ]
+        );
+    }
+}
+
+#[test]
+fn read_config_reads_back_an_applied_config() {
+    let mut source = lsm6();
+    source
+        .apply_config(&Config {
+            fifo_ctrl1: 0x10,
+            fifo_ctrl3: 0b1001,
+            fifo_ctrl5: 0x26,
+            orient_cfg_g: 0b100001,
+            int1_ctrl: 0b11,
+            int2_ctrl: 0b10,
+            ctrl1_xl: 0x4A,
+            ctrl2_g: 0x44,
+            ctrl3_c: 0x64,
+            ctrl4_c: 0b10,
+            ctrl6_c: 0b10000,
+            ctrl9_xl: 0x38,
+            ctrl10_c: 0x3C,
+            tap_cfg: 0x8E,
+            tap_ths_6d: 0x0C,
+            wake_up_ths: 0x82,
+            free_fall: 0x33,
+            md1_cfg: 0b100,
+            ..Config::default()
+        })
+        .unwrap();
+    let first = source.read_config().unwrap();
+
+    let mut copy = lsm6();
+    copy.apply_config(&first).unwrap();
+    let second = copy.read_config().unwrap();
+    assert_eq!(first, second);
+    assert_eq!(copy.read_config_builder(), source.read_config_builder());
+}
+
+#[test]
+fn lsm6dso_config_round_trips_through_its_own_registers() {
+    let mut source = lsm6_with_id(0x6C);
+    source
+        .apply_config(&Config {
+            fifo_ctrl1: 0x10,
+            fifo_ctrl2: 0b10000000,
+            fifo_ctrl3: 0x44,
+            fifo_ctrl4: 0b110,
+            int1_ctrl: 0b1000,
+            ctrl1_xl: 0x4A,
+            ctrl2_g: 0x44,
+            ctrl3_c: 0x44,
+            ctrl10_c: 0b100000,
+            tap_cfg: 0x80,
+            wake_up_ths: 0x02,
+            md1_cfg: 0b100000,
+            counter_bdr_reg1: 0b1100011,
+            tap_cfg0: 0b1000001,
+            tap_cfg1: 0x22,
+            ..Config::default()
+        })
+        .unwrap();
+    let first = source.read_config().unwrap();
+    assert_eq!(first.fifo_ctrl4, 0b110);
+    assert_eq!(first.counter_bdr_reg1, 0b100011);
+    assert_eq!((first.fifo_ctrl5, first.orient_cfg_g), (0, 0));
+
+    let mut copy = lsm6_with_id(0x6C);
+    copy.apply_config(&first).unwrap();
+    assert_eq!(copy.read_config(), Ok(first));
+    let recorder = copy.release();
+    assert_eq!(
+        recorder.writes[1..],
+        [
+            vec![registers::INT1_CTRL, 0, 0],
+            vec![registers::MD1_CFG, 0, 0],
+            vec![registers::CTRL3_C, 0x44, 0, 0, 0, 0, 0, 0, 0b100000],
+            vec![registers::lsm6dso::FIFO_CTRL1, 0x10, 0b10000000, 0x44],
+            vec![registers::lsm6dso::COUNTER_BDR_REG1, 0b100011],
+            vec![
+                registers::lsm6dso::TAP_CFG0,
+                0b1000001,
+                0x22,
+                0x80,
+                0,
+                0,
+                0x02,
+                0,
+                0
+            ],
+            vec![registers::CTRL1_XL, 0x4A, 0x44],
+            vec![registers::lsm6dso::FIFO_CTRL4, 0b110],
+            vec![registers::INT1_CTRL, 0b1000, 0],
+            vec![registers::MD1_CFG, 0b100000, 0],
+        ]
+    );
+    // The reserved register before FIFO_CTRL1 is never read
+    assert_eq!(
+        recorder.reads[1..],
+        [
+            registers::lsm6dso::FIFO_CTRL1,
+            registers::INT1_CTRL,
+            registers::lsm6dso::TAP_CFG0
+        ]
+    );
+}
+
+#[test]
+fn lsm6dso_dump_skips_its_reserved_registers() {
+    let reserved = [
+        0x06, 0x1F, 0x38, 0x3C, 0x3D, 0x44, 0x49, 0x4A, 0x4B, 0x4C, 0x53,
+    ];
+    let layout = registers::dump_layout(ChipVariant::Lsm6dso);
+    assert!(layout.iter().all(|(reg, _)| !reserved.contains(reg)));
+
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    let mut out = [0; 0x80];
+    assert_eq!(lsm6dso.dump_registers(&mut out), Ok(layout.len()));
+    let who_am_i = layout.iter().position(|(_, name)| *name == "WHO_AM_I");
+    assert_eq!(out[who_am_i.unwrap()], 0x6C);
+    // Each contiguous run is read in one burst
+    assert_eq!(
+        lsm6dso.release().reads[1..],
+        [0x01, 0x07, 0x20, 0x35, 0x39, 0x40, 0x56, 0x62, 0x6F]
+    );
+    assert_eq!(
+        registers::dump_layout(ChipVariant::Lsm6ds33),
+        &registers::DUMP_LAYOUT[..]
+    );
+}
+
+#[test]
+fn untagged_fifo_api_is_unsupported_on_the_lsm6dso() {
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    assert_eq!(
+        lsm6dso.set_fifo_mode(FifoMode::Continuous, DataRate::Hz104),
+        Err(Error::Unsupported)
+    );
+    assert_eq!(
+        lsm6dso.set_fifo_batching(true, true),
+        Err(Error::Unsupported)
+    );
+    assert_eq!(
+        lsm6dso.read_fifo(&mut [(0, 0, 0); 4]),
+        Err(Error::Unsupported)
+    );
+    assert!(writes(lsm6dso).is_empty());
+
+    // `fifo_ctrl5` isn't taken for a FIFO mode and rate on the LSM6DSO
+    let config = Config {
+        ctrl1_xl: 0x40,
+        fifo_ctrl5: 0x26,
+        ..Config::default()
+    };
+    let decoded = ConfigBuilder::new()
+        .accel(AccelerometerMode::Normal104Hz, AccelerometerScale::G2)
+        .gyro(GyroscopeMode::PowerDown, GyroscopeScale::Dps245)
+        .bdu(false)
+        .big_endian(false);
+    assert_eq!(
+        ConfigBuilder::from_config(ChipVariant::Lsm6dso, &config),
+        decoded
+    );
+    assert_eq!(
+        ConfigBuilder::from_config(ChipVariant::Lsm6ds33, &config),
+        decoded
+            .accel_axes(false, false, false)
+            .gyro_axes(false, false, false)
+            .fifo(FifoMode::Continuous, DataRate::Hz104)
+    );
+}
+
+#[test]
+fn low_power_setup_only_clears_the_fifo_mode_on_the_lsm6dso() {
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    lsm6dso
+        .set_register_raw(registers::lsm6dso::FIFO_CTRL4, 0b110110)
+        .unwrap();
+    lsm6dso.setup_low_power().unwrap();
+    assert_eq!(
+        writes(lsm6dso),
+        vec![
+            vec![registers::lsm6dso::FIFO_CTRL4, 0b110110],
+            vec![registers::lsm6dso::FIFO_CTRL4, 0b110000],
+            vec![registers::CTRL6_C, 0b10000],
+            vec![registers::CTRL1_XL, 0x10, 0, 0x44],
+        ]
+    );
+}
+
+#[test]
+fn builder_rejects_low_power_modes_with_high_performance_on() {
+    let mut lsm6 = lsm6();
+    let low_power = LSM6::configure()
+        .accel(AccelerometerMode::LowPower26Hz, AccelerometerScale::G2)
+        .gyro(GyroscopeMode::LowPower26Hz, GyroscopeScale::Dps245);
+    let rejected = ConfigError {
+        step: ConfigStep::Modes,
+        error: Error::InvalidConfig,
+    };
+    assert_eq!(low_power.apply(&mut lsm6), Err(rejected));
+    lsm6.set_bits(registers::CTRL6_C, 0b10000).unwrap();
+    assert_eq!(low_power.apply(&mut lsm6), Err(rejected));
+    lsm6.set_bits(registers::CTRL7_G, 0b10000000).unwrap();
+    low_power.apply(&mut lsm6).unwrap();
+    assert_eq!(
+        writes(lsm6),
+        vec![
+            vec![registers::CTRL6_C, 0b10000],
+            vec![registers::CTRL7_G, 0b10000000],
+            vec![registers::CTRL1_XL, 0x20, 0x20],
+        ]
+    );
+}
+
+#[test]
+fn builder_writes_axes_in_one_burst_and_the_endianness_with_the_modes() {
+    let mut lsm6 = lsm6();
+    lsm6.set_register_raw(registers::CTRL10_C, 0b100).unwrap();
+    LSM6::configure()
+        .accel_axes(true, true, false)
+        .gyro_axes(false, false, true)
+        .big_endian(true)
+        .apply(&mut lsm6)
+        .unwrap();
+    assert_eq!(
+        writes(lsm6)[1..],
+        [
+            vec![registers::CTRL9_XL, 0b110000, 0b1100],
+            vec![registers::CTRL3_C, 0b110],
+        ]
+    );
+
+    let mut lsm6dso = lsm6_with_id(0x6C);
+    assert_eq!(
+        LSM6::configure()
+            .gyro_axes(true, true, true)
+            .apply(&mut lsm6dso),
+        Err(ConfigError {
+            step: ConfigStep::Axes,
+            error: Error::Unsupported,
+        })
+    );
+    assert!(writes(lsm6dso).is_empty());
+}
@@ -0,0 +1,37 @@
+//! Checks the averages produced by `Decimator`.
+
+use lsm6ds33::Decimator;
+
+#[test]
+fn constant_input_is_reproduced_exactly() {
+    for &value in &[0, 1, -1, 12345, -12345] {
+        let reading = (value, value / 2, -value);
+        let mut decimator = Decimator::<8>::new(4, 8);
+        for i in 1..=32 {
+            let average = decimator.push(reading);
+            if i % 4 == 0 {
+                assert_eq!(average, Some(reading));
+            } else {
+                assert_eq!(average, None);
+            }
+        }
+    }
+}
+
+#[test]
+fn full_scale_input_does_not_overflow() {
+    let mut decimator = Decimator::<65535>::new(65535, 65535);
+    let mut last = None;
+    for _ in 0..65535 {
+        last = decimator.push((i16::MAX, i16::MIN, i16::MIN));
+    }
+    assert_eq!(last, Some((i16::MAX, i16::MIN, i16::MIN)));
+}
+
+#[test]
+fn decimate_averages_blocks() {
+    let readings = (0..64).map(|i| (i, -i, 0));
+    let averages: Vec<_> = Decimator::<32>::new(32, 32).decimate(readings).collect();
+    // The averages of 0..=31 and 32..=63, with halves rounded away from zero
+    assert_eq!(averages, vec![(16, -16, 0), (48, -48, 0)]);
+}
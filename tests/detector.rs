@@ -0,0 +1,92 @@
+//! Checks the software detectors against synthetic readings.
+
+use lsm6ds33::{
+    AccelerometerScale, MotionDetector, MotionEvent, MotionState, ShockDetector, ShockEvent,
+};
+
+/// 1 g on z at ±2 g, with a few mg of noise.
+fn resting(i: i32) -> (i16, i16, i16) {
+    let noise = ((i * 7919) % 81 - 40) as i16;
+    (noise, -noise, 16393 + noise / 2)
+}
+
+#[test]
+fn noise_on_a_desk_reads_still() {
+    let mut detector = MotionDetector::<16>::new(AccelerometerScale::G2);
+    for i in 0..1000 {
+        assert_eq!(detector.update(resting(i)), None);
+    }
+    assert_eq!(detector.state(), MotionState::Still);
+}
+
+#[test]
+fn motion_starts_and_stops_with_hysteresis() {
+    let mut detector = MotionDetector::<8>::new(AccelerometerScale::G2);
+    for i in 0..8 {
+        detector.update(resting(i));
+    }
+    // A 0.1 g bump on x
+    let (_, y, z) = resting(0);
+    assert_eq!(detector.update((1639, y, z)), Some(MotionEvent::Started));
+    assert_eq!(detector.state(), MotionState::Moving);
+
+    // Swinging by 40 mg is under the threshold, but not by the hysteresis
+    for i in 0..16 {
+        let x = if i % 2 == 0 { 0 } else { 656 };
+        assert_eq!(detector.update((x, y, z)), None);
+    }
+    // Swinging by 25 mg is still enough once the bump has left the window
+    let mut events = Vec::new();
+    for i in 0..16 {
+        let x = if i % 2 == 0 { 0 } else { 410 };
+        events.extend(detector.update((x, y, z)));
+    }
+    assert_eq!(events, vec![MotionEvent::Stopped]);
+    assert_eq!(detector.state(), MotionState::Still);
+}
+
+#[test]
+fn shocks_are_queued_with_peak_and_duration() {
+    let mut detector = ShockDetector::<2>::new(AccelerometerScale::G2, 1500);
+    for i in 0..10 {
+        assert!(!detector.update(resting(i)));
+    }
+    assert_eq!(detector.pop(), None);
+
+    // A knock peaking at 1.8 g on z
+    for &z in &[26229, 29509, 27000] {
+        assert!(detector.update((0, 0, z)));
+    }
+    detector.update(resting(0));
+    assert_eq!(
+        detector.pop(),
+        Some(ShockEvent {
+            peak_mg: 1800,
+            duration_samples: 3,
+            saturated: false,
+        })
+    );
+    assert_eq!(detector.pop(), None);
+
+    // Three more knocks overflow the queue, dropping the first
+    for &x in &[26000, 25000, 30000] {
+        detector.update((x, 0, 0));
+        detector.update(resting(0));
+    }
+    assert_eq!(detector.dropped(), 1);
+    assert_eq!(detector.pop().map(|e| e.peak_mg), Some(1525));
+    assert_eq!(detector.pop().map(|e| e.peak_mg), Some(1830));
+    assert_eq!(detector.pop(), None);
+}
+
+#[test]
+fn full_scale_shocks_flag_saturation() {
+    let mut detector = ShockDetector::<4>::new(AccelerometerScale::G16, 8000);
+    detector.update((i16::MAX, i16::MIN, i16::MAX));
+    detector.update((0, 0, 2049));
+    let event = detector.pop().unwrap();
+    // sqrt(3) * 16 g
+    assert_eq!(event.peak_mg, 27696);
+    assert_eq!(event.duration_samples, 1);
+    assert!(event.saturated);
+}
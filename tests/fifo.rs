@@ -0,0 +1,66 @@
+//! Checks reading the FIFO as a stream against a mocked i2c bus.
+
+mod common;
+
+use common::{lsm6, ADDRESS};
+use embedded_hal_mock::i2c::Transaction;
+use lsm6ds33::{registers, FifoEvent};
+
+/// A read of FIFO_STATUS1 through FIFO_STATUS4 reporting `words` unread words.
+fn fifo_status(words: u8, overrun: bool) -> Transaction {
+    let status2 = if overrun { 0b1000000 } else { 0 };
+    Transaction::write_read(
+        ADDRESS,
+        vec![registers::FIFO_STATUS1],
+        vec![words, status2, 0, 0],
+    )
+}
+
+fn sample(bytes: [u8; 6]) -> Transaction {
+    Transaction::write_read(ADDRESS, vec![registers::FIFO_DATA_OUT_L], bytes.to_vec())
+}
+
+#[test]
+fn stream_reports_overrun_then_drains_and_resumes() {
+    let mut lsm6 = lsm6(&[
+        fifo_status(6, true),
+        sample([1, 0, 2, 0, 3, 0]),
+        sample([0xFF, 0xFF, 0, 0, 0, 0x80]),
+        fifo_status(0, false),
+        fifo_status(3, false),
+        sample([4, 0, 5, 0, 6, 0]),
+        fifo_status(0, false),
+    ]);
+    let events: Vec<_> = lsm6.fifo_stream().unwrap().map(Result::unwrap).collect();
+    assert_eq!(
+        events,
+        vec![
+            FifoEvent::Overrun,
+            FifoEvent::Sample((1, 2, 3)),
+            FifoEvent::Sample((-1, 0, i16::MIN)),
+        ]
+    );
+
+    let mut stream = lsm6.fifo_stream().unwrap();
+    assert_eq!(
+        stream.next().unwrap().unwrap(),
+        FifoEvent::Sample((4, 5, 6))
+    );
+    assert!(stream.next().is_none());
+    lsm6.release().done();
+}
+
+#[test]
+fn status_is_decoded_from_one_read() {
+    let mut lsm6 = lsm6(&[Transaction::write_read(
+        ADDRESS,
+        vec![registers::FIFO_STATUS1],
+        vec![0x34, 0b10100001, 2, 0],
+    )]);
+    let status = lsm6.read_fifo_status().unwrap();
+    assert_eq!(status.unread_words, 0x134);
+    assert!(status.watermark && status.full && !status.overrun && !status.is_empty());
+    assert_eq!(status.pattern, Some(2));
+    assert_eq!(lsm6.fifo_pattern(), 2);
+    lsm6.release().done();
+}
@@ -0,0 +1,30 @@
+//! Checks the fixed-point conversions against the float ones.
+#![cfg(feature = "fixed")]
+
+use fixed::types::{I16F16, I8F8};
+use lsm6ds33::{AccelerometerScale, GyroscopeScale};
+
+#[test]
+fn fixed_conversions_match_float_ones() {
+    for &raw in &[0, 1, -1, 1000, -12345, i16::MAX, i16::MIN] {
+        let g: I16F16 = AccelerometerScale::G16.to_g_fixed(raw);
+        assert!((g.to_num::<f32>() - AccelerometerScale::G16.to_g(raw)).abs() < 1e-4);
+        let dps: I16F16 = GyroscopeScale::Dps2000.to_dps_fixed(raw);
+        assert!((dps.to_num::<f32>() - GyroscopeScale::Dps2000.to_dps(raw)).abs() < 1e-2);
+    }
+}
+
+#[test]
+fn fixed_conversions_saturate() {
+    assert_eq!(
+        GyroscopeScale::Dps2000.to_dps_fixed::<I8F8>(i16::MAX),
+        I8F8::MAX
+    );
+    assert_eq!(
+        GyroscopeScale::Dps2000.to_dps_fixed::<I8F8>(i16::MIN),
+        I8F8::MIN
+    );
+    // 16 g still fits
+    let g: I8F8 = AccelerometerScale::G16.to_g_fixed(i16::MIN);
+    assert_eq!(g, I8F8::from_num(-15.990784));
+}
@@ -0,0 +1,67 @@
+//! Checks the page-addressed writes and reads of the finite state machine.
+
+mod common;
+
+use common::{lsm6_with_id, read, write};
+use lsm6ds33::{registers, Error, FsmOutput};
+
+use registers::lsm6dso::{PAGE_ADDRESS, PAGE_RW, PAGE_SEL, PAGE_VALUE};
+
+#[test]
+fn page_writes_cross_into_the_next_page() {
+    let mut lsm6 = lsm6_with_id(
+        0x6C,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            write(PAGE_RW, 0x40),
+            write(PAGE_SEL, 0x41),
+            write(PAGE_ADDRESS, 0xFF),
+            write(PAGE_VALUE, 0xAA),
+            write(PAGE_SEL, 0x51),
+            write(PAGE_ADDRESS, 0),
+            write(PAGE_VALUE, 0xBB),
+            write(PAGE_SEL, 1),
+            write(PAGE_RW, 0),
+            write(registers::FUNC_CFG_ACCESS, 0),
+        ],
+    );
+    lsm6.write_page_memory(0x04FF, &[0xAA, 0xBB]).unwrap();
+    lsm6.release().done();
+}
+
+#[test]
+fn outputs_are_decoded_per_program() {
+    let mut outs = [0; 16];
+    outs[0] = 0b10000000;
+    outs[15] = 0b101;
+    let mut lsm6 = lsm6_with_id(
+        0x6C,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            read(registers::lsm6dso::FSM_OUTS1, &outs),
+            write(registers::FUNC_CFG_ACCESS, 0),
+        ],
+    );
+    let outputs = lsm6.read_fsm_outputs().unwrap();
+    assert_eq!(
+        outputs[0],
+        FsmOutput {
+            positive_x: true,
+            ..FsmOutput::default()
+        }
+    );
+    assert!(outputs[15].negative_z && outputs[15].negative_v);
+    assert_eq!(outputs[1], FsmOutput::default());
+    lsm6.release().done();
+}
+
+#[test]
+fn other_variants_are_refused() {
+    let mut lsm6 = common::lsm6(&[]);
+    assert!(matches!(lsm6.enable_fsm(true), Err(Error::Unsupported)));
+    assert!(matches!(
+        lsm6.load_fsm_programs(&[&[0x51, 0x00]]),
+        Err(Error::Unsupported)
+    ));
+    lsm6.release().done();
+}
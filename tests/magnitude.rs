@@ -0,0 +1,38 @@
+//! Checks the magnitude helpers at the extremes of the raw range.
+
+use lsm6ds33::Vector3;
+
+#[test]
+fn raw_magnitude_does_not_overflow() {
+    let v = Vector3 {
+        x: i16::MIN,
+        y: i16::MIN,
+        z: i16::MIN,
+    };
+    assert_eq!(v.magnitude_squared(), 3 * 32768 * 32768);
+    let v = Vector3 {
+        x: i16::MAX,
+        y: i16::MIN,
+        z: 0,
+    };
+    assert_eq!(v.magnitude_squared(), 32767 * 32767 + 32768 * 32768);
+}
+
+#[cfg(any(feature = "micromath", feature = "libm"))]
+#[test]
+fn deviation_from_1g_uses_the_scale() {
+    use lsm6ds33::AccelerometerScale;
+
+    // 1 g straight down the z axis at each scale
+    for &(scale, raw) in &[
+        (AccelerometerScale::G2, 16393),
+        (AccelerometerScale::G4, 8197),
+        (AccelerometerScale::G8, 4098),
+        (AccelerometerScale::G16, 2049),
+    ] {
+        let v = Vector3 { x: 0, y: 0, z: raw };
+        assert!(v.deviation_from_1g(scale).abs() < 0.01, "{:?}", scale);
+    }
+    // Free fall
+    assert!((Vector3::default().deviation_from_1g(AccelerometerScale::G2) + 1.).abs() < 0.01);
+}
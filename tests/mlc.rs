@@ -0,0 +1,59 @@
+//! Checks the bank switching around machine learning core configurations.
+#![cfg(feature = "mlc")]
+
+mod common;
+
+use common::{lsm6_variant, read, write};
+use lsm6ds33::{registers, AccelerometerScale, ChipVariant, Error};
+
+#[test]
+fn scripts_are_written_in_order_and_end_in_the_main_bank() {
+    // Sets the accelerometer to 26 Hz, then enables the MLC and writes a page in the embedded bank,
+    // without switching back
+    let script = [
+        (registers::CTRL1_XL, 0x20),
+        (registers::FUNC_CFG_ACCESS, 0x80),
+        (0x05, 0x10),
+        (0x17, 0x40),
+        (0x02, 0x31),
+        (0x08, 0xEA),
+        (0x09, 0x46),
+    ];
+    let mut expectations: Vec<_> = script
+        .iter()
+        .map(|&(reg, value)| write(reg, value))
+        .collect();
+    expectations.push(write(registers::FUNC_CFG_ACCESS, 0));
+    // The driver picked up the 26 Hz mode, so changing the scale keeps it
+    expectations.push(write(registers::CTRL1_XL, 0x28));
+    let mut lsm6 = lsm6_variant(ChipVariant::Lsm6dsox, &expectations);
+    lsm6.load_mlc_config(&script).unwrap();
+    lsm6.set_accel_scale(AccelerometerScale::G4).unwrap();
+    lsm6.release().done();
+}
+
+#[test]
+fn outputs_are_read_in_the_embedded_bank() {
+    let outputs = [1, 0, 4, 0, 0, 0, 0, 9];
+    let mut lsm6 = lsm6_variant(
+        ChipVariant::Lsm6dsox,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            read(registers::lsm6dso::MLC0_SRC, &outputs),
+            write(registers::FUNC_CFG_ACCESS, 0),
+        ],
+    );
+    assert_eq!(lsm6.read_mlc_outputs().unwrap(), outputs);
+    lsm6.release().done();
+}
+
+#[test]
+fn other_variants_are_refused() {
+    let mut lsm6 = common::lsm6(&[]);
+    assert!(matches!(
+        lsm6.load_mlc_config(&[(registers::CTRL1_XL, 0x20)]),
+        Err(Error::Unsupported)
+    ));
+    assert!(matches!(lsm6.read_mlc_outputs(), Err(Error::Unsupported)));
+    lsm6.release().done();
+}
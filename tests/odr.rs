@@ -0,0 +1,91 @@
+//! Checks the output data rate measurement against a simulated device with a fast oscillator.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use lsm6ds33::{
+    registers, AccelerometerMode, Error, GyroscopeMode, RegisterInterface, Sensor, LSM6,
+};
+
+/// A simulated LSM6 whose accelerometer samples every `period_us`,
+/// where every transaction takes 50 µs of the shared clock.
+struct Oscillator {
+    clock: Rc<Cell<u32>>,
+    period_us: u32,
+    last_read: u32,
+    registers: [u8; 0x80],
+}
+
+impl RegisterInterface for Oscillator {
+    type Error = ();
+
+    fn write_registers(&mut self, _address: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.clock.set(self.clock.get() + 50);
+        self.registers[bytes[0] as usize] = bytes[1];
+        Ok(())
+    }
+
+    fn read_registers(&mut self, _address: u8, start: u8, buffer: &mut [u8]) -> Result<(), ()> {
+        let now = self.clock.get() + 50;
+        self.clock.set(now);
+        buffer.copy_from_slice(&self.registers[start as usize..start as usize + buffer.len()]);
+        if start == registers::STATUS_REG {
+            let ready =
+                self.period_us > 0 && now / self.period_us > self.last_read / self.period_us;
+            buffer[0] = ready as u8;
+        } else if start == registers::OUTX_L_XL {
+            self.last_read = now;
+        }
+        Ok(())
+    }
+}
+
+fn lsm6(period_us: u32) -> (LSM6<Oscillator>, Rc<Cell<u32>>) {
+    let clock = Rc::new(Cell::new(0));
+    let mut registers = [0; 0x80];
+    registers[registers::WHO_AM_I as usize] = 0x69;
+    let bus = Oscillator {
+        clock: clock.clone(),
+        period_us,
+        last_read: 0,
+        registers,
+    };
+    (LSM6::from_interface(bus, 0x6B).unwrap(), clock)
+}
+
+#[test]
+fn measures_a_fast_oscillator() {
+    // 2% faster than 104 Hz
+    let (mut lsm6, clock) = lsm6(9427);
+    lsm6.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    let odr = lsm6.measure_accel_odr(|| clock.get(), 200).unwrap();
+    assert_eq!(odr.nominal_hz, 104.);
+    assert!((odr.deviation() - 0.02).abs() < 0.001, "{:?}", odr);
+    assert!((odr.period_s() - 0.009427).abs() < 0.00001);
+}
+
+#[test]
+fn refuses_powered_down_sensors_and_times_out() {
+    let (mut lsm6, clock) = lsm6(0);
+    assert!(matches!(
+        lsm6.measure_gyro_odr(|| clock.get(), 10),
+        Err(Error::InvalidConfig)
+    ));
+    lsm6.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    assert!(matches!(
+        lsm6.measure_accel_odr(|| clock.get(), 10),
+        Err(Error::Timeout)
+    ));
+}
+
+#[test]
+fn sample_period_follows_the_cached_mode() {
+    let (mut lsm6, _) = lsm6(0);
+    assert_eq!(lsm6.sample_period_us(Sensor::Accelerometer), 0);
+    lsm6.set_accel_mode(AccelerometerMode::LowPower13Hz)
+        .unwrap();
+    lsm6.set_gyro_mode(GyroscopeMode::HighPerformance833Hz)
+        .unwrap();
+    assert_eq!(lsm6.sample_period_us(Sensor::Accelerometer), 80_000);
+    assert_eq!(lsm6.sample_period_us(Sensor::Gyroscope), 1200);
+}
@@ -0,0 +1,186 @@
+//! Checks how probing for the LSM6 treats addresses that aren't acknowledged.
+
+use std::io::ErrorKind;
+
+use embedded_hal_mock::{
+    i2c::{Mock, Transaction},
+    MockError,
+};
+use lsm6ds33::{
+    detect, detect_with_classifier, registers, AccelerometerMode, ChipVariant, DetectedLsm6, Error,
+    LSM6,
+};
+
+/// The mock's stand-in for a NACK.
+fn is_nack(error: &MockError) -> bool {
+    *error == MockError::Io(ErrorKind::NotConnected)
+}
+
+fn who_am_i(address: u8) -> Transaction {
+    Transaction::write_read(address, vec![registers::WHO_AM_I], vec![0x69])
+}
+
+#[test]
+fn nack_moves_on_to_the_other_address() {
+    let lsm6 = LSM6::new_with_classifier(
+        Mock::new(&[
+            who_am_i(0x6B).with_error(MockError::Io(ErrorKind::NotConnected)),
+            who_am_i(0x6A),
+            Transaction::write(0x6A, vec![registers::CTRL3_C, 4]),
+        ]),
+        is_nack,
+    )
+    .unwrap();
+    assert_eq!(lsm6.address(), 0x6A);
+    lsm6.release().done();
+}
+
+#[test]
+fn other_faults_stop_the_probe() {
+    let error = MockError::Io(ErrorKind::TimedOut);
+    let result = LSM6::new_with_classifier(
+        Mock::new(&[who_am_i(0x6B).with_error(error.clone())]),
+        is_nack,
+    );
+    assert!(matches!(result, Err(Error::Bus(e)) if e == error));
+}
+
+#[test]
+fn nack_at_both_addresses_is_not_detected() {
+    let nack = MockError::Io(ErrorKind::NotConnected);
+    let result = LSM6::new_with_classifier(
+        Mock::new(&[
+            who_am_i(0x6B).with_error(nack.clone()),
+            who_am_i(0x6A).with_error(nack),
+        ]),
+        is_nack,
+    );
+    assert!(matches!(result, Err(Error::NotDetected)));
+}
+
+#[test]
+fn detect_reports_an_unknown_part() {
+    let unknown = |address| Transaction::write_read(address, vec![registers::WHO_AM_I], vec![0x6D]);
+    let mut i2c = Mock::new(&[unknown(0x6B), unknown(0x6A)]);
+    assert_eq!(
+        detect(&mut i2c),
+        Ok(Some(DetectedLsm6 {
+            address: 0x6B,
+            who_am_i: 0x6D,
+            variant: None
+        }))
+    );
+    i2c.done();
+
+    // A known part at the other address is reported instead
+    let mut i2c = Mock::new(&[unknown(0x6B), who_am_i(0x6A)]);
+    assert_eq!(
+        detect(&mut i2c),
+        Ok(Some(DetectedLsm6 {
+            address: 0x6A,
+            who_am_i: 0x69,
+            variant: Some(ChipVariant::Lsm6ds33)
+        }))
+    );
+    i2c.done();
+}
+
+#[test]
+fn detect_with_classifier_moves_on_after_a_nack() {
+    let nack = MockError::Io(ErrorKind::NotConnected);
+    let mut i2c = Mock::new(&[who_am_i(0x6B).with_error(nack.clone())]);
+    assert_eq!(detect(&mut i2c), Err(nack.clone()));
+    i2c.done();
+
+    let mut i2c = Mock::new(&[who_am_i(0x6B).with_error(nack.clone()), who_am_i(0x6A)]);
+    assert_eq!(
+        detect_with_classifier(&mut i2c, is_nack).map(|found| found.map(|found| found.address)),
+        Ok(Some(0x6A))
+    );
+    i2c.done();
+
+    let mut i2c = Mock::new(&[
+        who_am_i(0x6B).with_error(nack.clone()),
+        who_am_i(0x6A).with_error(nack),
+    ]);
+    assert_eq!(detect_with_classifier(&mut i2c, is_nack), Ok(None));
+    i2c.done();
+}
+
+#[test]
+fn probe_with_classifier_finds_the_second_address() {
+    let nack = MockError::Io(ErrorKind::NotConnected);
+    let mut i2c = Mock::new(&[who_am_i(0x6B).with_error(nack.clone())]);
+    assert_eq!(LSM6::probe(&mut i2c), Err(nack.clone()));
+    i2c.done();
+
+    let mut i2c = Mock::new(&[who_am_i(0x6B).with_error(nack), who_am_i(0x6A)]);
+    assert_eq!(
+        LSM6::probe_with_classifier(&mut i2c, is_nack),
+        Ok(Some((0x6A, ChipVariant::Lsm6ds33)))
+    );
+    i2c.done();
+}
+
+#[test]
+fn bus_fault_during_new_is_a_bus_error() {
+    let error = MockError::Io(ErrorKind::TimedOut);
+    let result = LSM6::new(Mock::new(&[who_am_i(0x6B).with_error(error.clone())]));
+    assert!(matches!(result, Err(Error::Bus(e)) if e == error));
+}
+
+#[test]
+fn wrong_id_during_new_is_reported_with_its_address() {
+    let result = LSM6::new(Mock::new(&[
+        Transaction::write_read(0x6B, vec![registers::WHO_AM_I], vec![0xFF]),
+        Transaction::write_read(0x6A, vec![registers::WHO_AM_I], vec![0xFF]),
+    ]));
+    assert!(matches!(
+        result,
+        Err(Error::WrongChipId {
+            address: 0x6B,
+            found: 0xFF
+        })
+    ));
+}
+
+#[test]
+fn check_reports_a_missing_or_reset_device() {
+    let ctrl =
+        |values: [u8; 3]| Transaction::write_read(0x6B, vec![registers::CTRL1_XL], values.to_vec());
+    let mut lsm6 = LSM6::new(Mock::new(&[
+        who_am_i(0x6B),
+        Transaction::write(0x6B, vec![registers::CTRL3_C, 4]),
+        who_am_i(0x6B),
+        ctrl([0, 0, 4]),
+        Transaction::write_read(0x6B, vec![registers::WHO_AM_I], vec![0xFF]),
+        who_am_i(0x6B),
+        ctrl([0, 0, 0x44]),
+        Transaction::write(0x6B, vec![registers::CTRL1_XL, 0x40]),
+        who_am_i(0x6B),
+        ctrl([0x40, 0, 4]),
+        // A reset with the accelerometer on, which leaves CTRL3_C as the driver set it
+        who_am_i(0x6B),
+        ctrl([0, 0, 4]),
+    ]))
+    .unwrap();
+    lsm6.check().unwrap();
+    assert!(matches!(
+        lsm6.check(),
+        Err(Error::WrongChipId {
+            address: 0x6B,
+            found: 0xFF
+        })
+    ));
+    assert!(matches!(
+        lsm6.check(),
+        Err(Error::ConfigurationLost { ctrl3_c: 0x44 })
+    ));
+    lsm6.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    lsm6.check().unwrap();
+    assert!(matches!(
+        lsm6.check(),
+        Err(Error::ConfigurationLost { ctrl3_c: 4 })
+    ));
+    lsm6.release().done();
+}
@@ -0,0 +1,148 @@
+//! Checks the accelerometer and gyroscope reads against a mocked i2c bus.
+
+mod common;
+
+use common::{lsm6, status, ADDRESS};
+use embedded_hal_mock::i2c::Transaction;
+use lsm6ds33::{registers, AccelerometerMode, AxisMapping, GyroscopeMode, Measurement, Vector3};
+
+#[test]
+fn read_accel_combines_bytes() {
+    let mut lsm6 = lsm6(&[
+        status(0b1),
+        Transaction::write_read(
+            ADDRESS,
+            vec![registers::OUTX_L_XL],
+            vec![0x34, 0x12, 0xFF, 0xFF, 0x00, 0x80],
+        ),
+    ]);
+    assert_eq!(lsm6.read_accel().unwrap(), Some((0x1234, -1, i16::MIN)));
+    lsm6.release().done();
+}
+
+#[test]
+fn read_accel_is_none_without_new_data() {
+    // Only the gyroscope has new data
+    let mut lsm6 = lsm6(&[status(0b10)]);
+    assert_eq!(lsm6.read_accel().unwrap(), None);
+    lsm6.release().done();
+}
+
+#[test]
+fn read_gyro_combines_bytes() {
+    let mut lsm6 = lsm6(&[
+        status(0b11),
+        Transaction::write_read(
+            ADDRESS,
+            vec![registers::OUTX_L_G],
+            vec![0xFF, 0x7F, 0x18, 0xFC, 0x00, 0x00],
+        ),
+    ]);
+    assert_eq!(lsm6.read_gyro().unwrap(), Some((i16::MAX, -1000, 0)));
+    lsm6.release().done();
+}
+
+#[test]
+fn read_gyro_is_none_without_new_data() {
+    let mut lsm6 = lsm6(&[status(0b1)]);
+    assert_eq!(lsm6.read_gyro().unwrap(), None);
+    lsm6.release().done();
+}
+
+#[test]
+fn read_all_synchronized_turns_on_bdu_and_reads_one_burst() {
+    let mut lsm6 = lsm6(&[
+        Transaction::write(ADDRESS, vec![registers::CTRL1_XL, 0x40]),
+        Transaction::write(ADDRESS, vec![registers::CTRL2_G, 0x40]),
+        Transaction::write(ADDRESS, vec![registers::CTRL3_C, 0x44]),
+        status(0b11),
+        Transaction::write_read(
+            ADDRESS,
+            vec![registers::OUT_TEMP_L],
+            vec![
+                0x10, 0, 1, 0, 2, 0, 3, 0, 0xFC, 0xFF, 0xFD, 0xFF, 0x00, 0x40,
+            ],
+        ),
+    ]);
+    lsm6.set_accel_mode(AccelerometerMode::Normal104Hz).unwrap();
+    lsm6.set_gyro_mode(GyroscopeMode::Normal104Hz).unwrap();
+    assert_eq!(
+        lsm6.read_all_synchronized().unwrap(),
+        Some(Measurement {
+            temp: 0x10,
+            gyro: Vector3 { x: 1, y: 2, z: 3 },
+            accel: Vector3 {
+                x: -4,
+                y: -3,
+                z: 0x4000
+            },
+        })
+    );
+    lsm6.release().done();
+}
+
+#[test]
+fn mounting_orientation_remaps_readings() {
+    let mut lsm6 = lsm6(&[
+        status(0b1),
+        Transaction::write_read(
+            ADDRESS,
+            vec![registers::OUTX_L_XL],
+            vec![0x01, 0x00, 0x02, 0x00, 0x00, 0x80],
+        ),
+    ]);
+    lsm6.set_mounting_orientation(AxisMapping::Z_90);
+    // x is the sensor's -y, y its x, and -32768 can't be negated
+    assert_eq!(lsm6.read_accel().unwrap(), Some((-2, 1, i16::MIN)));
+    lsm6.release().done();
+}
+
+#[test]
+fn axis_mappings_must_be_rotations() {
+    use lsm6ds33::{Axis, AxisMappingError};
+
+    assert_eq!(
+        AxisMapping::new(Axis::NegY, Axis::PosX, Axis::PosZ),
+        Ok(AxisMapping::Z_90)
+    );
+    assert_eq!(
+        AxisMapping::new(Axis::PosX, Axis::NegX, Axis::PosZ),
+        Err(AxisMappingError::RepeatedAxis)
+    );
+    // Swapping two axes without reversing one mirrors the frame
+    assert_eq!(
+        AxisMapping::new(Axis::PosY, Axis::PosX, Axis::PosZ),
+        Err(AxisMappingError::Reflection)
+    );
+    assert_eq!(
+        AxisMapping::new(Axis::PosX, Axis::PosY, Axis::NegZ),
+        Err(AxisMappingError::Reflection)
+    );
+    // A cyclic relabelling is a rotation
+    assert!(AxisMapping::new(Axis::PosY, Axis::PosZ, Axis::PosX).is_ok());
+}
+
+#[test]
+fn orientation_follows_the_axis_with_gravity() {
+    use lsm6ds33::Orientation;
+
+    let g = |x, y, z| Vector3 { x, y, z };
+    assert_eq!(
+        Orientation::from_accel(g(0.1, 0., 0.98), 0.8),
+        Some(Orientation::FaceUp)
+    );
+    assert_eq!(
+        Orientation::from_accel(g(0., -0.9, 0.3), 0.8),
+        Some(Orientation::PortraitDown)
+    );
+    assert_eq!(
+        Orientation::from_accel(g(1., 0., 0.), 0.8),
+        Some(Orientation::LandscapeUp)
+    );
+    // Halfway between faces
+    assert_eq!(Orientation::from_accel(g(0.7, 0., -0.7), 0.8), None);
+    assert_eq!(
+        Orientation::from_accel(g(0.7, 0., -0.71), 0.5),
+        Some(Orientation::FaceDown)
+    );
+}
@@ -0,0 +1,51 @@
+//! Checks that failed transactions are retried with `LSM6::set_retry`, and nothing else is.
+
+mod common;
+
+use std::io::ErrorKind;
+
+use common::{lsm6, status, ADDRESS};
+use embedded_hal_mock::i2c::Transaction;
+use embedded_hal_mock::MockError;
+use lsm6ds33::{registers, Error};
+
+fn nack() -> MockError {
+    MockError::Io(ErrorKind::NotConnected)
+}
+
+#[test]
+fn failed_reads_are_retried() {
+    let mut lsm6 = lsm6(&[status(0).with_error(nack()), status(0)]);
+    lsm6.set_retry(2);
+    // No new data is a result, not an error, so it isn't retried
+    assert_eq!(lsm6.read_accel().unwrap(), None);
+    assert_eq!(lsm6.retry_count(), 1);
+    lsm6.release().done();
+}
+
+#[test]
+fn errors_are_returned_once_retries_run_out() {
+    let mut lsm6 = lsm6(&[status(0).with_error(nack()), status(0).with_error(nack())]);
+    lsm6.set_retry(1);
+    assert!(matches!(lsm6.read_accel(), Err(Error::Bus(e)) if e == nack()));
+    assert_eq!(lsm6.retry_count(), 1);
+    lsm6.release().done();
+}
+
+#[test]
+fn nothing_is_retried_by_default() {
+    let mut lsm6 = lsm6(&[status(0).with_error(nack())]);
+    assert!(matches!(lsm6.read_accel(), Err(Error::Bus(_))));
+    assert_eq!(lsm6.retry_count(), 0);
+    lsm6.release().done();
+}
+
+#[test]
+fn reads_that_clear_interrupt_sources_are_not_retried() {
+    let sources = Transaction::write_read(ADDRESS, vec![registers::WAKE_UP_SRC], vec![0; 3]);
+    let mut lsm6 = lsm6(&[sources.with_error(nack())]);
+    lsm6.set_retry(2);
+    assert!(matches!(lsm6.clear_interrupts(), Err(Error::Bus(_))));
+    assert_eq!(lsm6.retry_count(), 0);
+    lsm6.release().done();
+}
@@ -0,0 +1,180 @@
+//! Checks the sequencing of the sensor hub's configuration and reads.
+
+mod common;
+
+use common::{lsm6_with_id, read, write, ADDRESS};
+use embedded_hal::blocking::i2c::Write;
+use embedded_hal_mock::{delay::MockNoop, i2c::Transaction, MockError};
+use lsm6ds33::{registers, Error, FifoWord, SensorHubConfig, SensorHubSlave, SlaveSlot};
+use std::io::ErrorKind;
+
+#[test]
+fn slave_is_configured_in_the_bank_before_the_master_starts() {
+    use registers::lsm6dsl::{MASTER_CONFIG, SENSORHUB1_REG, SLV0_ADD};
+    let mut lsm6 = lsm6_with_id(
+        0x6A,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            // A LIS3MDL at 0x1C, read from OUT_X_L with its auto-increment bit set
+            Transaction::write(ADDRESS, vec![SLV0_ADD, 0x39, 0xA8, 6]),
+            write(registers::FUNC_CFG_ACCESS, 0),
+            read(registers::CTRL10_C, &[0]),
+            write(registers::CTRL10_C, 0b100),
+            read(MASTER_CONFIG, &[0]),
+            write(MASTER_CONFIG, 0b1001),
+            read(SENSORHUB1_REG, &[1, 0, 2, 0, 0xFD, 0xFF]),
+            // Only MASTER_ON is cleared, leaving the pull-ups and FUNC_EN
+            read(MASTER_CONFIG, &[0b1001]),
+            write(MASTER_CONFIG, 0b1000),
+        ],
+    );
+    lsm6.configure_sensor_hub_slave0(SensorHubSlave {
+        address: 0x1C,
+        register: 0xA8,
+        len: 6,
+    })
+    .unwrap();
+    lsm6.enable_sensor_hub(SensorHubConfig {
+        pull_ups: true,
+        ..SensorHubConfig::default()
+    })
+    .unwrap();
+    assert_eq!(lsm6.read_sensor_hub_xyz().unwrap(), (1, 2, -3));
+    lsm6.disable_sensor_hub().unwrap();
+    lsm6.release().done();
+}
+
+#[test]
+fn reads_follow_each_variants_register_layout() {
+    let bytes: Vec<u8> = (1..=14).collect();
+    let mut lsm6 = lsm6_with_id(
+        0x6A,
+        &[
+            read(registers::lsm6dsl::SENSORHUB1_REG, &bytes[..12]),
+            read(registers::lsm6dsl::SENSORHUB13_REG, &bytes[12..]),
+        ],
+    );
+    let mut out = [0; 14];
+    lsm6.read_sensor_hub(&mut out).unwrap();
+    assert_eq!(out[..], bytes[..]);
+    lsm6.release().done();
+
+    let mut lsm6 = lsm6_with_id(
+        0x6C,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x40),
+            read(registers::lsm6dso::SENSOR_HUB_1, &bytes),
+            write(registers::FUNC_CFG_ACCESS, 0),
+        ],
+    );
+    lsm6.read_sensor_hub(&mut out).unwrap();
+    assert_eq!(out[..], bytes[..]);
+    lsm6.release().done();
+}
+
+#[test]
+fn passthrough_stops_the_master_and_restores_it_after_an_error() {
+    use registers::lsm6dso::MASTER_CONFIG;
+    let mut lsm6 = lsm6_with_id(
+        0x6C,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x40),
+            read(MASTER_CONFIG, &[0b1100]),
+            write(registers::FUNC_CFG_ACCESS, 0),
+            // START_CONFIG first, then MASTER_ON off and PASS_THROUGH_MODE on
+            write(registers::FUNC_CFG_ACCESS, 0x40),
+            write(MASTER_CONFIG, 0b101100),
+            write(registers::FUNC_CFG_ACCESS, 0),
+            write(registers::FUNC_CFG_ACCESS, 0x40),
+            write(MASTER_CONFIG, 0b111000),
+            write(registers::FUNC_CFG_ACCESS, 0),
+            // The magnetometer's CTRL_REG3, which fails
+            Transaction::write(0x1C, vec![0x22, 0]).with_error(MockError::Io(ErrorKind::Other)),
+            write(registers::FUNC_CFG_ACCESS, 0x40),
+            write(MASTER_CONFIG, 0b1100),
+            write(registers::FUNC_CFG_ACCESS, 0),
+        ],
+    );
+    let result = lsm6.with_passthrough(&mut MockNoop::new(), |bus| bus.write(0x1C, &[0x22, 0]));
+    assert!(matches!(result, Err(Error::Bus(_))));
+    lsm6.release().done();
+}
+
+#[test]
+fn external_sensor_window_follows_the_earlier_slots() {
+    use registers::lsm6dsl::{SENSORHUB13_REG, SENSORHUB1_REG, SLV0_ADD};
+    let mut lsm6 = lsm6_with_id(
+        0x6A,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            // Slave 0 reads six bytes and slave 1 seven
+            read(SLV0_ADD, &[0x39, 0xA8, 6, 0x3D, 0x28, 7]),
+            write(registers::FUNC_CFG_ACCESS, 0),
+            // So slave 1's bytes run from SENSORHUB7_REG across the gap to SENSORHUB13_REG
+            read(SENSORHUB1_REG + 6, &[1, 2, 3, 4, 5, 6]),
+            read(SENSORHUB13_REG, &[7]),
+            read(registers::FUNC_SRC, &[1]),
+        ],
+    );
+    let mut out = [0; 8];
+    assert_eq!(
+        lsm6.read_external_sensor(SlaveSlot::Slave1, &mut out)
+            .unwrap(),
+        7
+    );
+    assert_eq!(out, [1, 2, 3, 4, 5, 6, 7, 0]);
+    assert!(
+        lsm6.read_embedded_function_status()
+            .unwrap()
+            .sensor_hub_end_op
+    );
+    lsm6.release().done();
+}
+
+#[test]
+fn batched_external_sensor_data_is_tagged_with_its_slot() {
+    assert_eq!(
+        FifoWord::decode(&[0x0F << 3, 1, 2, 3, 4, 5, 6]),
+        FifoWord::ExternalSensor {
+            slot: SlaveSlot::Slave1,
+            data: [1, 2, 3, 4, 5, 6],
+        }
+    );
+    assert!(matches!(
+        FifoWord::decode(&[0x19 << 3, 0, 0, 0, 0, 0, 0]),
+        FifoWord::Other { tag: 0x19, .. }
+    ));
+}
+
+#[test]
+fn iron_correction_is_written_in_the_bank_and_enabled() {
+    use registers::lsm6dsl::{MAG_OFFX_L, MAG_SI_XX, MASTER_CONFIG};
+    let mut lsm6 = lsm6_with_id(
+        0x6A,
+        &[
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            Transaction::write(ADDRESS, vec![MAG_OFFX_L, 0x10, 0, 0xF0, 0xFF, 0, 1]),
+            write(registers::FUNC_CFG_ACCESS, 0),
+            read(MASTER_CONFIG, &[1]),
+            write(MASTER_CONFIG, 0b11),
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            // The identity matrix with a negative coefficient, in sign-magnitude eighths
+            Transaction::write(ADDRESS, vec![MAG_SI_XX, 8, 0, 0, 0, 0x88, 0, 0, 0, 8]),
+            write(registers::FUNC_CFG_ACCESS, 0),
+            read(registers::CTRL9_XL, &[0x38]),
+            write(registers::CTRL9_XL, 0x3C),
+            write(registers::FUNC_CFG_ACCESS, 0x80),
+            read(MAG_SI_XX, &[8, 0, 0, 0, 0x88, 0, 0, 0, 8]),
+            write(registers::FUNC_CFG_ACCESS, 0),
+        ],
+    );
+    lsm6.set_hard_iron_offset((16, -16, 256)).unwrap();
+    let matrix = [[8, 0, 0], [0, -8, 0], [0, 0, 8]];
+    lsm6.set_soft_iron_matrix(matrix).unwrap();
+    assert!(matches!(
+        lsm6.set_soft_iron_matrix([[-128, 0, 0], [0, 8, 0], [0, 0, 8]]),
+        Err(Error::InvalidConfig)
+    ));
+    assert_eq!(lsm6.soft_iron_matrix().unwrap(), matrix);
+    lsm6.release().done();
+}
@@ -0,0 +1,43 @@
+//! Guards the serialized format of the configuration types.
+//! Run with `--features serde`.
+
+#![cfg(feature = "serde")]
+
+use lsm6ds33::{AccelerometerMode, AccelerometerScale, Config, ConfigBuilder, InterruptPin, LSM6};
+
+#[test]
+fn builder_uses_field_and_variant_names() {
+    let builder = LSM6::configure()
+        .accel(AccelerometerMode::Normal104Hz, AccelerometerScale::G4)
+        .bdu(true)
+        .drdy_on(InterruptPin::Int1);
+    let json = concat!(
+        r#"{"accel":["Normal104Hz","G4"],"gyro":null,"bdu":true,"big_endian":null,"#,
+        r#""accel_axes":null,"gyro_axes":null,"fifo":null,"drdy":"Int1"}"#
+    );
+    assert_eq!(serde_json::to_string(&builder).unwrap(), json);
+    assert_eq!(
+        serde_json::from_str::<ConfigBuilder>(json).unwrap(),
+        builder
+    );
+}
+
+#[test]
+fn missing_fields_are_unset() {
+    let builder: ConfigBuilder = serde_json::from_str(r#"{"bdu":false}"#).unwrap();
+    assert_eq!(builder, ConfigBuilder::new().bdu(false));
+}
+
+#[test]
+fn config_round_trips() {
+    let config = Config {
+        ctrl1_xl: 0x48,
+        ctrl3_c: 0x44,
+        fifo_ctrl5: 0x26,
+        md1_cfg: 0b100000,
+        ..Config::default()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains(r#""ctrl1_xl":72"#));
+    assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+}
@@ -0,0 +1,116 @@
+//! Checks the framing of register reads and writes over SPI.
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use embedded_hal::{blocking::spi, digital::v2::OutputPin};
+use embedded_hal_mock::{
+    pin::{Mock as PinMock, State, Transaction as PinTransaction},
+    spi::{Mock as SpiMock, Transaction as SpiTransaction},
+};
+use lsm6ds33::{registers, LSM6};
+
+/// The chip select transactions of `transactions` register accesses, each one low then high.
+fn selects(transactions: usize) -> Vec<PinTransaction> {
+    (0..transactions)
+        .flat_map(|_| {
+            vec![
+                PinTransaction::set(State::Low),
+                PinTransaction::set(State::High),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn reads_set_the_top_address_bit_and_clock_the_data_out() {
+    let spi = SpiMock::new(&[
+        SpiTransaction::write(vec![registers::WHO_AM_I | 0x80]),
+        SpiTransaction::transfer(vec![0], vec![0x69]),
+        SpiTransaction::write(vec![registers::CTRL3_C, 4]),
+        SpiTransaction::write(vec![registers::STATUS_REG | 0x80]),
+        SpiTransaction::transfer(vec![0], vec![0b10]),
+        // A burst of the gyroscope outputs, with zeros sent while they are clocked out
+        SpiTransaction::write(vec![registers::OUTX_L_G | 0x80]),
+        SpiTransaction::transfer(vec![0; 6], vec![1, 0, 2, 0, 3, 0]),
+    ]);
+    let cs = PinMock::new(&selects(4));
+    let mut lsm6 = LSM6::new_spi(spi, cs).unwrap();
+    assert_eq!(lsm6.read_gyro().unwrap(), Some((1, 2, 3)));
+    let (mut spi, mut cs) = lsm6.release().release();
+    spi.done();
+    cs.done();
+}
+
+#[test]
+fn writes_leave_the_top_address_bit_clear() {
+    let spi = SpiMock::new(&[
+        SpiTransaction::write(vec![registers::WHO_AM_I | 0x80]),
+        SpiTransaction::transfer(vec![0], vec![0x69]),
+        SpiTransaction::write(vec![registers::CTRL3_C, 4]),
+        SpiTransaction::write(vec![registers::INT1_CTRL, 0b11]),
+    ]);
+    let cs = PinMock::new(&selects(3));
+    let mut lsm6 = LSM6::new_spi(spi, cs).unwrap();
+    lsm6.set_register_raw(registers::INT1_CTRL, 0b11).unwrap();
+    let (mut spi, mut cs) = lsm6.release().release();
+    spi.done();
+    cs.done();
+}
+
+/// A bus and chip select pin that record, in order, everything done to them.
+#[derive(Clone, Default)]
+struct Log(Rc<RefCell<Vec<String>>>);
+
+impl spi::Write<u8> for Log {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        self.0.borrow_mut().push(format!("write {:02X?}", words));
+        Ok(())
+    }
+}
+
+impl spi::Transfer<u8> for Log {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+        self.0.borrow_mut().push(format!("transfer {:02X?}", words));
+        // WHO_AM_I, for the constructor
+        words[0] = 0x69;
+        Ok(words)
+    }
+}
+
+impl OutputPin for Log {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().push("cs low".into());
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().push("cs high".into());
+        Ok(())
+    }
+}
+
+#[test]
+fn chip_select_frames_each_transaction() {
+    let log = Log::default();
+    LSM6::new_spi(log.clone(), log.clone()).unwrap();
+    assert_eq!(
+        *log.0.borrow(),
+        [
+            "cs low",
+            "write [8F]",
+            "transfer [00]",
+            "cs high",
+            "cs low",
+            "write [12, 04]",
+            "cs high",
+        ]
+    );
+}
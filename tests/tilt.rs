@@ -0,0 +1,139 @@
+//! Checks the tilt angles of known orientations.
+#![cfg(any(feature = "micromath", feature = "libm"))]
+
+use lsm6ds33::{tilt_angles, AccelerometerScale, OrientationFilter, Vector3};
+
+/// The raw reading of `g` at `scale`, as the sensor would report it.
+fn raw(scale: AccelerometerScale, g: (f32, f32, f32)) -> Vector3<f32> {
+    let count = |g: f32| {
+        let raw = (g * 1_000_000. / scale.micro_g_per_lsb() as f32).round() as i16;
+        scale.to_g(raw)
+    };
+    Vector3 {
+        x: count(g.0),
+        y: count(g.1),
+        z: count(g.2),
+    }
+}
+
+/// How far off the angles can be, in degrees. `micromath`'s approximations are much coarser.
+const TOLERANCE: f32 = if cfg!(feature = "libm") { 0.05 } else { 2. };
+
+#[test]
+fn known_orientations_at_every_scale() {
+    let half = core::f32::consts::FRAC_1_SQRT_2;
+    // (gravity in g, pitch, roll)
+    let table = [
+        ((0., 0., 1.), 0., 0.),
+        ((0., half, half), 0., 45.),
+        ((0., 1., 0.), 0., 90.),
+        ((0., 0., -1.), 0., 180.),
+        ((-half, 0., half), 45., 0.),
+        ((half, 0., half), -45., 0.),
+        ((-1., 0., 0.), 90., 0.),
+        ((1., 0., 0.), -90., 0.),
+    ];
+    for &scale in &[
+        AccelerometerScale::G2,
+        AccelerometerScale::G4,
+        AccelerometerScale::G8,
+        AccelerometerScale::G16,
+    ] {
+        for &(g, pitch, roll) in &table {
+            let angles = tilt_angles(raw(scale, g));
+            assert!(
+                (angles.pitch - pitch).abs() < TOLERANCE && (angles.roll - roll).abs() < TOLERANCE,
+                "{:?} at {:?} gave {:?}",
+                g,
+                scale,
+                angles
+            );
+        }
+    }
+}
+
+#[test]
+fn degenerate_readings_are_finite() {
+    let angles = tilt_angles(Vector3::default());
+    assert_eq!((angles.pitch, angles.roll), (0., 0.));
+    let angles = tilt_angles(Vector3 {
+        x: 1.,
+        y: 0.,
+        z: 0.,
+    });
+    assert_eq!(angles.roll, 0.);
+    assert!(angles.pitch.is_finite());
+}
+
+/// Gravity as the accelerometer sees it at `roll` degrees about x.
+fn gravity_at_roll(roll: f32) -> Vector3<f32> {
+    let roll = roll.to_radians();
+    Vector3 {
+        x: 0.,
+        y: roll.sin(),
+        z: roll.cos(),
+    }
+}
+
+#[test]
+fn filter_starts_from_the_accelerometer() {
+    let mut filter = OrientationFilter::new(1.);
+    let angles = filter.update(Vector3::default(), gravity_at_roll(30.), 0.01);
+    assert!((angles.roll - 30.).abs() < TOLERANCE);
+    assert_eq!(filter.state().angles, Some((angles.pitch, angles.roll)));
+}
+
+#[test]
+fn filter_tracks_a_biased_rotation() {
+    let dt = 0.01;
+    let rate = 20.;
+    let bias = 1.;
+    let mut filter = OrientationFilter::new(0.5);
+    let mut roll = 0.;
+    let mut worst: f32 = 0.;
+    for i in 0..1000 {
+        // Rotate to 80 degrees, then hold
+        if roll < 80. {
+            roll += rate * dt;
+        }
+        let gyro = Vector3 {
+            x: if roll < 80. { rate } else { 0. } + bias,
+            y: 0.,
+            z: 0.,
+        };
+        let angles = filter.update(gyro, gravity_at_roll(roll), dt);
+        if i > 500 {
+            worst = worst.max((angles.roll - roll).abs());
+        }
+    }
+    // A constant bias leaves an error of about bias × time constant
+    assert!(worst < 0.5 * bias + TOLERANCE, "{}", worst);
+}
+
+#[test]
+fn filter_ignores_the_accelerometer_during_impacts() {
+    let mut filter = OrientationFilter::new(0.5);
+    filter.update(Vector3::default(), gravity_at_roll(0.), 0.01);
+    let impact = Vector3 {
+        x: 0.,
+        y: 3.,
+        z: 1.,
+    };
+    let angles = filter.update(Vector3::default(), impact, 0.01);
+    assert_eq!(angles.roll, 0.);
+}
+
+#[test]
+fn filter_uses_timestamp_deltas_and_restores_state() {
+    let mut filter = OrientationFilter::new(1000.);
+    let gyro = Vector3 {
+        x: 10.,
+        y: 0.,
+        z: 0.,
+    };
+    filter.update_with_timestamp(gyro, gravity_at_roll(0.), u32::MAX - 19_999);
+    // 40000 ticks of 25 µs, across the counter wrapping, is 1 s
+    let mut restored = OrientationFilter::from_state(1000., filter.state());
+    let angles = restored.update_with_timestamp(gyro, gravity_at_roll(0.), 20_000);
+    assert!((angles.roll - 10.).abs() < 0.1, "{:?}", angles);
+}